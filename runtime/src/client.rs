@@ -0,0 +1,161 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpStream, ToSocketAddrs, UnixStream};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::frame::{read_frame, write_frame};
+use crate::stream::{feed, FrameFeed, STREAM_BUFFER};
+use crate::{FrameSink, FrameStream, Transport};
+
+
+/** Reads the root table `id` out of a response frame, generated clients provide it as `frame_id` */
+pub type FrameId = fn(&[u8]) -> Option<String>;
+
+enum Waiting {
+  Response(oneshot::Sender<Vec<u8>>),
+  Stream(FrameFeed),
+  /** A stream that fell behind, the rest of its frames is dropped until it's dropped too */
+  CutOff,
+}
+
+/** Requests waiting for responses, `None` once the connection is closed */
+type Pending = Arc<Mutex<Option<HashMap<String, Waiting>>>>;
+
+/** Client side of a length-prefixed stream, any number of requests may be in flight at once */
+pub struct StreamTransport {
+  outgoing: mpsc::Sender<Vec<u8>>,
+  pending: Pending,
+}
+
+impl StreamTransport {
+  /** Spawns the tasks reading responses and writing requests, so it has to be called within a tokio runtime */
+  pub fn new<IO>(stream: IO, frame_id: FrameId) -> Self
+  where
+    IO: AsyncRead + AsyncWrite + Send + 'static,
+  {
+    let (mut reader, mut writer) = tokio::io::split(stream);
+    let pending: Pending = Arc::new(Mutex::new(Some(HashMap::new())));
+    let (outgoing, mut requests) = mpsc::channel::<Vec<u8>>(STREAM_BUFFER);
+
+    let read_pending = pending.clone();
+    tokio::spawn(async move {
+      while let Ok(Some(frame)) = read_frame(&mut reader).await {
+        let id = match frame_id(&frame) {
+          Some(id) => id,
+          None => continue,
+        };
+
+        // responses nobody waits for anymore are dropped, a stream stays registered until it's dropped
+        let mut pending = read_pending.lock().unwrap();
+        let pending = match pending.as_mut() {
+          Some(pending) => pending,
+          None => break,
+        };
+        match pending.remove(&id) {
+          Some(Waiting::Response(respond)) => {
+            let _ = respond.send(frame);
+          }
+          Some(Waiting::Stream(frames)) => {
+            // a slow stream is cut off instead of holding up the other calls on the connection
+            let waiting = if frames.push(frame) { Waiting::Stream(frames) } else { Waiting::CutOff };
+            pending.insert(id, waiting);
+          }
+          Some(Waiting::CutOff) => {
+            pending.insert(id, Waiting::CutOff);
+          }
+          None => {}
+        }
+      }
+
+      // dropping the senders fails every request still in flight
+      read_pending.lock().unwrap().take();
+    });
+
+    let write_pending = pending.clone();
+    tokio::spawn(async move {
+      while let Some(frame) = requests.recv().await {
+        if write_frame(&mut writer, &frame).await.is_err() {
+          break;
+        }
+      }
+      write_pending.lock().unwrap().take();
+    });
+
+    StreamTransport { outgoing, pending }
+  }
+}
+
+impl StreamTransport {
+  fn register(&self, id: &str, waiting: Waiting) -> io::Result<()> {
+    match self.pending.lock().unwrap().as_mut().ok_or_else(|| connection_closed(id))?.entry(id.to_owned()) {
+      Entry::Occupied(_) => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("request {id} is already in flight"))),
+      Entry::Vacant(entry) => {
+        entry.insert(waiting);
+        Ok(())
+      }
+    }
+  }
+
+  fn forget(pending: &Pending, id: &str) {
+    if let Some(pending) = pending.lock().unwrap().as_mut() {
+      pending.remove(id);
+    }
+  }
+
+  /** Waits while the writer is behind */
+  async fn write(&self, msg: Vec<u8>, id: &str) -> io::Result<()> {
+    if self.outgoing.send(msg).await.is_err() {
+      StreamTransport::forget(&self.pending, id);
+      return Err(connection_closed(id));
+    }
+    Ok(())
+  }
+
+  async fn open(&self, msg: Vec<u8>, id: String) -> io::Result<FrameStream> {
+    let (frames, received) = feed();
+    self.register(&id, Waiting::Stream(frames))?;
+    self.write(msg, &id).await?;
+
+    let pending = self.pending.clone();
+    Ok(received.with_cleanup(move || StreamTransport::forget(&pending, &id)))
+  }
+}
+
+impl Transport for StreamTransport {
+  async fn send_message(&self, msg: Vec<u8>, id: String) -> io::Result<Vec<u8>> {
+    let (respond, response) = oneshot::channel();
+    self.register(&id, Waiting::Response(respond))?;
+    self.write(msg, &id).await?;
+
+    response.await.map_err(|_| connection_closed(&id))
+  }
+
+  async fn open_stream(&self, msg: Vec<u8>, id: String) -> io::Result<FrameStream> {
+    self.open(msg, id).await
+  }
+
+  async fn open_call(&self, msg: Vec<u8>, id: String) -> io::Result<(FrameSink, FrameStream)> {
+    let responses = self.open(msg, id).await?;
+    Ok((FrameSink::from(self.outgoing.clone()), responses))
+  }
+}
+
+fn connection_closed(id: &str) -> io::Error {
+  io::Error::new(io::ErrorKind::ConnectionAborted, format!("connection closed before request {id} was answered"))
+}
+
+
+pub async fn connect_tcp(addr: impl ToSocketAddrs, frame_id: FrameId) -> io::Result<StreamTransport> {
+  let stream = TcpStream::connect(addr).await?;
+  stream.set_nodelay(true)?;
+  Ok(StreamTransport::new(stream, frame_id))
+}
+
+pub async fn connect_unix(path: impl AsRef<Path>, frame_id: FrameId) -> io::Result<StreamTransport> {
+  let stream = UnixStream::connect(path).await?;
+  Ok(StreamTransport::new(stream, frame_id))
+}
@@ -0,0 +1,33 @@
+use std::io;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+
+/** Frames above this length are rejected before allocating, so a broken peer can't exhaust memory */
+pub const MAX_FRAME_LENGTH: usize = 16 * 1024 * 1024;
+
+/** Reads a frame prefixed with its big endian u32 length, `None` on a clean end of stream */
+pub async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+  let length = match reader.read_u32().await {
+    Ok(length) => length as usize,
+    Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+    Err(e) => return Err(e),
+  };
+
+  if length > MAX_FRAME_LENGTH {
+    return Err(io::Error::new(io::ErrorKind::InvalidData, format!("frame of {length} bytes exceeds {MAX_FRAME_LENGTH} bytes")));
+  }
+
+  let mut frame = vec![0; length];
+  reader.read_exact(&mut frame).await?;
+  Ok(Some(frame))
+}
+
+pub async fn write_frame<W: AsyncWrite + Unpin>(writer: &mut W, frame: &[u8]) -> io::Result<()> {
+  if frame.len() > MAX_FRAME_LENGTH {
+    return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("frame of {} bytes exceeds {MAX_FRAME_LENGTH} bytes", frame.len())));
+  }
+
+  writer.write_u32(frame.len() as u32).await?;
+  writer.write_all(frame).await?;
+  writer.flush().await
+}
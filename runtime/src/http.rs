@@ -0,0 +1,109 @@
+use std::convert::Infallible;
+use std::io;
+use std::sync::Arc;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Body, Bytes};
+use hyper::header::{ALLOW, CONTENT_TYPE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+use crate::frame::MAX_FRAME_LENGTH;
+use crate::server::{accept, set_nodelay};
+use crate::stream::STREAM_BUFFER;
+use crate::{FrameStream, MethodMatch, Route, Service};
+
+
+pub const FLATBUFFERS_CONTENT_TYPE: &str = "application/x-flatbuffers";
+
+/** Accepts connections until the task is dropped, each one is served on its own task.
+A failed accept is logged and skipped like by `serve_tcp` */
+pub async fn serve_http<S: Service>(listener: TcpListener, service: Arc<S>) -> io::Result<()> {
+  loop {
+    let (stream, _) = accept(|| listener.accept()).await;
+    set_nodelay(&stream);
+
+    let service = service.clone();
+    tokio::spawn(async move {
+      let handler = service_fn(move |request| {
+        let service = service.clone();
+        async move { Ok::<_, Infallible>(handle_http(&*service, request).await) }
+      });
+      http1::Builder::new().serve_connection(TokioIo::new(stream), handler).await
+    });
+  }
+}
+
+/** Answers a POST of one request frame with its response frame, mountable in any hyper based router.
+The path ends with `/Service/Method` like the ones generated clients post to, any prefix before it is left to the router.
+A method the service doesn't have is answered with 404, one the payload doesn't call with 400.
+Methods streaming their requests or responses are refused, streams need a socket.
+A service not telling its response streams apart gets only their first frame answered */
+pub async fn handle_http<S, B>(service: &S, request: Request<B>) -> Response<Full<Bytes>>
+where
+  S: Service,
+  B: Body,
+  B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+  if request.method() != Method::POST {
+    let mut response = plain_response(StatusCode::METHOD_NOT_ALLOWED, "only POST is allowed".to_owned());
+    response.headers_mut().insert(ALLOW, Method::POST.as_str().parse().unwrap());
+    return response;
+  }
+
+  let rpc_method = request.uri().path().rsplitn(3, '/').take(2).collect::<Vec<&str>>();
+  let rpc_method = match rpc_method.as_slice() {
+    [method, service] if !method.is_empty() && !service.is_empty() => format!("{service}/{method}"),
+    _ => return plain_response(StatusCode::NOT_FOUND, "path must name the call as /Service/Method".to_owned()),
+  };
+
+  let content_type = request.headers().get(CONTENT_TYPE).and_then(|value| value.to_str().ok());
+  let media_type = content_type.and_then(|content_type| content_type.split(';').next()).map(str::trim);
+  if media_type != Some(FLATBUFFERS_CONTENT_TYPE) {
+    return plain_response(StatusCode::UNSUPPORTED_MEDIA_TYPE, format!("content-type must be {FLATBUFFERS_CONTENT_TYPE}"));
+  }
+
+  let frame = match Limited::new(request.into_body(), MAX_FRAME_LENGTH).collect().await {
+    Ok(body) => body.to_bytes().to_vec(),
+    Err(e) if e.is::<http_body_util::LengthLimitError>() => {
+      return plain_response(StatusCode::PAYLOAD_TOO_LARGE, format!("frame exceeds {MAX_FRAME_LENGTH} bytes"));
+    }
+    Err(e) => return plain_response(StatusCode::BAD_REQUEST, format!("can't read request body: {e}")),
+  };
+
+  match service.match_method(&frame, &rpc_method) {
+    MethodMatch::Called => {}
+    MethodMatch::Other => return plain_response(StatusCode::BAD_REQUEST, format!("payload doesn't call {rpc_method}")),
+    MethodMatch::Unknown => return plain_response(StatusCode::NOT_FOUND, format!("there is no method {rpc_method}")),
+  }
+  if service.route(&frame) != Route::Call {
+    return plain_response(StatusCode::BAD_REQUEST, "request streams need a socket transport".to_owned());
+  }
+  if service.streams_responses(&frame) {
+    return plain_response(StatusCode::NOT_IMPLEMENTED, "response streams need a socket transport".to_owned());
+  }
+
+  let (responses, mut frames) = mpsc::channel(STREAM_BUFFER);
+  let call = service.call(frame, FrameStream::empty(), responses.into());
+  let first_frame = tokio::select! {
+    first_frame = frames.recv() => first_frame,
+    _ = call => frames.try_recv().ok(),
+  };
+  let first_frame = match first_frame {
+    Some(first_frame) => first_frame,
+    None => return plain_response(StatusCode::INTERNAL_SERVER_ERROR, "service didn't answer".to_owned()),
+  };
+
+  let mut response = Response::new(Full::new(Bytes::from(first_frame)));
+  response.headers_mut().insert(CONTENT_TYPE, FLATBUFFERS_CONTENT_TYPE.parse().unwrap());
+  response
+}
+
+fn plain_response(status: StatusCode, message: String) -> Response<Full<Bytes>> {
+  let mut response = Response::new(Full::new(Bytes::from(message)));
+  *response.status_mut() = status;
+  response
+}
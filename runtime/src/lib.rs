@@ -0,0 +1,70 @@
+/*! Runtime for code generated by flat-rpc: the traits generated clients and servers plug into,
+length-prefixed TCP and Unix socket transports on tokio and, behind the `websocket` and `http` features, WebSocket and HTTP servers */
+
+mod frame;
+mod stream;
+mod server;
+mod client;
+#[cfg(feature = "websocket")]
+mod websocket;
+#[cfg(feature = "http")]
+mod http;
+
+use std::future::Future;
+use std::io;
+
+pub use futures_core::Stream;
+pub use frame::{read_frame, write_frame, MAX_FRAME_LENGTH};
+pub use stream::{FrameSink, FrameStream, STREAM_BUFFER};
+pub use server::{serve_connection, serve_tcp, serve_unix, MethodMatch, Route};
+pub use client::{connect_tcp, connect_unix, FrameId, StreamTransport};
+#[cfg(feature = "websocket")]
+pub use websocket::{serve_websocket, serve_websocket_connection};
+#[cfg(feature = "http")]
+pub use http::{handle_http, serve_http, FLATBUFFERS_CONTENT_TYPE};
+
+
+/** Sends a request frame and resolves with the response frame carrying the same root table `id` */
+pub trait Transport {
+  fn send_message(&self, msg: Vec<u8>, id: String) -> impl Future<Output = io::Result<Vec<u8>>> + Send;
+
+  /** Sends a request frame of a streaming method, every frame carrying the same root table `id` is yielded
+  until the stream is dropped, generated clients stop at the end of stream marker */
+  fn open_stream(&self, msg: Vec<u8>, id: String) -> impl Future<Output = io::Result<FrameStream>> + Send {
+    let _ = (msg, id);
+    async { Err(io::Error::new(io::ErrorKind::Unsupported, "transport doesn't support streaming")) }
+  }
+
+  /** Sends the first request frame of a method streaming its requests, the rest go through the sink
+  with the same `id` and end with an end of stream marker or an error frame. Responses are yielded like by `open_stream` */
+  fn open_call(&self, msg: Vec<u8>, id: String) -> impl Future<Output = io::Result<(FrameSink, FrameStream)>> + Send {
+    let _ = (msg, id);
+    async { Err(io::Error::new(io::ErrorKind::Unsupported, "transport doesn't support streaming requests")) }
+  }
+}
+
+/** Answers a request frame, generated servers implement it on top of `process_request_frames`.
+Plain methods send one frame, streaming ones send theirs as they come */
+pub trait Service: Send + Sync + 'static {
+  /** `requests` yields the frames routed to the call after `frame`, it's empty unless `route` opened a stream */
+  fn call(&self, frame: Vec<u8>, requests: FrameStream, responses: FrameSink) -> impl Future<Output = ()> + Send;
+
+  /** Only services with methods streaming their requests route frames to running calls */
+  fn route(&self, frame: &[u8]) -> Route {
+    let _ = frame;
+    Route::Call
+  }
+
+  /** Only services with methods streaming their responses tell those calls apart, `handle_http` refuses them */
+  fn streams_responses(&self, frame: &[u8]) -> bool {
+    let _ = frame;
+    false
+  }
+
+  /** Checks `method`, `Service/Method` as named by an HTTP path, against the method `frame` calls.
+  Only services naming their methods tell them apart, any method is taken as called otherwise */
+  fn match_method(&self, frame: &[u8], method: &str) -> MethodMatch {
+    let _ = (frame, method);
+    MethodMatch::Called
+  }
+}
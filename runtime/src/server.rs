@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream, UnixListener};
+use tokio::sync::mpsc;
+
+use crate::frame::{read_frame, write_frame};
+use crate::stream::{feed, FrameFeed, STREAM_BUFFER};
+use crate::{FrameSink, FrameStream, Service};
+
+
+/** What a connection does with a request frame, see `Service::route` */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Route {
+  /** Starts a call reading no further frames */
+  Call,
+  /** Feeds the running call with this id, or starts one */
+  Stream(String),
+  /** Ends the request stream with this id, its call still gets the frame.
+  Without a stream open under the id the frame starts a call, for the service to answer it with an error */
+  End(String),
+}
+
+/** How the `Service/Method` named by an HTTP path relates to a request frame, see `Service::match_method` */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MethodMatch {
+  /** The frame calls the named method */
+  Called,
+  /** The named method exists but takes another payload */
+  Other,
+  /** The service has no such method */
+  Unknown,
+}
+
+
+/** Pause after a failed accept that wasn't the connection's fault, like running out of file descriptors */
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+/** Accepts connections until the task is dropped, each one is served on its own task.
+A failed accept is logged and skipped, it never stops the server */
+pub async fn serve_tcp<S: Service>(listener: TcpListener, service: Arc<S>) -> io::Result<()> {
+  loop {
+    let (stream, _) = accept(|| listener.accept()).await;
+    set_nodelay(&stream);
+    tokio::spawn(serve_connection(stream, service.clone()));
+  }
+}
+
+/** Same as `serve_tcp` for a Unix domain socket */
+pub async fn serve_unix<S: Service>(listener: UnixListener, service: Arc<S>) -> io::Result<()> {
+  loop {
+    let (stream, _) = accept(|| listener.accept()).await;
+    tokio::spawn(serve_connection(stream, service.clone()));
+  }
+}
+
+/** Retries `accept` until it yields a connection. A connection reset before it was accepted only costs itself,
+other failures pause accepting for a bit, so connections closing meanwhile give back what ran out */
+pub(crate) async fn accept<T, F>(mut accept: impl FnMut() -> F) -> T
+where
+  F: Future<Output = io::Result<T>>,
+{
+  loop {
+    match accept().await {
+      Ok(connection) => return connection,
+      Err(e) if is_connection_error(&e) => log::debug!("dropped a connection failing to be accepted: {e}"),
+      Err(e) => {
+        log::warn!("accepting a connection failed, retrying in {ACCEPT_BACKOFF:?}: {e}");
+        tokio::time::sleep(ACCEPT_BACKOFF).await;
+      }
+    }
+  }
+}
+
+fn is_connection_error(error: &io::Error) -> bool {
+  matches!(
+    error.kind(),
+    io::ErrorKind::ConnectionAborted | io::ErrorKind::ConnectionRefused | io::ErrorKind::ConnectionReset | io::ErrorKind::Interrupted
+  )
+}
+
+/** Frames are small and answered right away, a connection that can't turn Nagle off is still served */
+pub(crate) fn set_nodelay(stream: &TcpStream) {
+  if let Err(e) = stream.set_nodelay(true) {
+    log::debug!("can't set TCP_NODELAY on an accepted connection: {e}");
+  }
+}
+
+/** Every call runs on its own task, responses are written as soon as they are ready,
+so they may go out of order and clients match them by the root table `id`.
+Frames of a request stream go to their call as `Service::route` tells */
+pub async fn serve_connection<S, IO>(stream: IO, service: Arc<S>) -> io::Result<()>
+where
+  S: Service,
+  IO: AsyncRead + AsyncWrite + Send + 'static,
+{
+  let (mut reader, mut writer) = tokio::io::split(stream);
+  let (responses, mut outgoing) = mpsc::channel::<Vec<u8>>(STREAM_BUFFER);
+
+  let write_responses = tokio::spawn(async move {
+    while let Some(frame) = outgoing.recv().await {
+      write_frame(&mut writer, &frame).await?;
+    }
+    Ok::<(), io::Error>(())
+  });
+
+  let mut dispatcher = Dispatcher::new(service, responses);
+  let read_result = loop {
+    match read_frame(&mut reader).await {
+      Ok(Some(frame)) => dispatcher.dispatch(frame),
+      Ok(None) => break Ok(()),
+      Err(e) => break Err(e),
+    }
+  };
+
+  // requests still in flight hold their own senders, the writer stops once they all answered
+  drop(dispatcher);
+  let write_result = write_responses.await
+    .unwrap_or_else(|e| Err(io::Error::other(e)));
+
+  read_result.and(write_result)
+}
+
+
+/** Routes the request frames of one connection, each call runs on its own task */
+pub(crate) struct Dispatcher<S> {
+  service: Arc<S>,
+  responses: mpsc::Sender<Vec<u8>>,
+  streams: Streams,
+  calls: u64,
+}
+
+/** Open request streams, an entry is forgotten once its end arrives or the connection closes */
+type Streams = Arc<Mutex<HashMap<String, OpenStream>>>;
+
+struct OpenStream {
+  /** Tells the call of the entry apart from a later one reusing its id */
+  call: u64,
+  /** `None` once the call returned, stopped reading or fell behind. Frames continuing a stream look like
+  ones opening a call, so the rest of the stream is dropped up to its end instead of starting new calls */
+  frames: Option<FrameFeed>,
+}
+
+impl<S: Service> Dispatcher<S> {
+  pub(crate) fn new(service: Arc<S>, responses: mpsc::Sender<Vec<u8>>) -> Self {
+    Dispatcher { service, responses, streams: Arc::default(), calls: 0 }
+  }
+
+  /** Never waits for a call, one with `STREAM_BUFFER` request frames unread has its stream cut off instead */
+  pub(crate) fn dispatch(&mut self, frame: Vec<u8>) {
+    match self.service.route(&frame) {
+      Route::Call => self.spawn_call(frame, FrameStream::empty(), None),
+      Route::Stream(id) => {
+        let mut streams = self.streams.lock().unwrap();
+        match streams.get_mut(&id) {
+          Some(stream) => {
+            if stream.frames.as_ref().is_some_and(|frames| !frames.push(frame)) {
+              stream.frames = None;
+            }
+          }
+          None => {
+            self.calls += 1;
+            let (frames, requests) = feed();
+            streams.insert(id.clone(), OpenStream { call: self.calls, frames: Some(frames) });
+            drop(streams);
+            self.spawn_call(frame, requests, Some((id, self.calls)));
+          }
+        }
+      }
+      Route::End(id) => {
+        let stream = self.streams.lock().unwrap().remove(&id);
+        match stream {
+          Some(OpenStream { frames: Some(frames), .. }) => {
+            frames.push(frame);
+          }
+          // the call already answered, it only stopped reading its stream
+          Some(OpenStream { frames: None, .. }) => {}
+          None => self.spawn_call(frame, FrameStream::empty(), None),
+        }
+      }
+    }
+  }
+
+  /** A call returning before the end of its request stream stops it being fed, the entry goes with the end */
+  fn spawn_call(&self, frame: Vec<u8>, requests: FrameStream, stream: Option<(String, u64)>) {
+    let service = self.service.clone();
+    let responses = FrameSink::from(self.responses.clone());
+    let streams = self.streams.clone();
+    tokio::spawn(async move {
+      service.call(frame, requests, responses).await;
+      if let Some((id, call)) = stream {
+        if let Some(stream) = streams.lock().unwrap().get_mut(&id).filter(|stream| stream.call == call) {
+          stream.frames = None;
+        }
+      }
+    });
+  }
+}
+
+impl<S> Drop for Dispatcher<S> {
+  /** Ends the request streams still open, the connection is closed */
+  fn drop(&mut self) {
+    self.streams.lock().unwrap().clear();
+  }
+}
@@ -0,0 +1,121 @@
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use futures_core::Stream;
+use tokio::sync::mpsc;
+
+
+/** Frames buffered per stream and per connection writer. A call writing waits while its connection is busy,
+but the reader of a connection never waits for one stream: a stream with this many frames unread is cut off,
+like the TS `FrameQueue`, and the other calls on the connection go on */
+pub const STREAM_BUFFER: usize = 64;
+
+/** Where the frames of one call are written, sending waits while the connection is busy */
+#[derive(Debug, Clone)]
+pub struct FrameSink(mpsc::Sender<Vec<u8>>);
+
+impl FrameSink {
+  /** `false` once nobody reads frames anymore, a streaming handler should stop then */
+  pub async fn send(&self, frame: Vec<u8>) -> bool {
+    self.0.send(frame).await.is_ok()
+  }
+
+  /** Sends without waiting, for `Drop` impls. A frame that doesn't fit the buffer is sent from a new task */
+  pub fn send_detached(&self, frame: Vec<u8>) {
+    if let Err(mpsc::error::TrySendError::Full(frame)) = self.0.try_send(frame) {
+      if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+        let frames = self.0.clone();
+        runtime.spawn(async move { frames.send(frame).await });
+      }
+    }
+  }
+}
+
+impl From<mpsc::Sender<Vec<u8>>> for FrameSink {
+  fn from(sender: mpsc::Sender<Vec<u8>>) -> Self {
+    FrameSink(sender)
+  }
+}
+
+
+/** Where a connection reader puts the frames of one stream, without ever waiting for it */
+pub(crate) struct FrameFeed {
+  frames: mpsc::Sender<Vec<u8>>,
+  fell_behind: Arc<AtomicBool>,
+}
+
+impl FrameFeed {
+  /** `false` once the stream is dropped or has `STREAM_BUFFER` frames unread, the frame is dropped then.
+  Dropping the feed afterwards ends the stream, so it's cut off and not just missing a frame */
+  pub(crate) fn push(&self, frame: Vec<u8>) -> bool {
+    match self.frames.try_send(frame) {
+      Ok(()) => true,
+      Err(mpsc::error::TrySendError::Full(_)) => {
+        self.fell_behind.store(true, Ordering::Relaxed);
+        false
+      }
+      Err(mpsc::error::TrySendError::Closed(_)) => false,
+    }
+  }
+}
+
+/** A stream and the feed of its connection reader */
+pub(crate) fn feed() -> (FrameFeed, FrameStream) {
+  let (frames, received) = mpsc::channel(STREAM_BUFFER);
+  let stream = FrameStream::new(received);
+  let feed = FrameFeed { frames, fell_behind: stream.fell_behind.clone() };
+  (feed, stream)
+}
+
+
+/** Frames of one streaming call, dropping it tells the transport to forget the call */
+pub struct FrameStream {
+  frames: mpsc::Receiver<Vec<u8>>,
+  fell_behind: Arc<AtomicBool>,
+  on_drop: Option<Box<dyn FnOnce() + Send + Sync>>,
+}
+
+impl FrameStream {
+  pub fn new(frames: mpsc::Receiver<Vec<u8>>) -> Self {
+    FrameStream { frames, fell_behind: Arc::default(), on_drop: None }
+  }
+
+  /** A stream that already ended, what calls not streaming their requests get */
+  pub fn empty() -> Self {
+    let (_, frames) = mpsc::channel(1);
+    FrameStream::new(frames)
+  }
+
+  /** Runs `on_drop` when the stream is dropped */
+  pub fn with_cleanup(mut self, on_drop: impl FnOnce() + Send + Sync + 'static) -> Self {
+    self.on_drop = Some(Box::new(on_drop));
+    self
+  }
+
+  /** `None` once the connection is closed or the stream fell behind */
+  pub async fn next_frame(&mut self) -> Option<Vec<u8>> {
+    self.frames.recv().await
+  }
+
+  /** Whether the stream was cut off because `STREAM_BUFFER` frames were left unread, instead of the connection closing */
+  pub fn fell_behind(&self) -> bool {
+    self.fell_behind.load(Ordering::Relaxed)
+  }
+}
+
+impl Stream for FrameStream {
+  type Item = Vec<u8>;
+
+  fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    self.frames.poll_recv(cx)
+  }
+}
+
+impl Drop for FrameStream {
+  fn drop(&mut self) {
+    if let Some(on_drop) = self.on_drop.take() {
+      on_drop();
+    }
+  }
+}
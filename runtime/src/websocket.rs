@@ -0,0 +1,71 @@
+use std::io;
+use std::sync::Arc;
+use futures_util::{SinkExt, StreamExt};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::WebSocketStream;
+
+use crate::server::{accept, set_nodelay, Dispatcher};
+use crate::stream::STREAM_BUFFER;
+use crate::Service;
+
+
+/** Accepts connections until the task is dropped, each one is upgraded and served on its own task.
+A failed accept or handshake only drops its connection */
+pub async fn serve_websocket<S: Service>(listener: TcpListener, service: Arc<S>) -> io::Result<()> {
+  loop {
+    let (stream, _) = accept(|| listener.accept()).await;
+    set_nodelay(&stream);
+
+    let service = service.clone();
+    tokio::spawn(async move {
+      let stream = tokio_tungstenite::accept_async(stream).await.map_err(into_io_error)?;
+      serve_websocket_connection(stream, service).await
+    });
+  }
+}
+
+/** Same as `serve_connection` with a binary message per frame, for sockets upgraded elsewhere */
+pub async fn serve_websocket_connection<S, IO>(stream: WebSocketStream<IO>, service: Arc<S>) -> io::Result<()>
+where
+  S: Service,
+  IO: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+  let (mut sink, mut source) = stream.split();
+  let (responses, mut outgoing) = mpsc::channel::<Vec<u8>>(STREAM_BUFFER);
+
+  let write_responses = tokio::spawn(async move {
+    while let Some(frame) = outgoing.recv().await {
+      sink.send(Message::binary(frame)).await?;
+    }
+    Ok::<(), WsError>(())
+  });
+
+  // the stream ends after the close handshake, tungstenite answers pings and closes by itself
+  let mut dispatcher = Dispatcher::new(service, responses);
+  let read_result = loop {
+    match source.next().await {
+      Some(Ok(Message::Binary(frame))) => dispatcher.dispatch(frame.to_vec()),
+      Some(Ok(_)) => continue,
+      Some(Err(e)) => break Err(into_io_error(e)),
+      None => break Ok(()),
+    }
+  };
+
+  drop(dispatcher);
+  let write_result = match write_responses.await {
+    Ok(write_result) => write_result.map_err(into_io_error),
+    Err(e) => Err(io::Error::other(e)),
+  };
+
+  read_result.and(write_result)
+}
+
+fn into_io_error(error: WsError) -> io::Error {
+  match error {
+    WsError::Io(e) => e,
+    e => io::Error::other(e),
+  }
+}
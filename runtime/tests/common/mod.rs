@@ -0,0 +1,122 @@
+#![allow(dead_code)]
+
+/** The transport tests serve and call rpc.fbs through the code flat-rpc generates for it. Test files declare
+`use common::schema_generated;` next to `mod common;` so the generated `crate::schema_generated` paths resolve */
+#[allow(clippy::all, unused_imports, mismatched_lifetime_syntaxes)]
+pub mod schema_generated;
+#[allow(clippy::all, unused_imports)]
+pub mod rpc_server;
+#[allow(clippy::all, unused_imports)]
+pub mod rpc_client;
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use flatbuffers::FlatBufferBuilder;
+use tokio::sync::mpsc::UnboundedSender;
+use flat_rpc_runtime::Stream;
+
+use rpc_server::{ChunkContent, CountContent, NumberContent, RequestContext, RequestStream, RpcError, TestHandler, TextContent};
+use schema_generated::{root_as_envelope, Envelope, EnvelopeArgs, Payload};
+
+
+/** `Echo` answers its text after `delay_ms` and refuses an empty one, `Count` streams `0` to `n - 1` and fails
+afterwards when asked to. `Gather` counts the chunks of its request stream, stopping at the `take` of the first one
+when that isn't 0, after holding on to the first chunk for its `hold_ms`. What each gathering comes to is reported
+to `gathered`, errors included */
+#[derive(Default)]
+pub struct Handler {
+  pub gathered: Option<UnboundedSender<Result<u32, RpcError>>>,
+}
+
+impl TestHandler for Handler {
+  async fn echo(&self, _ctx: &RequestContext, req: TextContent) -> Result<TextContent, RpcError> {
+    tokio::time::sleep(Duration::from_millis(req.delay_ms.into())).await;
+    if req.value.is_empty() {
+      return Err(RpcError::new(RpcError::BAD_REQUEST, "nothing to echo"));
+    }
+    Ok(TextContent { value: req.value, ..Default::default() })
+  }
+
+  fn count(&self, _ctx: &RequestContext, req: CountContent) -> impl Stream<Item = Result<NumberContent, RpcError>> + Send {
+    Numbers { next: 0, count: req }
+  }
+
+  async fn gather(&self, _ctx: &RequestContext, mut reqs: RequestStream<ChunkContent>) -> Result<NumberContent, RpcError> {
+    let mut gathered = 0;
+    let mut take = 0;
+    let outcome = loop {
+      match reqs.next().await {
+        Some(Ok(chunk)) => {
+          if gathered == 0 {
+            take = chunk.take;
+            tokio::time::sleep(Duration::from_millis(chunk.hold_ms.into())).await;
+          }
+          gathered += 1;
+          if gathered == take {
+            break Ok(gathered);
+          }
+        }
+        Some(Err(error)) => break Err(error.with_details(format!("after {gathered} chunks"))),
+        None => break Ok(gathered),
+      }
+    };
+    if let Some(reported) = &self.gathered {
+      let _ = reported.send(outcome.clone());
+    }
+    outcome.map(|n| NumberContent { n, ..Default::default() })
+  }
+}
+
+struct Numbers {
+  next: u32,
+  count: CountContent,
+}
+
+impl Stream for Numbers {
+  type Item = Result<NumberContent, RpcError>;
+
+  fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    let n = self.next;
+    self.next += 1;
+    Poll::Ready(match n {
+      n if n < self.count.n => Some(Ok(NumberContent { n, ..Default::default() })),
+      n if n == self.count.n && self.count.fail => Some(Err(RpcError::internal(format!("failed after {n}")))),
+      _ => None,
+    })
+  }
+}
+
+
+/** The frame a client finishing request stream `id` sends */
+pub fn end_frame(id: &str) -> Vec<u8> {
+  let mut builder = FlatBufferBuilder::new();
+  let id = builder.create_string(id);
+  let envelope = Envelope::create(&mut builder, &EnvelopeArgs { id: Some(id), payload_type: Payload::NONE, payload: None });
+  builder.finish(envelope, None);
+  builder.finished_data().to_vec()
+}
+
+pub fn text_frame(id: &str, value: &str, delay_ms: u32) -> Vec<u8> {
+  Vec::from(TextContent { id: id.to_owned(), value: value.to_owned(), delay_ms })
+}
+
+/** The text of a Text frame, or `code: message` of an RpcError one */
+pub fn text_of(frame: &[u8]) -> String {
+  let envelope = root_as_envelope(frame).unwrap();
+  match envelope.payload_type() {
+    Payload::Text => envelope.payload_as_text().unwrap().value().unwrap_or_default().to_owned(),
+    Payload::RpcError => {
+      let error = envelope.payload_as_rpc_error().unwrap();
+      format!("{}: {}", error.code(), error.message().unwrap_or_default())
+    }
+    unexpected => panic!("expected Text or RpcError, got {unexpected:?}"),
+  }
+}
+
+/** A socket path no other test run uses */
+pub fn socket_path(name: &str) -> std::path::PathBuf {
+  let path = std::env::temp_dir().join(format!("flat-rpc-runtime-{}-{name}.sock", std::process::id()));
+  let _ = std::fs::remove_file(&path);
+  path
+}
@@ -0,0 +1,502 @@
+
+pub use crate::schema_generated::{ Text, TextArgs, TextT, Count, CountArgs, CountT, Number, NumberArgs, NumberT, Chunk, ChunkArgs, ChunkT, RpcError as RpcErrorTable, RpcErrorArgs, RpcErrorT, Envelope, EnvelopeArgs, EnvelopeT, Payload, root_as_envelope };
+use flatbuffers::{FlatBufferBuilder, WIPOffset, UnionWIPOffset};
+pub trait IntoOffset<T: 'static> {
+  fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<T>;
+}
+
+/** Decoding a protocol table into its content type failed */
+#[derive(Debug, Clone)]
+pub struct DecodeError {
+  pub message: String,
+}
+
+impl std::fmt::Display for DecodeError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "can't decode protocol table: {}", self.message)
+  }
+}
+
+impl std::error::Error for DecodeError {}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct RpcError {
+  pub code: u32,
+  pub message: String,
+  pub details: String,
+}
+
+impl RpcError {
+  pub const BAD_REQUEST: u32 = 400;
+  pub const CANCELLED: u32 = 499;
+  pub const INTERNAL: u32 = 500;
+  pub const NOT_IMPLEMENTED: u32 = 501;
+  pub const BAD_RESPONSE: u32 = 502;
+  pub const UNAVAILABLE: u32 = 503;
+
+  pub fn new(code: u32, message: impl Into<String>) -> Self {
+    RpcError { code, message: message.into(), details: String::new() }
+  }
+
+  pub fn internal(message: impl Into<String>) -> Self {
+    RpcError::new(RpcError::INTERNAL, message)
+  }
+
+  pub fn with_details(mut self, details: impl Into<String>) -> Self {
+    self.details = details.into();
+    self
+  }
+}
+
+impl std::fmt::Display for RpcError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "rpc error {}: {}", self.code, self.message)
+  }
+}
+
+impl std::error::Error for RpcError {}
+
+impl From<DecodeError> for RpcError {
+  fn from(error: DecodeError) -> Self {
+    RpcError::new(RpcError::BAD_REQUEST, error.to_string())
+  }
+}
+
+
+impl From<RpcErrorTable<'_>> for RpcError {
+  fn from(error: RpcErrorTable<'_>) -> Self {
+    RpcError {
+      code: error.code(),
+      message: error.message().unwrap_or_default().to_owned(),
+      details: error.details().unwrap_or_default().to_owned(),
+    }
+  }
+}
+
+
+const _: () = {
+assert!(Payload::Text.0 == 1, "Payload::Text must be 1 to stay wire compatible with the schema");
+assert!(Payload::Count.0 == 2, "Payload::Count must be 2 to stay wire compatible with the schema");
+assert!(Payload::Number.0 == 3, "Payload::Number must be 3 to stay wire compatible with the schema");
+assert!(Payload::Chunk.0 == 4, "Payload::Chunk must be 4 to stay wire compatible with the schema");
+assert!(Payload::RpcError.0 == 5, "Payload::RpcError must be 5 to stay wire compatible with the schema");
+};
+
+
+pub enum PayloadContent {
+Text(TextContent),
+Count(CountContent),
+Number(NumberContent),
+Chunk(ChunkContent),
+RpcError(RpcErrorContent),
+}
+
+impl PayloadContent {
+  pub fn union_type(&self) -> Payload {
+    match self {
+      PayloadContent::Text(_) => Payload::Text,
+      PayloadContent::Count(_) => Payload::Count,
+      PayloadContent::Number(_) => Payload::Number,
+      PayloadContent::Chunk(_) => Payload::Chunk,
+      PayloadContent::RpcError(_) => Payload::RpcError,
+    }
+  }
+
+  /** `table` must be the value stored next to `union_type` in a buffer checked by the verifier, which verifies union values against their type.
+  NONE and members unknown to this schema version decode as None */
+  #[allow(dead_code)]
+  fn from_table(union_type: Payload, table: flatbuffers::Table<'_>) -> Result<Option<Self>, DecodeError> {
+    match union_type {
+      Payload::Text => TextContent::try_from(unsafe { Text::init_from_table(table) }).map(|value| Some(PayloadContent::Text(value))),
+      Payload::Count => CountContent::try_from(unsafe { Count::init_from_table(table) }).map(|value| Some(PayloadContent::Count(value))),
+      Payload::Number => NumberContent::try_from(unsafe { Number::init_from_table(table) }).map(|value| Some(PayloadContent::Number(value))),
+      Payload::Chunk => ChunkContent::try_from(unsafe { Chunk::init_from_table(table) }).map(|value| Some(PayloadContent::Chunk(value))),
+      Payload::RpcError => RpcErrorContent::try_from(unsafe { RpcErrorTable::init_from_table(table) }).map(|value| Some(PayloadContent::RpcError(value))),
+      _ => Ok(None),
+    }
+  }
+
+  /** Decodes a vector of unions out of its parallel type and value vectors, NONE and unknown members are skipped */
+  #[allow(dead_code)]
+  fn from_vectors<'a>(types: impl IntoIterator<Item = Payload>, tables: impl IntoIterator<Item = flatbuffers::Table<'a>>) -> Result<Vec<Self>, DecodeError> {
+    let mut values = vec![];
+    for (union_type, table) in types.into_iter().zip(tables) {
+      if let Some(value) = Self::from_table(union_type, table)? {
+        values.push(value);
+      }
+    }
+    Ok(values)
+  }
+}
+
+impl IntoOffset<UnionWIPOffset> for PayloadContent {
+  fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<UnionWIPOffset> {
+    match self {
+      PayloadContent::Text(value) => value.into_offset(builder).as_union_value(),
+      PayloadContent::Count(value) => value.into_offset(builder).as_union_value(),
+      PayloadContent::Number(value) => value.into_offset(builder).as_union_value(),
+      PayloadContent::Chunk(value) => value.into_offset(builder).as_union_value(),
+      PayloadContent::RpcError(value) => value.into_offset(builder).as_union_value(),
+    }
+  }
+}
+
+
+pub struct TextContent {
+	pub value: String,
+	pub delay_ms: u32,
+	pub id: String
+}
+
+
+impl Default for TextContent {
+fn default() -> Self {
+TextContent {
+value: Default::default(),
+delay_ms: Default::default(),
+id: Default::default(),
+}
+}
+}
+impl IntoOffset<Text<'static>> for TextContent {
+fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<Text<'static>> {
+let value_offset = builder.create_string(&self.value);
+let args = &TextArgs { 
+  value: Some(value_offset), delay_ms: self.delay_ms,   };
+Text::create(builder, args)
+}
+}
+
+
+impl From < TextContent > for Vec < u8 > { fn from (content : TextContent) -> Self { let mut builder = FlatBufferBuilder :: new () ; let id = content . id . to_owned () ; let root_type = EnvelopeContent { id , payload : Some (PayloadContent :: Text (content)) , } ; let offset = root_type . into_offset (& mut builder) ; builder . finish (offset , None) ; Vec :: from (builder . finished_data ()) } }
+impl TryFrom<Text<'_>> for TextContent {
+type Error = DecodeError;
+fn try_from(table: Text<'_>) -> Result<Self, Self::Error> {
+Ok(TextContent {
+value: table.value().unwrap_or_default().to_owned(),
+delay_ms: table.delay_ms(),
+id: String::new(),
+})
+}
+}
+
+pub struct CountContent {
+	pub n: u32,
+	pub fail: bool,
+	pub id: String
+}
+
+
+impl Default for CountContent {
+fn default() -> Self {
+CountContent {
+n: Default::default(),
+fail: Default::default(),
+id: Default::default(),
+}
+}
+}
+impl IntoOffset<Count<'static>> for CountContent {
+fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<Count<'static>> {
+let args = &CountArgs { 
+   n: self.n,
+fail: self.fail,   };
+Count::create(builder, args)
+}
+}
+
+
+impl From < CountContent > for Vec < u8 > { fn from (content : CountContent) -> Self { let mut builder = FlatBufferBuilder :: new () ; let id = content . id . to_owned () ; let root_type = EnvelopeContent { id , payload : Some (PayloadContent :: Count (content)) , } ; let offset = root_type . into_offset (& mut builder) ; builder . finish (offset , None) ; Vec :: from (builder . finished_data ()) } }
+impl TryFrom<Count<'_>> for CountContent {
+type Error = DecodeError;
+fn try_from(table: Count<'_>) -> Result<Self, Self::Error> {
+Ok(CountContent {
+n: table.n(),
+fail: table.fail(),
+id: String::new(),
+})
+}
+}
+
+pub struct NumberContent {
+	pub n: u32,
+	pub id: String
+}
+
+
+impl Default for NumberContent {
+fn default() -> Self {
+NumberContent {
+n: Default::default(),
+id: Default::default(),
+}
+}
+}
+impl IntoOffset<Number<'static>> for NumberContent {
+fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<Number<'static>> {
+let args = &NumberArgs { 
+   n: self.n,   };
+Number::create(builder, args)
+}
+}
+
+
+impl From < NumberContent > for Vec < u8 > { fn from (content : NumberContent) -> Self { let mut builder = FlatBufferBuilder :: new () ; let id = content . id . to_owned () ; let root_type = EnvelopeContent { id , payload : Some (PayloadContent :: Number (content)) , } ; let offset = root_type . into_offset (& mut builder) ; builder . finish (offset , None) ; Vec :: from (builder . finished_data ()) } }
+impl TryFrom<Number<'_>> for NumberContent {
+type Error = DecodeError;
+fn try_from(table: Number<'_>) -> Result<Self, Self::Error> {
+Ok(NumberContent {
+n: table.n(),
+id: String::new(),
+})
+}
+}
+
+pub struct ChunkContent {
+	pub take: u32,
+	pub hold_ms: u32,
+	pub id: String
+}
+
+
+impl Default for ChunkContent {
+fn default() -> Self {
+ChunkContent {
+take: Default::default(),
+hold_ms: Default::default(),
+id: Default::default(),
+}
+}
+}
+impl IntoOffset<Chunk<'static>> for ChunkContent {
+fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<Chunk<'static>> {
+let args = &ChunkArgs { 
+   take: self.take,
+hold_ms: self.hold_ms,   };
+Chunk::create(builder, args)
+}
+}
+
+
+impl From < ChunkContent > for Vec < u8 > { fn from (content : ChunkContent) -> Self { let mut builder = FlatBufferBuilder :: new () ; let id = content . id . to_owned () ; let root_type = EnvelopeContent { id , payload : Some (PayloadContent :: Chunk (content)) , } ; let offset = root_type . into_offset (& mut builder) ; builder . finish (offset , None) ; Vec :: from (builder . finished_data ()) } }
+impl TryFrom<Chunk<'_>> for ChunkContent {
+type Error = DecodeError;
+fn try_from(table: Chunk<'_>) -> Result<Self, Self::Error> {
+Ok(ChunkContent {
+take: table.take(),
+hold_ms: table.hold_ms(),
+id: String::new(),
+})
+}
+}
+
+pub struct RpcErrorContent {
+	pub code: u32,
+	pub message: String,
+	pub details: String,
+	pub id: String
+}
+
+
+impl Default for RpcErrorContent {
+fn default() -> Self {
+RpcErrorContent {
+code: Default::default(),
+message: Default::default(),
+details: Default::default(),
+id: Default::default(),
+}
+}
+}
+impl IntoOffset<RpcErrorTable<'static>> for RpcErrorContent {
+fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<RpcErrorTable<'static>> {
+let message_offset = builder.create_string(&self.message);
+let details_offset = builder.create_string(&self.details);
+let args = &RpcErrorArgs { 
+  message: Some(message_offset),
+details: Some(details_offset), code: self.code,   };
+RpcErrorTable::create(builder, args)
+}
+}
+
+
+impl From < RpcErrorContent > for Vec < u8 > { fn from (content : RpcErrorContent) -> Self { let mut builder = FlatBufferBuilder :: new () ; let id = content . id . to_owned () ; let root_type = EnvelopeContent { id , payload : Some (PayloadContent :: RpcError (content)) , } ; let offset = root_type . into_offset (& mut builder) ; builder . finish (offset , None) ; Vec :: from (builder . finished_data ()) } }
+impl TryFrom<RpcErrorTable<'_>> for RpcErrorContent {
+type Error = DecodeError;
+fn try_from(table: RpcErrorTable<'_>) -> Result<Self, Self::Error> {
+Ok(RpcErrorContent {
+code: table.code(),
+message: table.message().unwrap_or_default().to_owned(),
+details: table.details().unwrap_or_default().to_owned(),
+id: String::new(),
+})
+}
+}
+
+pub struct EnvelopeContent {
+	pub id: String,
+	pub payload: Option<PayloadContent>,
+}
+
+
+impl Default for EnvelopeContent {
+fn default() -> Self {
+EnvelopeContent {
+id: Default::default(),
+payload: Default::default(),
+}
+}
+}
+impl IntoOffset<Envelope<'static>> for EnvelopeContent {
+fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<Envelope<'static>> {
+let id_offset = builder.create_string(&self.id);
+let payload_type = self.payload.as_ref().map(PayloadContent::union_type).unwrap_or_default();
+let payload_offset = self.payload.map(|value| value.into_offset(builder));
+let args = &EnvelopeArgs { 
+ payload_type, payload: payload_offset, id: Some(id_offset),    };
+Envelope::create(builder, args)
+}
+}
+
+
+
+
+use std::future::Future;
+
+/** Per request data passed to every handler */
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+  /** id of the incoming root table, echoed back in the response */
+  pub id: String,
+  pub service: &'static str,
+  pub method: &'static str,
+}
+
+
+/** Failed requests are answered with an Payload::RpcError payload */
+fn error_frame(id: &str, error: &RpcError) -> Vec<u8> {
+  Vec::from(RpcErrorContent {
+    id: id.to_owned(),
+    code: error.code,
+    message: error.message.to_owned(),
+    details: error.details.to_owned(),
+  })
+}
+
+
+/** A root table with an empty payload ends a stream, failed streams end with an RpcError payload instead */
+fn end_of_stream_frame(id: &str) -> Vec<u8> {
+  let mut builder = FlatBufferBuilder::new();
+  let id = builder.create_string(id);
+  let root_type = Envelope::create(&mut builder, &EnvelopeArgs {
+    id: Some(id),
+    payload_type: Payload::NONE,
+    payload: None,
+  });
+  builder.finish(root_type, None);
+
+  Vec::from(builder.finished_data())
+}
+
+pub trait TestHandler {
+fn echo(&self, ctx: &RequestContext, req: TextContent) -> impl Future<Output = Result<TextContent, RpcError>> + Send;
+fn count(&self, ctx: &RequestContext, req: CountContent) -> impl flat_rpc_runtime::Stream<Item = Result<NumberContent, RpcError>> + Send;
+fn gather(&self, ctx: &RequestContext, reqs: RequestStream<ChunkContent>) -> impl Future<Output = Result<NumberContent, RpcError>> + Send;
+}
+pub trait RequestHandler: TestHandler {}
+impl<T: TestHandler> RequestHandler for T {}
+pub async fn process_request(handler: &impl RequestHandler, buffer: Vec<u8>) -> Vec<u8> {
+let root_type = match root_as_envelope(&buffer) {
+Ok(root_type) => root_type,
+Err(e) => return error_frame("", &RpcError::new(RpcError::BAD_REQUEST, format!("can't parse binary as a root type: {e}"))),
+};
+let id = root_type.id().unwrap_or_default();
+let response = match root_type.payload_type() {
+Payload::Text => match root_type.payload_as_text().map(TextContent::try_from) {
+Some(Ok(request)) => {
+let ctx = RequestContext { id: id.to_owned(), service: "Test", method: "Echo" };
+TestHandler::echo(handler, &ctx, request).await.map(|mut response| {
+if response.id.is_empty() {
+response.id = id.to_owned();
+}
+Vec::from(response)
+})
+}
+Some(Err(error)) => Err(RpcError::from(error)),
+None => Err(RpcError::new(RpcError::BAD_REQUEST, "payload of Text is absent")),
+},
+Payload::Count => Err(RpcError::new(RpcError::BAD_REQUEST, "Test::Count is a streaming method, serve it with process_request_frames")),
+Payload::Chunk => Err(RpcError::new(RpcError::BAD_REQUEST, "Test::Gather is a streaming method, serve it with process_request_frames")),
+Payload::NONE | Payload::RpcError => Err(RpcError::new(RpcError::BAD_REQUEST, format!("no request stream is open with id {id:?}"))),
+unknown_variant => Err(RpcError::new(RpcError::NOT_IMPLEMENTED, format!("UNKNOWN PAYLOAD TYPE {:?}", unknown_variant))),
+};
+
+match response {
+Ok(frame) => frame,
+Err(error) => error_frame(id, &error),
+}
+}
+fn decode_test_gather_request (frame : & [u8]) -> Result < Option < ChunkContent > , RpcError > { let root_type = root_as_envelope (frame) . map_err (| e | RpcError :: new (RpcError :: BAD_REQUEST , format ! ("can't parse binary as a root type: {e}"))) ? ; match root_type . payload_type () { Payload :: Chunk => { let payload = root_type . payload_as_chunk () . ok_or_else (|| RpcError :: new (RpcError :: BAD_REQUEST , "payload of Chunk is absent")) ? ; let mut request = ChunkContent :: try_from (payload) ? ; request . id = root_type . id () . unwrap_or_default () . to_owned () ; Ok (Some (request)) } Payload :: NONE => Ok (None) , Payload :: RpcError => Err (root_type . payload_as_rpc_error () . map (RpcError :: from) . unwrap_or_default ()) , unexpected => Err (RpcError :: new (RpcError :: BAD_REQUEST , format ! ("request stream item must be {}, got {:?}" , "Chunk" , unexpected))) , } }
+# [doc = r" Frames continuing a request stream are told apart by their payload type "] pub fn route_request (frame : & [u8]) -> flat_rpc_runtime :: Route { let root_type = match root_as_envelope (frame) { Ok (root_type) => root_type , Err (_) => return flat_rpc_runtime :: Route :: Call , } ; let id = root_type . id () . unwrap_or_default () . to_owned () ; match root_type . payload_type () { Payload :: Chunk => flat_rpc_runtime :: Route :: Stream (id) , Payload :: NONE | Payload :: RpcError => flat_rpc_runtime :: Route :: End (id) , _ => flat_rpc_runtime :: Route :: Call , } } # [doc = r" Requests of a streaming call, it ends after the end of stream marker or the first error, a client cancelling
+    the stream included. A handler leaving `STREAM_BUFFER` requests unread gets an UNAVAILABLE error instead of the rest "] pub struct RequestStream < T > { first : Option < Result < T , RpcError >> , frames : Option < flat_rpc_runtime :: FrameStream > , decode : fn (& [u8]) -> Result < Option < T > , RpcError > , } impl < T > RequestStream < T > { fn new (first : Result < Option < T > , RpcError > , frames : flat_rpc_runtime :: FrameStream , decode : fn (& [u8]) -> Result < Option < T > , RpcError >) -> Self { RequestStream { first : first . transpose () , frames : Some (frames) , decode } } pub async fn next (& mut self) -> Option < Result < T , RpcError >> where T : Unpin { std :: future :: poll_fn (| cx | flat_rpc_runtime :: Stream :: poll_next (std :: pin :: Pin :: new (& mut * self) , cx)) . await } } impl < T : Unpin > flat_rpc_runtime :: Stream for RequestStream < T > { type Item = Result < T , RpcError > ; fn poll_next (mut self : std :: pin :: Pin < & mut Self > , cx : & mut std :: task :: Context < '_ >) -> std :: task :: Poll < Option < Self :: Item >> { let item = match self . first . take () { Some (first) => Some (first) , None => { let frames = match self . frames . as_mut () { Some (frames) => frames , None => return std :: task :: Poll :: Ready (None) , } ; match std :: task :: ready ! (flat_rpc_runtime :: Stream :: poll_next (std :: pin :: Pin :: new (& mut * frames) , cx)) { Some (frame) => (self . decode) (& frame) . transpose () , None if frames . fell_behind () => Some (Err (RpcError :: new (RpcError :: UNAVAILABLE , format ! ("more than {} requests were waiting, the handler fell behind" , flat_rpc_runtime :: STREAM_BUFFER)))) , None => Some (Err (RpcError :: new (RpcError :: UNAVAILABLE , "connection closed before the request stream ended"))) , } } } ; if ! matches ! (item , Some (Ok (_))) { self . frames = None ; } std :: task :: Poll :: Ready (item) } }
+pub async fn process_request_frames(handler: &impl RequestHandler, buffer: Vec<u8>, requests: flat_rpc_runtime::FrameStream, responses: &flat_rpc_runtime::FrameSink) {
+let root_type = match root_as_envelope(&buffer) {
+Ok(root_type) => root_type,
+Err(_) => {
+responses.send(process_request(handler, buffer).await).await;
+return;
+}
+};
+let id = root_type.id().unwrap_or_default();
+match root_type.payload_type() {
+Payload::Count => match root_type.payload_as_count().map(CountContent::try_from) {
+Some(Ok(request)) => {
+let ctx = RequestContext { id: id.to_owned(), service: "Test", method: "Count" };
+let mut items = std::pin::pin!(TestHandler::count(handler, &ctx, request));
+while let Some(item) = std::future::poll_fn(|cx| flat_rpc_runtime::Stream::poll_next(items.as_mut(), cx)).await {
+let frame = match item.map(|mut response| {
+if response.id.is_empty() {
+response.id = id.to_owned();
+}
+Vec::from(response)
+}) {
+Ok(frame) => frame,
+Err(error) => {
+responses.send(error_frame(id, &error)).await;
+return;
+}
+};
+if !responses.send(frame).await {
+return;
+}
+}
+responses.send(end_of_stream_frame(id)).await;
+}
+Some(Err(error)) => {
+responses.send(error_frame(id, &RpcError::from(error))).await;
+}
+None => {
+responses.send(error_frame(id, &RpcError::new(RpcError::BAD_REQUEST, "payload of Count is absent"))).await;
+}
+},
+Payload::Chunk => {
+let ctx = RequestContext { id: id.to_owned(), service: "Test", method: "Gather" };
+let request = RequestStream::new(decode_test_gather_request(&buffer), requests, decode_test_gather_request);
+let frame = match TestHandler::gather(handler, &ctx, request).await.map(|mut response| {
+if response.id.is_empty() {
+response.id = id.to_owned();
+}
+Vec::from(response)
+}) {
+Ok(frame) => frame,
+Err(error) => error_frame(id, &error),
+};
+responses.send(frame).await;
+}
+_ => {
+responses.send(process_request(handler, buffer).await).await;
+}
+}
+}
+pub struct RpcServer < H > (pub H) ; impl < H : RequestHandler + Send + Sync + 'static > flat_rpc_runtime :: Service for RpcServer < H > { async fn call (& self , frame : Vec < u8 > , requests : flat_rpc_runtime :: FrameStream , responses : flat_rpc_runtime :: FrameSink) { process_request_frames (& self . 0 , frame , requests , & responses) . await } fn route (& self , frame : & [u8]) -> flat_rpc_runtime :: Route { route_request (frame) } fn streams_responses (& self , frame : & [u8]) -> bool { match root_as_envelope (frame) { Ok (root_type) => matches ! (root_type . payload_type () , Payload :: Count) , Err (_) => false , } } fn match_method (& self , frame : & [u8] , method : & str) -> flat_rpc_runtime :: MethodMatch { let payload_type = match root_as_envelope (frame) { Ok (root_type) => Some (root_type . payload_type ()) , Err (_) => None , } ; match (payload_type , method) { (Some (Payload :: Text) , "Test/Echo") | (Some (Payload :: Count) , "Test/Count") | (Some (Payload :: Chunk) , "Test/Gather") => flat_rpc_runtime :: MethodMatch :: Called , (_ , "Test/Echo" | "Test/Count" | "Test/Gather") => flat_rpc_runtime :: MethodMatch :: Other , _ => flat_rpc_runtime :: MethodMatch :: Unknown , } } }
\ No newline at end of file
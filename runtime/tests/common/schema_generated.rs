@@ -0,0 +1,1409 @@
+// Written the way `flatc --rust --gen-object-api rpc.fbs` of flatc 24.3.25 writes it,
+// regenerate it with that command once the schema changes
+
+
+// @generated
+
+use core::mem;
+use core::cmp::Ordering;
+
+extern crate flatbuffers;
+use self::flatbuffers::{EndianScalar, Follow};
+
+#[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
+pub const ENUM_MIN_PAYLOAD: u8 = 0;
+#[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
+pub const ENUM_MAX_PAYLOAD: u8 = 5;
+#[deprecated(since = "2.0.0", note = "Use associated constants instead. This will no longer be generated in 2021.")]
+#[allow(non_camel_case_types)]
+pub const ENUM_VALUES_PAYLOAD: [Payload; 6] = [
+  Payload::NONE,
+  Payload::Text,
+  Payload::Count,
+  Payload::Number,
+  Payload::Chunk,
+  Payload::RpcError,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(transparent)]
+pub struct Payload(pub u8);
+#[allow(non_upper_case_globals)]
+impl Payload {
+  pub const NONE: Self = Self(0);
+  pub const Text: Self = Self(1);
+  pub const Count: Self = Self(2);
+  pub const Number: Self = Self(3);
+  pub const Chunk: Self = Self(4);
+  pub const RpcError: Self = Self(5);
+
+  pub const ENUM_MIN: u8 = 0;
+  pub const ENUM_MAX: u8 = 5;
+  pub const ENUM_VALUES: &'static [Self] = &[
+    Self::NONE,
+    Self::Text,
+    Self::Count,
+    Self::Number,
+    Self::Chunk,
+    Self::RpcError,
+  ];
+  /// Returns the variant's name or "" if unknown.
+  pub fn variant_name(self) -> Option<&'static str> {
+    match self {
+      Self::NONE => Some("NONE"),
+      Self::Text => Some("Text"),
+      Self::Count => Some("Count"),
+      Self::Number => Some("Number"),
+      Self::Chunk => Some("Chunk"),
+      Self::RpcError => Some("RpcError"),
+      _ => None,
+    }
+  }
+}
+impl core::fmt::Debug for Payload {
+  fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+    if let Some(name) = self.variant_name() {
+      f.write_str(name)
+    } else {
+      f.write_fmt(format_args!("<UNKNOWN {:?}>", self.0))
+    }
+  }
+}
+impl<'a> flatbuffers::Follow<'a> for Payload {
+  type Inner = Self;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    let b = flatbuffers::read_scalar_at::<u8>(buf, loc);
+    Self(b)
+  }
+}
+
+impl flatbuffers::Push for Payload {
+    type Output = Payload;
+    #[inline]
+    unsafe fn push(&self, dst: &mut [u8], _written_len: usize) {
+        flatbuffers::emplace_scalar::<u8>(dst, self.0);
+    }
+}
+
+impl flatbuffers::EndianScalar for Payload {
+  type Scalar = u8;
+  #[inline]
+  fn to_little_endian(self) -> u8 {
+    self.0.to_le()
+  }
+  #[inline]
+  #[allow(clippy::wrong_self_convention)]
+  fn from_little_endian(v: u8) -> Self {
+    let b = u8::from_le(v);
+    Self(b)
+  }
+}
+
+impl<'a> flatbuffers::Verifiable for Payload {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    u8::run_verifier(v, pos)
+  }
+}
+
+impl flatbuffers::SimpleToVerifyInSlice for Payload {}
+pub struct PayloadUnionTableOffset {}
+
+#[allow(clippy::upper_case_acronyms)]
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayloadT {
+  NONE,
+  Text(Box<TextT>),
+  Count(Box<CountT>),
+  Number(Box<NumberT>),
+  Chunk(Box<ChunkT>),
+  RpcError(Box<RpcErrorT>),
+}
+impl Default for PayloadT {
+  fn default() -> Self {
+    Self::NONE
+  }
+}
+impl PayloadT {
+  pub fn payload_type(&self) -> Payload {
+    match self {
+      Self::NONE => Payload::NONE,
+      Self::Text(_) => Payload::Text,
+      Self::Count(_) => Payload::Count,
+      Self::Number(_) => Payload::Number,
+      Self::Chunk(_) => Payload::Chunk,
+      Self::RpcError(_) => Payload::RpcError,
+    }
+  }
+  pub fn pack<'b, A: flatbuffers::Allocator + 'b>(&self, fbb: &mut flatbuffers::FlatBufferBuilder<'b, A>) -> Option<flatbuffers::WIPOffset<flatbuffers::UnionWIPOffset>> {
+    match self {
+      Self::NONE => None,
+      Self::Text(v) => Some(v.pack(fbb).as_union_value()),
+      Self::Count(v) => Some(v.pack(fbb).as_union_value()),
+      Self::Number(v) => Some(v.pack(fbb).as_union_value()),
+      Self::Chunk(v) => Some(v.pack(fbb).as_union_value()),
+      Self::RpcError(v) => Some(v.pack(fbb).as_union_value()),
+    }
+  }
+  /// If the union variant matches, return the owned TextT, setting the union to NONE.
+  pub fn take_text(&mut self) -> Option<Box<TextT>> {
+    if let Self::Text(_) = self {
+      let v = core::mem::replace(self, Self::NONE);
+      if let Self::Text(w) = v {
+        Some(w)
+      } else {
+        unreachable!()
+      }
+    } else {
+      None
+    }
+  }
+  /// If the union variant matches, return a reference to the TextT.
+  pub fn as_text(&self) -> Option<&TextT> {
+    if let Self::Text(v) = self { Some(v.as_ref()) } else { None }
+  }
+  /// If the union variant matches, return a mutable reference to the TextT.
+  pub fn as_text_mut(&mut self) -> Option<&mut TextT> {
+    if let Self::Text(v) = self { Some(v.as_mut()) } else { None }
+  }
+  /// If the union variant matches, return the owned CountT, setting the union to NONE.
+  pub fn take_count(&mut self) -> Option<Box<CountT>> {
+    if let Self::Count(_) = self {
+      let v = core::mem::replace(self, Self::NONE);
+      if let Self::Count(w) = v {
+        Some(w)
+      } else {
+        unreachable!()
+      }
+    } else {
+      None
+    }
+  }
+  /// If the union variant matches, return a reference to the CountT.
+  pub fn as_count(&self) -> Option<&CountT> {
+    if let Self::Count(v) = self { Some(v.as_ref()) } else { None }
+  }
+  /// If the union variant matches, return a mutable reference to the CountT.
+  pub fn as_count_mut(&mut self) -> Option<&mut CountT> {
+    if let Self::Count(v) = self { Some(v.as_mut()) } else { None }
+  }
+  /// If the union variant matches, return the owned NumberT, setting the union to NONE.
+  pub fn take_number(&mut self) -> Option<Box<NumberT>> {
+    if let Self::Number(_) = self {
+      let v = core::mem::replace(self, Self::NONE);
+      if let Self::Number(w) = v {
+        Some(w)
+      } else {
+        unreachable!()
+      }
+    } else {
+      None
+    }
+  }
+  /// If the union variant matches, return a reference to the NumberT.
+  pub fn as_number(&self) -> Option<&NumberT> {
+    if let Self::Number(v) = self { Some(v.as_ref()) } else { None }
+  }
+  /// If the union variant matches, return a mutable reference to the NumberT.
+  pub fn as_number_mut(&mut self) -> Option<&mut NumberT> {
+    if let Self::Number(v) = self { Some(v.as_mut()) } else { None }
+  }
+  /// If the union variant matches, return the owned ChunkT, setting the union to NONE.
+  pub fn take_chunk(&mut self) -> Option<Box<ChunkT>> {
+    if let Self::Chunk(_) = self {
+      let v = core::mem::replace(self, Self::NONE);
+      if let Self::Chunk(w) = v {
+        Some(w)
+      } else {
+        unreachable!()
+      }
+    } else {
+      None
+    }
+  }
+  /// If the union variant matches, return a reference to the ChunkT.
+  pub fn as_chunk(&self) -> Option<&ChunkT> {
+    if let Self::Chunk(v) = self { Some(v.as_ref()) } else { None }
+  }
+  /// If the union variant matches, return a mutable reference to the ChunkT.
+  pub fn as_chunk_mut(&mut self) -> Option<&mut ChunkT> {
+    if let Self::Chunk(v) = self { Some(v.as_mut()) } else { None }
+  }
+  /// If the union variant matches, return the owned RpcErrorT, setting the union to NONE.
+  pub fn take_rpc_error(&mut self) -> Option<Box<RpcErrorT>> {
+    if let Self::RpcError(_) = self {
+      let v = core::mem::replace(self, Self::NONE);
+      if let Self::RpcError(w) = v {
+        Some(w)
+      } else {
+        unreachable!()
+      }
+    } else {
+      None
+    }
+  }
+  /// If the union variant matches, return a reference to the RpcErrorT.
+  pub fn as_rpc_error(&self) -> Option<&RpcErrorT> {
+    if let Self::RpcError(v) = self { Some(v.as_ref()) } else { None }
+  }
+  /// If the union variant matches, return a mutable reference to the RpcErrorT.
+  pub fn as_rpc_error_mut(&mut self) -> Option<&mut RpcErrorT> {
+    if let Self::RpcError(v) = self { Some(v.as_mut()) } else { None }
+  }
+}
+pub enum TextOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Text<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for Text<'a> {
+  type Inner = Text<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: flatbuffers::Table::new(buf, loc) }
+  }
+}
+
+impl<'a> Text<'a> {
+  pub const VT_VALUE: flatbuffers::VOffsetT = 4;
+  pub const VT_DELAY_MS: flatbuffers::VOffsetT = 6;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    Text { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args TextArgs<'args>
+  ) -> flatbuffers::WIPOffset<Text<'bldr>> {
+    let mut builder = TextBuilder::new(_fbb);
+    builder.add_delay_ms(args.delay_ms);
+    if let Some(x) = args.value { builder.add_value(x); }
+    builder.finish()
+  }
+
+  pub fn unpack(&self) -> TextT {
+    let value = self.value().map(|x| {
+      x.to_string()
+    });
+    let delay_ms = self.delay_ms();
+    TextT {
+      value,
+      delay_ms,
+    }
+  }
+
+  #[inline]
+  pub fn value(&self) -> Option<&'a str> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<&str>>(Text::VT_VALUE, None)}
+  }
+  #[inline]
+  pub fn delay_ms(&self) -> u32 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u32>(Text::VT_DELAY_MS, Some(0)).unwrap()}
+  }
+}
+
+impl flatbuffers::Verifiable for Text<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<flatbuffers::ForwardsUOffset<&str>>("value", Self::VT_VALUE, false)?
+     .visit_field::<u32>("delay_ms", Self::VT_DELAY_MS, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct TextArgs<'a> {
+    pub value: Option<flatbuffers::WIPOffset<&'a str>>,
+    pub delay_ms: u32,
+}
+impl<'a> Default for TextArgs<'a> {
+  #[inline]
+  fn default() -> Self {
+    TextArgs {
+      value: None,
+      delay_ms: 0,
+    }
+  }
+}
+
+pub struct TextBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> TextBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_value(&mut self, value: flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(Text::VT_VALUE, value);
+  }
+  #[inline]
+  pub fn add_delay_ms(&mut self, delay_ms: u32) {
+    self.fbb_.push_slot::<u32>(Text::VT_DELAY_MS, delay_ms, 0);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> TextBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    TextBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<Text<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for Text<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("Text");
+      ds.field("value", &self.value());
+      ds.field("delay_ms", &self.delay_ms());
+      ds.finish()
+  }
+}
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextT {
+  pub value: Option<String>,
+  pub delay_ms: u32,
+}
+impl Default for TextT {
+  fn default() -> Self {
+    Self {
+      value: None,
+      delay_ms: 0,
+    }
+  }
+}
+impl TextT {
+  pub fn pack<'b, A: flatbuffers::Allocator + 'b>(
+    &self,
+    _fbb: &mut flatbuffers::FlatBufferBuilder<'b, A>
+  ) -> flatbuffers::WIPOffset<Text<'b>> {
+    let value = self.value.as_ref().map(|x|{
+      _fbb.create_string(x)
+    });
+    let delay_ms = self.delay_ms;
+    Text::create(_fbb, &TextArgs{
+      value,
+      delay_ms,
+    })
+  }
+}
+pub enum CountOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Count<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for Count<'a> {
+  type Inner = Count<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: flatbuffers::Table::new(buf, loc) }
+  }
+}
+
+impl<'a> Count<'a> {
+  pub const VT_N: flatbuffers::VOffsetT = 4;
+  pub const VT_FAIL: flatbuffers::VOffsetT = 6;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    Count { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args CountArgs
+  ) -> flatbuffers::WIPOffset<Count<'bldr>> {
+    let mut builder = CountBuilder::new(_fbb);
+    builder.add_n(args.n);
+    builder.add_fail(args.fail);
+    builder.finish()
+  }
+
+  pub fn unpack(&self) -> CountT {
+    let n = self.n();
+    let fail = self.fail();
+    CountT {
+      n,
+      fail,
+    }
+  }
+
+  #[inline]
+  pub fn n(&self) -> u32 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u32>(Count::VT_N, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn fail(&self) -> bool {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<bool>(Count::VT_FAIL, Some(false)).unwrap()}
+  }
+}
+
+impl flatbuffers::Verifiable for Count<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<u32>("n", Self::VT_N, false)?
+     .visit_field::<bool>("fail", Self::VT_FAIL, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct CountArgs {
+    pub n: u32,
+    pub fail: bool,
+}
+impl Default for CountArgs {
+  #[inline]
+  fn default() -> Self {
+    CountArgs {
+      n: 0,
+      fail: false,
+    }
+  }
+}
+
+pub struct CountBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> CountBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_n(&mut self, n: u32) {
+    self.fbb_.push_slot::<u32>(Count::VT_N, n, 0);
+  }
+  #[inline]
+  pub fn add_fail(&mut self, fail: bool) {
+    self.fbb_.push_slot::<bool>(Count::VT_FAIL, fail, false);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> CountBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    CountBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<Count<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for Count<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("Count");
+      ds.field("n", &self.n());
+      ds.field("fail", &self.fail());
+      ds.finish()
+  }
+}
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CountT {
+  pub n: u32,
+  pub fail: bool,
+}
+impl Default for CountT {
+  fn default() -> Self {
+    Self {
+      n: 0,
+      fail: false,
+    }
+  }
+}
+impl CountT {
+  pub fn pack<'b, A: flatbuffers::Allocator + 'b>(
+    &self,
+    _fbb: &mut flatbuffers::FlatBufferBuilder<'b, A>
+  ) -> flatbuffers::WIPOffset<Count<'b>> {
+    let n = self.n;
+    let fail = self.fail;
+    Count::create(_fbb, &CountArgs{
+      n,
+      fail,
+    })
+  }
+}
+pub enum NumberOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Number<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for Number<'a> {
+  type Inner = Number<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: flatbuffers::Table::new(buf, loc) }
+  }
+}
+
+impl<'a> Number<'a> {
+  pub const VT_N: flatbuffers::VOffsetT = 4;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    Number { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args NumberArgs
+  ) -> flatbuffers::WIPOffset<Number<'bldr>> {
+    let mut builder = NumberBuilder::new(_fbb);
+    builder.add_n(args.n);
+    builder.finish()
+  }
+
+  pub fn unpack(&self) -> NumberT {
+    let n = self.n();
+    NumberT {
+      n,
+    }
+  }
+
+  #[inline]
+  pub fn n(&self) -> u32 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u32>(Number::VT_N, Some(0)).unwrap()}
+  }
+}
+
+impl flatbuffers::Verifiable for Number<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<u32>("n", Self::VT_N, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct NumberArgs {
+    pub n: u32,
+}
+impl Default for NumberArgs {
+  #[inline]
+  fn default() -> Self {
+    NumberArgs {
+      n: 0,
+    }
+  }
+}
+
+pub struct NumberBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> NumberBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_n(&mut self, n: u32) {
+    self.fbb_.push_slot::<u32>(Number::VT_N, n, 0);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> NumberBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    NumberBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<Number<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for Number<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("Number");
+      ds.field("n", &self.n());
+      ds.finish()
+  }
+}
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberT {
+  pub n: u32,
+}
+impl Default for NumberT {
+  fn default() -> Self {
+    Self {
+      n: 0,
+    }
+  }
+}
+impl NumberT {
+  pub fn pack<'b, A: flatbuffers::Allocator + 'b>(
+    &self,
+    _fbb: &mut flatbuffers::FlatBufferBuilder<'b, A>
+  ) -> flatbuffers::WIPOffset<Number<'b>> {
+    let n = self.n;
+    Number::create(_fbb, &NumberArgs{
+      n,
+    })
+  }
+}
+pub enum ChunkOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Chunk<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for Chunk<'a> {
+  type Inner = Chunk<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: flatbuffers::Table::new(buf, loc) }
+  }
+}
+
+impl<'a> Chunk<'a> {
+  pub const VT_TAKE: flatbuffers::VOffsetT = 4;
+  pub const VT_HOLD_MS: flatbuffers::VOffsetT = 6;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    Chunk { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args ChunkArgs
+  ) -> flatbuffers::WIPOffset<Chunk<'bldr>> {
+    let mut builder = ChunkBuilder::new(_fbb);
+    builder.add_hold_ms(args.hold_ms);
+    builder.add_take(args.take);
+    builder.finish()
+  }
+
+  pub fn unpack(&self) -> ChunkT {
+    let take = self.take();
+    let hold_ms = self.hold_ms();
+    ChunkT {
+      take,
+      hold_ms,
+    }
+  }
+
+  #[inline]
+  pub fn take(&self) -> u32 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u32>(Chunk::VT_TAKE, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn hold_ms(&self) -> u32 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u32>(Chunk::VT_HOLD_MS, Some(0)).unwrap()}
+  }
+}
+
+impl flatbuffers::Verifiable for Chunk<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<u32>("take", Self::VT_TAKE, false)?
+     .visit_field::<u32>("hold_ms", Self::VT_HOLD_MS, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct ChunkArgs {
+    pub take: u32,
+    pub hold_ms: u32,
+}
+impl Default for ChunkArgs {
+  #[inline]
+  fn default() -> Self {
+    ChunkArgs {
+      take: 0,
+      hold_ms: 0,
+    }
+  }
+}
+
+pub struct ChunkBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> ChunkBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_take(&mut self, take: u32) {
+    self.fbb_.push_slot::<u32>(Chunk::VT_TAKE, take, 0);
+  }
+  #[inline]
+  pub fn add_hold_ms(&mut self, hold_ms: u32) {
+    self.fbb_.push_slot::<u32>(Chunk::VT_HOLD_MS, hold_ms, 0);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> ChunkBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    ChunkBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<Chunk<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for Chunk<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("Chunk");
+      ds.field("take", &self.take());
+      ds.field("hold_ms", &self.hold_ms());
+      ds.finish()
+  }
+}
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkT {
+  pub take: u32,
+  pub hold_ms: u32,
+}
+impl Default for ChunkT {
+  fn default() -> Self {
+    Self {
+      take: 0,
+      hold_ms: 0,
+    }
+  }
+}
+impl ChunkT {
+  pub fn pack<'b, A: flatbuffers::Allocator + 'b>(
+    &self,
+    _fbb: &mut flatbuffers::FlatBufferBuilder<'b, A>
+  ) -> flatbuffers::WIPOffset<Chunk<'b>> {
+    let take = self.take;
+    let hold_ms = self.hold_ms;
+    Chunk::create(_fbb, &ChunkArgs{
+      take,
+      hold_ms,
+    })
+  }
+}
+pub enum RpcErrorOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct RpcError<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for RpcError<'a> {
+  type Inner = RpcError<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: flatbuffers::Table::new(buf, loc) }
+  }
+}
+
+impl<'a> RpcError<'a> {
+  pub const VT_CODE: flatbuffers::VOffsetT = 4;
+  pub const VT_MESSAGE: flatbuffers::VOffsetT = 6;
+  pub const VT_DETAILS: flatbuffers::VOffsetT = 8;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    RpcError { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args RpcErrorArgs<'args>
+  ) -> flatbuffers::WIPOffset<RpcError<'bldr>> {
+    let mut builder = RpcErrorBuilder::new(_fbb);
+    if let Some(x) = args.details { builder.add_details(x); }
+    if let Some(x) = args.message { builder.add_message(x); }
+    builder.add_code(args.code);
+    builder.finish()
+  }
+
+  pub fn unpack(&self) -> RpcErrorT {
+    let code = self.code();
+    let message = self.message().map(|x| {
+      x.to_string()
+    });
+    let details = self.details().map(|x| {
+      x.to_string()
+    });
+    RpcErrorT {
+      code,
+      message,
+      details,
+    }
+  }
+
+  #[inline]
+  pub fn code(&self) -> u32 {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<u32>(RpcError::VT_CODE, Some(0)).unwrap()}
+  }
+  #[inline]
+  pub fn message(&self) -> Option<&'a str> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<&str>>(RpcError::VT_MESSAGE, None)}
+  }
+  #[inline]
+  pub fn details(&self) -> Option<&'a str> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<&str>>(RpcError::VT_DETAILS, None)}
+  }
+}
+
+impl flatbuffers::Verifiable for RpcError<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<u32>("code", Self::VT_CODE, false)?
+     .visit_field::<flatbuffers::ForwardsUOffset<&str>>("message", Self::VT_MESSAGE, false)?
+     .visit_field::<flatbuffers::ForwardsUOffset<&str>>("details", Self::VT_DETAILS, false)?
+     .finish();
+    Ok(())
+  }
+}
+pub struct RpcErrorArgs<'a> {
+    pub code: u32,
+    pub message: Option<flatbuffers::WIPOffset<&'a str>>,
+    pub details: Option<flatbuffers::WIPOffset<&'a str>>,
+}
+impl<'a> Default for RpcErrorArgs<'a> {
+  #[inline]
+  fn default() -> Self {
+    RpcErrorArgs {
+      code: 0,
+      message: None,
+      details: None,
+    }
+  }
+}
+
+pub struct RpcErrorBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> RpcErrorBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_code(&mut self, code: u32) {
+    self.fbb_.push_slot::<u32>(RpcError::VT_CODE, code, 0);
+  }
+  #[inline]
+  pub fn add_message(&mut self, message: flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(RpcError::VT_MESSAGE, message);
+  }
+  #[inline]
+  pub fn add_details(&mut self, details: flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(RpcError::VT_DETAILS, details);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> RpcErrorBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    RpcErrorBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<RpcError<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for RpcError<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("RpcError");
+      ds.field("code", &self.code());
+      ds.field("message", &self.message());
+      ds.field("details", &self.details());
+      ds.finish()
+  }
+}
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcErrorT {
+  pub code: u32,
+  pub message: Option<String>,
+  pub details: Option<String>,
+}
+impl Default for RpcErrorT {
+  fn default() -> Self {
+    Self {
+      code: 0,
+      message: None,
+      details: None,
+    }
+  }
+}
+impl RpcErrorT {
+  pub fn pack<'b, A: flatbuffers::Allocator + 'b>(
+    &self,
+    _fbb: &mut flatbuffers::FlatBufferBuilder<'b, A>
+  ) -> flatbuffers::WIPOffset<RpcError<'b>> {
+    let code = self.code;
+    let message = self.message.as_ref().map(|x|{
+      _fbb.create_string(x)
+    });
+    let details = self.details.as_ref().map(|x|{
+      _fbb.create_string(x)
+    });
+    RpcError::create(_fbb, &RpcErrorArgs{
+      code,
+      message,
+      details,
+    })
+  }
+}
+pub enum EnvelopeOffset {}
+#[derive(Copy, Clone, PartialEq)]
+
+pub struct Envelope<'a> {
+  pub _tab: flatbuffers::Table<'a>,
+}
+
+impl<'a> flatbuffers::Follow<'a> for Envelope<'a> {
+  type Inner = Envelope<'a>;
+  #[inline]
+  unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+    Self { _tab: flatbuffers::Table::new(buf, loc) }
+  }
+}
+
+impl<'a> Envelope<'a> {
+  pub const VT_ID: flatbuffers::VOffsetT = 4;
+  pub const VT_PAYLOAD_TYPE: flatbuffers::VOffsetT = 6;
+  pub const VT_PAYLOAD: flatbuffers::VOffsetT = 8;
+
+  #[inline]
+  pub unsafe fn init_from_table(table: flatbuffers::Table<'a>) -> Self {
+    Envelope { _tab: table }
+  }
+  #[allow(unused_mut)]
+  pub fn create<'bldr: 'args, 'args: 'mut_bldr, 'mut_bldr, A: flatbuffers::Allocator + 'bldr>(
+    _fbb: &'mut_bldr mut flatbuffers::FlatBufferBuilder<'bldr, A>,
+    args: &'args EnvelopeArgs<'args>
+  ) -> flatbuffers::WIPOffset<Envelope<'bldr>> {
+    let mut builder = EnvelopeBuilder::new(_fbb);
+    if let Some(x) = args.payload { builder.add_payload(x); }
+    if let Some(x) = args.id { builder.add_id(x); }
+    builder.add_payload_type(args.payload_type);
+    builder.finish()
+  }
+
+  pub fn unpack(&self) -> EnvelopeT {
+    let id = self.id().map(|x| {
+      x.to_string()
+    });
+    let payload = match self.payload_type() {
+      Payload::NONE => PayloadT::NONE,
+      Payload::Text => PayloadT::Text(Box::new(
+        self.payload_as_text()
+            .expect("Invalid union table, expected `Payload::Text`.")
+            .unpack()
+      )),
+      Payload::Count => PayloadT::Count(Box::new(
+        self.payload_as_count()
+            .expect("Invalid union table, expected `Payload::Count`.")
+            .unpack()
+      )),
+      Payload::Number => PayloadT::Number(Box::new(
+        self.payload_as_number()
+            .expect("Invalid union table, expected `Payload::Number`.")
+            .unpack()
+      )),
+      Payload::Chunk => PayloadT::Chunk(Box::new(
+        self.payload_as_chunk()
+            .expect("Invalid union table, expected `Payload::Chunk`.")
+            .unpack()
+      )),
+      Payload::RpcError => PayloadT::RpcError(Box::new(
+        self.payload_as_rpc_error()
+            .expect("Invalid union table, expected `Payload::RpcError`.")
+            .unpack()
+      )),
+      _ => PayloadT::NONE,
+    };
+    EnvelopeT {
+      id,
+      payload,
+    }
+  }
+
+  #[inline]
+  pub fn id(&self) -> Option<&'a str> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<&str>>(Envelope::VT_ID, None)}
+  }
+  #[inline]
+  pub fn payload_type(&self) -> Payload {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<Payload>(Envelope::VT_PAYLOAD_TYPE, Some(Payload::NONE)).unwrap()}
+  }
+  #[inline]
+  pub fn payload(&self) -> Option<flatbuffers::Table<'a>> {
+    // Safety:
+    // Created from valid Table for this object
+    // which contains a valid value in this slot
+    unsafe { self._tab.get::<flatbuffers::ForwardsUOffset<flatbuffers::Table<'a>>>(Envelope::VT_PAYLOAD, None)}
+  }
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_text(&self) -> Option<Text<'a>> {
+    if self.payload_type() == Payload::Text {
+      self.payload().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Text::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_count(&self) -> Option<Count<'a>> {
+    if self.payload_type() == Payload::Count {
+      self.payload().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Count::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_number(&self) -> Option<Number<'a>> {
+    if self.payload_type() == Payload::Number {
+      self.payload().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Number::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_chunk(&self) -> Option<Chunk<'a>> {
+    if self.payload_type() == Payload::Chunk {
+      self.payload().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { Chunk::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  #[allow(non_snake_case)]
+  pub fn payload_as_rpc_error(&self) -> Option<RpcError<'a>> {
+    if self.payload_type() == Payload::RpcError {
+      self.payload().map(|t| {
+       // Safety:
+       // Created from a valid Table for this object
+       // Which contains a valid union in this slot
+       unsafe { RpcError::init_from_table(t) }
+     })
+    } else {
+      None
+    }
+  }
+
+}
+
+impl flatbuffers::Verifiable for Envelope<'_> {
+  #[inline]
+  fn run_verifier(
+    v: &mut flatbuffers::Verifier, pos: usize
+  ) -> Result<(), flatbuffers::InvalidFlatbuffer> {
+    use self::flatbuffers::Verifiable;
+    v.visit_table(pos)?
+     .visit_field::<flatbuffers::ForwardsUOffset<&str>>("id", Self::VT_ID, false)?
+     .visit_union::<Payload, _>("payload_type", Self::VT_PAYLOAD_TYPE, "payload", Self::VT_PAYLOAD, false, |key, v, pos| {
+        match key {
+          Payload::Text => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Text>>("Payload::Text", pos),
+          Payload::Count => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Count>>("Payload::Count", pos),
+          Payload::Number => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Number>>("Payload::Number", pos),
+          Payload::Chunk => v.verify_union_variant::<flatbuffers::ForwardsUOffset<Chunk>>("Payload::Chunk", pos),
+          Payload::RpcError => v.verify_union_variant::<flatbuffers::ForwardsUOffset<RpcError>>("Payload::RpcError", pos),
+          _ => Ok(()),
+        }
+     })?
+     .finish();
+    Ok(())
+  }
+}
+pub struct EnvelopeArgs<'a> {
+    pub id: Option<flatbuffers::WIPOffset<&'a str>>,
+    pub payload_type: Payload,
+    pub payload: Option<flatbuffers::WIPOffset<flatbuffers::UnionWIPOffset>>,
+}
+impl<'a> Default for EnvelopeArgs<'a> {
+  #[inline]
+  fn default() -> Self {
+    EnvelopeArgs {
+      id: None,
+      payload_type: Payload::NONE,
+      payload: None,
+    }
+  }
+}
+
+pub struct EnvelopeBuilder<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> {
+  fbb_: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+  start_: flatbuffers::WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+}
+impl<'a: 'b, 'b, A: flatbuffers::Allocator + 'a> EnvelopeBuilder<'a, 'b, A> {
+  #[inline]
+  pub fn add_id(&mut self, id: flatbuffers::WIPOffset<&'b  str>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(Envelope::VT_ID, id);
+  }
+  #[inline]
+  pub fn add_payload_type(&mut self, payload_type: Payload) {
+    self.fbb_.push_slot::<Payload>(Envelope::VT_PAYLOAD_TYPE, payload_type, Payload::NONE);
+  }
+  #[inline]
+  pub fn add_payload(&mut self, payload: flatbuffers::WIPOffset<flatbuffers::UnionWIPOffset>) {
+    self.fbb_.push_slot_always::<flatbuffers::WIPOffset<_>>(Envelope::VT_PAYLOAD, payload);
+  }
+  #[inline]
+  pub fn new(_fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>) -> EnvelopeBuilder<'a, 'b, A> {
+    let start = _fbb.start_table();
+    EnvelopeBuilder {
+      fbb_: _fbb,
+      start_: start,
+    }
+  }
+  #[inline]
+  pub fn finish(self) -> flatbuffers::WIPOffset<Envelope<'a>> {
+    let o = self.fbb_.end_table(self.start_);
+    flatbuffers::WIPOffset::new(o.value())
+  }
+}
+
+impl core::fmt::Debug for Envelope<'_> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let mut ds = f.debug_struct("Envelope");
+      ds.field("id", &self.id());
+      ds.field("payload_type", &self.payload_type());
+      match self.payload_type() {
+        Payload::Text => {
+          if let Some(x) = self.payload_as_text() {
+            ds.field("payload", &x)
+          } else {
+            ds.field("payload", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Payload::Count => {
+          if let Some(x) = self.payload_as_count() {
+            ds.field("payload", &x)
+          } else {
+            ds.field("payload", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Payload::Number => {
+          if let Some(x) = self.payload_as_number() {
+            ds.field("payload", &x)
+          } else {
+            ds.field("payload", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Payload::Chunk => {
+          if let Some(x) = self.payload_as_chunk() {
+            ds.field("payload", &x)
+          } else {
+            ds.field("payload", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        Payload::RpcError => {
+          if let Some(x) = self.payload_as_rpc_error() {
+            ds.field("payload", &x)
+          } else {
+            ds.field("payload", &"InvalidFlatbuffer: Union discriminant does not match value.")
+          }
+        },
+        _ => {
+          let x: Option<()> = None;
+          ds.field("payload", &x)
+        },
+      };
+      ds.finish()
+  }
+}
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvelopeT {
+  pub id: Option<String>,
+  pub payload: PayloadT,
+}
+impl Default for EnvelopeT {
+  fn default() -> Self {
+    Self {
+      id: None,
+      payload: PayloadT::NONE,
+    }
+  }
+}
+impl EnvelopeT {
+  pub fn pack<'b, A: flatbuffers::Allocator + 'b>(
+    &self,
+    _fbb: &mut flatbuffers::FlatBufferBuilder<'b, A>
+  ) -> flatbuffers::WIPOffset<Envelope<'b>> {
+    let id = self.id.as_ref().map(|x|{
+      _fbb.create_string(x)
+    });
+    let payload_type = self.payload.payload_type();
+    let payload = self.payload.pack(_fbb);
+    Envelope::create(_fbb, &EnvelopeArgs{
+      id,
+      payload_type,
+      payload,
+    })
+  }
+}
+#[inline]
+/// Verifies that a buffer of bytes contains a `Envelope`
+/// and returns it.
+/// Note that verification is still experimental and may not
+/// catch every error, or be maximally performant. For the
+/// previous, unchecked, behavior use
+/// `root_as_envelope_unchecked`.
+pub fn root_as_envelope(buf: &[u8]) -> Result<Envelope, flatbuffers::InvalidFlatbuffer> {
+  flatbuffers::root::<Envelope>(buf)
+}
+#[inline]
+/// Verifies that a buffer of bytes contains a size prefixed
+/// `Envelope` and returns it.
+/// Note that verification is still experimental and may not
+/// catch every error, or be maximally performant. For the
+/// previous, unchecked, behavior use
+/// `size_prefixed_root_as_envelope_unchecked`.
+pub fn size_prefixed_root_as_envelope(buf: &[u8]) -> Result<Envelope, flatbuffers::InvalidFlatbuffer> {
+  flatbuffers::size_prefixed_root::<Envelope>(buf)
+}
+#[inline]
+/// Verifies, with the given options, that a buffer of bytes
+/// contains a `Envelope` and returns it.
+/// Note that verification is still experimental and may not
+/// catch every error, or be maximally performant. For the
+/// previous, unchecked, behavior use
+/// `root_as_envelope_unchecked`.
+pub fn root_as_envelope_with_opts<'b, 'o>(
+  opts: &'o flatbuffers::VerifierOptions,
+  buf: &'b [u8],
+) -> Result<Envelope<'b>, flatbuffers::InvalidFlatbuffer> {
+  flatbuffers::root_with_opts::<Envelope<'b>>(opts, buf)
+}
+#[inline]
+/// Verifies, with the given verifier options, that a buffer of
+/// bytes contains a size prefixed `Envelope` and returns
+/// it. Note that verification is still experimental and may not
+/// catch every error, or be maximally performant. For the
+/// previous, unchecked, behavior use
+/// `root_as_envelope_unchecked`.
+pub fn size_prefixed_root_as_envelope_with_opts<'b, 'o>(
+  opts: &'o flatbuffers::VerifierOptions,
+  buf: &'b [u8],
+) -> Result<Envelope<'b>, flatbuffers::InvalidFlatbuffer> {
+  flatbuffers::size_prefixed_root_with_opts::<Envelope<'b>>(opts, buf)
+}
+#[inline]
+/// Assumes, without verification, that a buffer of bytes contains a Envelope and returns it.
+/// # Safety
+/// Callers must trust the given bytes do indeed contain a valid `Envelope`.
+pub unsafe fn root_as_envelope_unchecked(buf: &[u8]) -> Envelope {
+  flatbuffers::root_unchecked::<Envelope>(buf)
+}
+#[inline]
+/// Assumes, without verification, that a buffer of bytes contains a size prefixed Envelope and returns it.
+/// # Safety
+/// Callers must trust the given bytes do indeed contain a valid size prefixed `Envelope`.
+pub unsafe fn size_prefixed_root_as_envelope_unchecked(buf: &[u8]) -> Envelope {
+  flatbuffers::size_prefixed_root_unchecked::<Envelope>(buf)
+}
+#[inline]
+pub fn finish_envelope_buffer<'a, 'b, A: flatbuffers::Allocator + 'a>(
+    fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>,
+    root: flatbuffers::WIPOffset<Envelope<'a>>) {
+  fbb.finish(root, None);
+}
+
+#[inline]
+pub fn finish_size_prefixed_envelope_buffer<'a, 'b, A: flatbuffers::Allocator + 'a>(fbb: &'b mut flatbuffers::FlatBufferBuilder<'a, A>, root: flatbuffers::WIPOffset<Envelope<'a>>) {
+  fbb.finish_size_prefixed(root, None);
+}
@@ -0,0 +1,159 @@
+#![cfg(feature = "http")]
+
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Bytes;
+use hyper::client::conn::http1::{self, SendRequest};
+use hyper::header::CONTENT_TYPE;
+use hyper::{Request, StatusCode};
+use hyper_util::rt::TokioIo;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::timeout;
+use flat_rpc_runtime::{serve_http, FLATBUFFERS_CONTENT_TYPE, MAX_FRAME_LENGTH};
+
+use common::rpc_client::{frame_id, ChunkContent, CountContent};
+use common::rpc_server::RpcServer;
+use common::{schema_generated, text_frame, text_of, Handler};
+
+
+const PATIENCE: Duration = Duration::from_secs(5);
+
+async fn http_server() -> String {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap().to_string();
+  tokio::spawn(serve_http(listener, Arc::new(RpcServer(Handler::default()))));
+  addr
+}
+
+async fn connect(addr: &str) -> SendRequest<Full<Bytes>> {
+  let stream = TcpStream::connect(addr).await.unwrap();
+  let (sender, connection) = http1::handshake(TokioIo::new(stream)).await.unwrap();
+  tokio::spawn(connection);
+  sender
+}
+
+fn post(path: &str, body: Vec<u8>) -> Request<Full<Bytes>> {
+  Request::post(path)
+    .header(CONTENT_TYPE, FLATBUFFERS_CONTENT_TYPE)
+    .body(Full::new(Bytes::from(body)))
+    .unwrap()
+}
+
+async fn call(sender: &mut SendRequest<Full<Bytes>>, request: Request<Full<Bytes>>) -> (StatusCode, Vec<u8>) {
+  let response = timeout(PATIENCE, sender.send_request(request)).await.unwrap().unwrap();
+  let status = response.status();
+  (status, response.into_body().collect().await.unwrap().to_bytes().to_vec())
+}
+
+
+#[tokio::test]
+async fn requests_on_one_connection_are_answered_in_turn() {
+  let addr = http_server().await;
+  let mut sender = connect(&addr).await;
+
+  for n in 0..16 {
+    let id = format!("request-{n}");
+    let (status, response) = call(&mut sender, post("/Test/Echo", text_frame(&id, &n.to_string(), 0))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!((frame_id(&response).unwrap(), text_of(&response)), (id, n.to_string()));
+  }
+}
+
+#[tokio::test]
+async fn a_slow_call_holds_up_only_its_own_connection() {
+  let addr = http_server().await;
+  let mut slow_sender = connect(&addr).await;
+  let slow = tokio::spawn(async move { call(&mut slow_sender, post("/Test/Echo", text_frame("slow", "slow", 500))).await });
+  tokio::time::sleep(Duration::from_millis(50)).await;
+
+  let mut fast_sender = connect(&addr).await;
+  let (_, fast) = timeout(Duration::from_millis(300), call(&mut fast_sender, post("/Test/Echo", text_frame("fast", "fast", 0)))).await
+    .expect("a slow call held up another connection");
+  assert_eq!(text_of(&fast), "fast");
+  assert!(!slow.is_finished());
+
+  let (_, slow) = timeout(PATIENCE, slow).await.unwrap().unwrap();
+  assert_eq!(text_of(&slow), "slow");
+}
+
+#[tokio::test]
+async fn requests_that_are_not_frames_are_refused() {
+  let addr = http_server().await;
+  let mut sender = connect(&addr).await;
+
+  let get = Request::get("/Test/Echo").body(Full::default()).unwrap();
+  assert_eq!(call(&mut sender, get).await.0, StatusCode::METHOD_NOT_ALLOWED);
+
+  let json = Request::post("/Test/Echo").header(CONTENT_TYPE, "application/json").body(Full::new(Bytes::from("{}"))).unwrap();
+  assert_eq!(call(&mut sender, json).await.0, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+  let too_large = post("/Test/Echo", vec![0; MAX_FRAME_LENGTH + 1]);
+  assert_eq!(call(&mut sender, too_large).await.0, StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn the_path_must_name_the_method_the_payload_calls() {
+  let addr = http_server().await;
+  let mut sender = connect(&addr).await;
+
+  let (status, message) = call(&mut sender, post("/Test/Missing", text_frame("unknown", "text", 0))).await;
+  assert_eq!((status, String::from_utf8(message).unwrap()), (StatusCode::NOT_FOUND, "there is no method Test/Missing".to_owned()));
+
+  let (status, _) = call(&mut sender, post("/Echo", text_frame("short", "text", 0))).await;
+  assert_eq!(status, StatusCode::NOT_FOUND);
+
+  let (status, message) = call(&mut sender, post("/Test/Count", text_frame("other", "text", 0))).await;
+  assert_eq!((status, String::from_utf8(message).unwrap()), (StatusCode::BAD_REQUEST, "payload doesn't call Test/Count".to_owned()));
+
+  let (status, response) = call(&mut sender, post("/rpc/v1/Test/Echo", text_frame("prefixed", "text", 0))).await;
+  assert_eq!((status, text_of(&response)), (StatusCode::OK, "text".to_owned()));
+}
+
+#[tokio::test]
+async fn streaming_calls_are_refused() {
+  let addr = http_server().await;
+  let mut sender = connect(&addr).await;
+
+  let count = CountContent { id: "responses".to_owned(), n: 3, ..Default::default() };
+  let (status, message) = call(&mut sender, post("/Test/Count", count.into())).await;
+  assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+  assert_eq!(String::from_utf8(message).unwrap(), "response streams need a socket transport");
+
+  let chunk = ChunkContent { id: "requests".to_owned(), ..Default::default() };
+  let (status, message) = call(&mut sender, post("/Test/Gather", chunk.into())).await;
+  assert_eq!(status, StatusCode::BAD_REQUEST);
+  assert_eq!(String::from_utf8(message).unwrap(), "request streams need a socket transport");
+
+  let (status, response) = call(&mut sender, post("/Test/Echo", text_frame("after", "plain", 0))).await;
+  assert_eq!((status, text_of(&response)), (StatusCode::OK, "plain".to_owned()));
+}
+
+#[tokio::test]
+async fn a_failing_call_is_answered_with_its_error_frame() {
+  let addr = http_server().await;
+  let mut sender = connect(&addr).await;
+
+  let (status, response) = call(&mut sender, post("/Test/Echo", text_frame("empty", "", 0))).await;
+  assert_eq!((status, text_of(&response)), (StatusCode::OK, "400: nothing to echo".to_owned()));
+}
+
+#[tokio::test]
+async fn a_connection_cut_mid_body_ends_only_that_connection() {
+  let addr = http_server().await;
+
+  let mut stream = TcpStream::connect(&addr).await.unwrap();
+  let head = format!("POST /Test/echo HTTP/1.1\r\nHost: localhost\r\nContent-Type: {FLATBUFFERS_CONTENT_TYPE}\r\nContent-Length: 100\r\n\r\n");
+  stream.write_all(head.as_bytes()).await.unwrap();
+  stream.write_all(b"cut:echo").await.unwrap();
+  stream.shutdown().await.unwrap();
+  let mut rest = vec![];
+  timeout(PATIENCE, stream.read_to_end(&mut rest)).await.unwrap().unwrap();
+
+  let mut sender = connect(&addr).await;
+  let (status, response) = call(&mut sender, post("/Test/Echo", text_frame("next", "still serving", 0))).await;
+  assert_eq!((status, text_of(&response)), (StatusCode::OK, "still serving".to_owned()));
+}
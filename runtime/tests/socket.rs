@@ -0,0 +1,163 @@
+mod common;
+
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, UnixListener, UnixStream};
+use tokio::time::timeout;
+use flat_rpc_runtime::{connect_tcp, connect_unix, read_frame, serve_connection, serve_tcp, serve_unix, StreamTransport, Transport};
+
+use common::rpc_client::{frame_id, TestClient, TextContent};
+use common::rpc_server::RpcServer;
+use common::{schema_generated, socket_path, text_frame, text_of, Handler};
+
+
+const PATIENCE: Duration = Duration::from_secs(5);
+
+async fn tcp_server() -> String {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap().to_string();
+  tokio::spawn(serve_tcp(listener, Arc::new(RpcServer(Handler::default()))));
+  addr
+}
+
+fn unix_server(name: &str) -> std::path::PathBuf {
+  let path = socket_path(name);
+  let listener = UnixListener::bind(&path).unwrap();
+  tokio::spawn(serve_unix(listener, Arc::new(RpcServer(Handler::default()))));
+  path
+}
+
+async fn echo(client: &TestClient<StreamTransport>, value: &str, delay_ms: u32) -> String {
+  let response = client.echo(TextContent { value: value.to_owned(), delay_ms, ..Default::default() }).await.unwrap();
+  response.value
+}
+
+async fn send(transport: &StreamTransport, id: &str, value: &str) -> io::Result<String> {
+  let response = transport.send_message(text_frame(id, value, 0), id.to_owned()).await?;
+  assert_eq!(frame_id(&response).as_deref(), Some(id));
+  Ok(text_of(&response))
+}
+
+async fn requests_are_matched_by_id(transport: StreamTransport) {
+  let client = Arc::new(TestClient::new(transport));
+  let requests = (0..64).map(|n| {
+    let client = client.clone();
+    tokio::spawn(async move { echo(&client, &n.to_string(), 0).await })
+  }).collect::<Vec<_>>();
+
+  for (n, request) in requests.into_iter().enumerate() {
+    assert_eq!(timeout(PATIENCE, request).await.unwrap().unwrap(), n.to_string());
+  }
+}
+
+/** The slow call is answered last although it was sent first */
+async fn responses_are_sent_when_ready(transport: StreamTransport) {
+  let client = Arc::new(TestClient::new(transport));
+  let slow = tokio::spawn({
+    let client = client.clone();
+    async move { echo(&client, "slow", 500).await }
+  });
+  tokio::time::sleep(Duration::from_millis(50)).await;
+
+  let fast = timeout(Duration::from_millis(300), echo(&client, "fast", 0)).await;
+  assert_eq!(fast.expect("a slow call held up the connection"), "fast");
+  assert!(!slow.is_finished());
+  assert_eq!(timeout(PATIENCE, slow).await.unwrap().unwrap(), "slow");
+}
+
+/** A length prefix promising more bytes than the peer sends before it's gone */
+async fn write_truncated_frame(stream: &mut (impl AsyncWrite + Unpin)) {
+  stream.write_u32(100).await.unwrap();
+  stream.write_all(b"cut:echo").await.unwrap();
+  stream.shutdown().await.unwrap();
+}
+
+/** The truncated frame fails only its own connection, the next one is served */
+async fn truncated_request_ends_its_connection<IO: AsyncRead + AsyncWrite + Unpin>(mut stream: IO, next: StreamTransport) {
+  write_truncated_frame(&mut stream).await;
+  let mut rest = vec![];
+  assert_eq!(timeout(PATIENCE, stream.read_to_end(&mut rest)).await.unwrap().unwrap(), 0);
+
+  assert_eq!(send(&next, "next", "still serving").await.unwrap(), "still serving");
+}
+
+
+#[tokio::test]
+async fn tcp_requests_on_one_connection_are_matched_by_id() {
+  let addr = tcp_server().await;
+  requests_are_matched_by_id(connect_tcp(addr, frame_id).await.unwrap()).await;
+}
+
+#[tokio::test]
+async fn unix_requests_on_one_connection_are_matched_by_id() {
+  let path = unix_server("matched");
+  requests_are_matched_by_id(connect_unix(path, frame_id).await.unwrap()).await;
+}
+
+#[tokio::test]
+async fn tcp_responses_are_sent_when_ready() {
+  let addr = tcp_server().await;
+  responses_are_sent_when_ready(connect_tcp(addr, frame_id).await.unwrap()).await;
+}
+
+#[tokio::test]
+async fn unix_responses_are_sent_when_ready() {
+  let path = unix_server("ready");
+  responses_are_sent_when_ready(connect_unix(path, frame_id).await.unwrap()).await;
+}
+
+#[tokio::test]
+async fn tcp_request_cut_mid_frame_ends_only_its_connection() {
+  let addr = tcp_server().await;
+  let stream = TcpStream::connect(&addr).await.unwrap();
+  truncated_request_ends_its_connection(stream, connect_tcp(&addr, frame_id).await.unwrap()).await;
+}
+
+#[tokio::test]
+async fn unix_request_cut_mid_frame_ends_only_its_connection() {
+  let path = unix_server("cut");
+  let stream = UnixStream::connect(&path).await.unwrap();
+  truncated_request_ends_its_connection(stream, connect_unix(&path, frame_id).await.unwrap()).await;
+}
+
+#[tokio::test]
+async fn serving_a_connection_cut_mid_frame_fails_after_answering_earlier_requests() {
+  let (client, server) = tokio::io::duplex(1024);
+  let serving = tokio::spawn(serve_connection(server, Arc::new(RpcServer(Handler::default()))));
+  let (mut reader, mut writer) = tokio::io::split(client);
+
+  let request = text_frame("early", "answered", 100);
+  writer.write_u32(request.len() as u32).await.unwrap();
+  writer.write_all(&request).await.unwrap();
+  write_truncated_frame(&mut writer).await;
+
+  let error = timeout(PATIENCE, serving).await.unwrap().unwrap().unwrap_err();
+  assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+  let response = read_frame(&mut reader).await.unwrap().unwrap();
+  assert_eq!(text_of(&response), "answered");
+}
+
+#[tokio::test]
+async fn response_cut_mid_frame_fails_the_requests_in_flight() {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  tokio::spawn(async move {
+    let (mut stream, _) = listener.accept().await.unwrap();
+    read_frame(&mut stream).await.unwrap();
+    read_frame(&mut stream).await.unwrap();
+    write_truncated_frame(&mut stream).await;
+  });
+
+  let transport = Arc::new(connect_tcp(addr, frame_id).await.unwrap());
+  let first = tokio::spawn({
+    let transport = transport.clone();
+    async move { send(&transport, "first", "1").await }
+  });
+  let second = timeout(PATIENCE, send(&transport, "second", "2")).await.unwrap();
+
+  assert_eq!(second.unwrap_err().kind(), io::ErrorKind::ConnectionAborted);
+  assert_eq!(timeout(PATIENCE, first).await.unwrap().unwrap().unwrap_err().kind(), io::ErrorKind::ConnectionAborted);
+  assert_eq!(transport.send_message(text_frame("late", "3", 0), "late".to_owned()).await.unwrap_err().kind(), io::ErrorKind::ConnectionAborted);
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use flat_rpc_runtime::{connect_tcp, serve_tcp, StreamTransport, Transport, STREAM_BUFFER};
+
+use common::rpc_client::{frame_id, ChunkContent, CountContent, NumberContent, ResponseStream, RpcError, TestClient, TextContent};
+use common::rpc_server::{self, RpcServer};
+use common::{end_frame, schema_generated, text_of, Handler};
+
+
+const PATIENCE: Duration = Duration::from_secs(5);
+
+async fn connect(handler: Handler) -> TestClient<StreamTransport> {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  tokio::spawn(serve_tcp(listener, Arc::new(RpcServer(handler))));
+  TestClient::new(connect_tcp(addr, frame_id).await.unwrap())
+}
+
+/** A client whose server reports what each `Gather` call came to */
+async fn connect_gathering() -> (TestClient<StreamTransport>, mpsc::UnboundedReceiver<Result<u32, rpc_server::RpcError>>) {
+  let (gathered, reported) = mpsc::unbounded_channel();
+  (connect(Handler { gathered: Some(gathered) }).await, reported)
+}
+
+async fn next(responses: &mut ResponseStream<NumberContent>) -> Option<Result<u32, RpcError>> {
+  timeout(PATIENCE, responses.next()).await.unwrap().map(|number| number.map(|number| number.n))
+}
+
+fn chunk(id: &str, take: u32, hold_ms: u32) -> ChunkContent {
+  ChunkContent { id: id.to_owned(), take, hold_ms }
+}
+
+async fn fast_echo(client: &TestClient<StreamTransport>) -> Result<TextContent, RpcError> {
+  let fast = timeout(Duration::from_millis(200), client.echo(TextContent { value: "fast".to_owned(), ..Default::default() })).await;
+  fast.expect("a full stream held up the connection")
+}
+
+
+#[tokio::test]
+async fn a_request_stream_goes_to_its_call_up_to_the_end() {
+  let client = connect(Handler::default()).await;
+  let mut call = client.gather(chunk("gathered", 0, 0)).await.unwrap();
+  for _ in 0..2 {
+    call.send(chunk("", 0, 0)).await.unwrap();
+  }
+
+  assert_eq!(timeout(PATIENCE, call.finish()).await.unwrap().unwrap().n, 3);
+}
+
+#[tokio::test]
+async fn a_call_returning_first_drops_the_rest_of_its_stream_and_frees_its_id() {
+  let client = connect(Handler::default()).await;
+  let mut call = client.gather(chunk("early", 1, 0)).await.unwrap();
+  tokio::time::sleep(Duration::from_millis(100)).await;
+
+  // the rest would start calls answering the next one with this id if it wasn't dropped
+  for _ in 0..2 {
+    call.send(chunk("", 0, 0)).await.unwrap();
+  }
+  assert_eq!(timeout(PATIENCE, call.finish()).await.unwrap().unwrap().n, 1);
+  tokio::time::sleep(Duration::from_millis(100)).await;
+
+  let mut call = client.gather(chunk("early", 2, 0)).await.unwrap();
+  call.send(chunk("", 0, 0)).await.unwrap();
+  assert_eq!(timeout(PATIENCE, call.finish()).await.unwrap().unwrap().n, 2);
+}
+
+#[tokio::test]
+async fn a_dropped_request_stream_is_cancelled() {
+  let (client, mut reported) = connect_gathering().await;
+  let mut call = client.gather(chunk("dropped", 0, 0)).await.unwrap();
+  call.send(chunk("", 0, 0)).await.unwrap();
+  drop(call);
+
+  let error = timeout(PATIENCE, reported.recv()).await.unwrap().unwrap().unwrap_err();
+  assert_eq!((error.code, error.details.as_str()), (rpc_server::RpcError::CANCELLED, "after 2 chunks"));
+}
+
+#[tokio::test]
+async fn an_end_without_an_open_stream_is_answered() {
+  let client = connect(Handler::default()).await;
+  let response = timeout(PATIENCE, client.transport.send_message(end_frame("orphan"), "orphan".to_owned())).await.unwrap().unwrap();
+  assert_eq!(text_of(&response), "400: no request stream is open with id \"orphan\"");
+}
+
+#[tokio::test]
+async fn a_request_stream_falling_behind_is_cut_off_without_holding_up_the_connection() {
+  let (client, mut reported) = connect_gathering().await;
+  let mut call = client.gather(chunk("held", 0, 300)).await.unwrap();
+  for _ in 0..STREAM_BUFFER + 8 {
+    call.send(chunk("", 0, 0)).await.unwrap();
+  }
+
+  assert_eq!(fast_echo(&client).await.unwrap().value, "fast");
+  let error = timeout(PATIENCE, call.finish()).await.unwrap().err().expect("a stream cut off fails its call");
+  assert_eq!(error.code, RpcError::UNAVAILABLE);
+  let error = timeout(PATIENCE, reported.recv()).await.unwrap().unwrap().unwrap_err();
+  assert_eq!(error.details, format!("after {} chunks", STREAM_BUFFER + 1));
+}
+
+#[tokio::test]
+async fn a_response_stream_falling_behind_is_cut_off_without_holding_up_the_connection() {
+  let client = connect(Handler::default()).await;
+  let count = CountContent { n: STREAM_BUFFER as u32 * 2, ..Default::default() };
+  let mut responses = client.count(count).await.unwrap();
+  tokio::time::sleep(Duration::from_millis(100)).await;
+
+  assert_eq!(fast_echo(&client).await.unwrap().value, "fast");
+
+  for n in 0..STREAM_BUFFER as u32 {
+    assert_eq!(next(&mut responses).await.unwrap().unwrap(), n);
+  }
+  let error = next(&mut responses).await.unwrap().unwrap_err();
+  assert_eq!(error.code, RpcError::UNAVAILABLE);
+  assert!(next(&mut responses).await.is_none());
+}
+
+#[tokio::test]
+async fn a_response_stream_filling_its_buffer_is_complete() {
+  let client = connect(Handler::default()).await;
+  let count = CountContent { n: STREAM_BUFFER as u32 - 1, ..Default::default() };
+  let mut responses = client.count(count).await.unwrap();
+  tokio::time::sleep(Duration::from_millis(100)).await;
+
+  for n in 0..STREAM_BUFFER as u32 - 1 {
+    assert_eq!(next(&mut responses).await.unwrap().unwrap(), n);
+  }
+  assert!(next(&mut responses).await.is_none());
+}
+
+#[tokio::test]
+async fn a_failing_response_stream_ends_with_its_error() {
+  let client = connect(Handler::default()).await;
+  let mut responses = client.count(CountContent { n: 2, fail: true, ..Default::default() }).await.unwrap();
+
+  assert_eq!(next(&mut responses).await.unwrap().unwrap(), 0);
+  assert_eq!(next(&mut responses).await.unwrap().unwrap(), 1);
+  let error = next(&mut responses).await.unwrap().unwrap_err();
+  assert_eq!((error.code, error.message.as_str()), (RpcError::INTERNAL, "failed after 2"));
+  assert!(next(&mut responses).await.is_none());
+}
+
+#[tokio::test]
+async fn a_failing_call_is_answered_with_its_error() {
+  let client = connect(Handler::default()).await;
+  let error = timeout(PATIENCE, client.echo(TextContent::default())).await.unwrap().err().expect("an empty text can't be echoed");
+  assert_eq!((error.code, error.message.as_str()), (RpcError::BAD_REQUEST, "nothing to echo"));
+}
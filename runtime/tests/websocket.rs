@@ -0,0 +1,111 @@
+#![cfg(feature = "websocket")]
+
+mod common;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use futures_util::{SinkExt, StreamExt};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use flat_rpc_runtime::serve_websocket;
+
+use common::rpc_client::frame_id;
+use common::rpc_server::RpcServer;
+use common::{schema_generated, text_frame, text_of, Handler};
+
+
+const PATIENCE: Duration = Duration::from_secs(5);
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn websocket_server() -> String {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap().to_string();
+  tokio::spawn(serve_websocket(listener, Arc::new(RpcServer(Handler::default()))));
+  addr
+}
+
+async fn connect(addr: &str) -> Socket {
+  tokio_tungstenite::connect_async(format!("ws://{addr}")).await.unwrap().0
+}
+
+/** The next binary message as `(id, text)` */
+async fn receive(socket: &mut Socket) -> (String, String) {
+  loop {
+    match timeout(PATIENCE, socket.next()).await.unwrap().unwrap().unwrap() {
+      Message::Binary(response) => return (frame_id(&response).unwrap(), text_of(&response)),
+      _ => continue,
+    }
+  }
+}
+
+
+#[tokio::test]
+async fn requests_on_one_socket_are_matched_by_id() {
+  let addr = websocket_server().await;
+  let mut socket = connect(&addr).await;
+
+  for n in 0..64 {
+    socket.send(Message::binary(text_frame(&format!("request-{n}"), &n.to_string(), 0))).await.unwrap();
+  }
+  let mut responses = HashMap::new();
+  for _ in 0..64 {
+    let (id, answer) = receive(&mut socket).await;
+    responses.insert(id, answer);
+  }
+
+  for n in 0..64 {
+    assert_eq!(responses[&format!("request-{n}")], n.to_string());
+  }
+}
+
+#[tokio::test]
+async fn responses_are_sent_when_ready() {
+  let addr = websocket_server().await;
+  let mut socket = connect(&addr).await;
+
+  socket.send(Message::binary(text_frame("slow", "slow", 300))).await.unwrap();
+  socket.send(Message::binary(text_frame("fast", "fast", 0))).await.unwrap();
+
+  assert_eq!(receive(&mut socket).await, ("fast".to_owned(), "fast".to_owned()));
+  assert_eq!(receive(&mut socket).await, ("slow".to_owned(), "slow".to_owned()));
+}
+
+#[tokio::test]
+async fn text_messages_are_ignored() {
+  let addr = websocket_server().await;
+  let mut socket = connect(&addr).await;
+
+  socket.send(Message::text("not a frame")).await.unwrap();
+  socket.send(Message::binary(text_frame("after", "binary", 0))).await.unwrap();
+
+  assert_eq!(receive(&mut socket).await, ("after".to_owned(), "binary".to_owned()));
+}
+
+#[tokio::test]
+async fn a_socket_cut_mid_message_ends_only_its_connection() {
+  let addr = websocket_server().await;
+
+  // half of a handshake
+  let mut stream = TcpStream::connect(&addr).await.unwrap();
+  stream.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nUpgrade: webs").await.unwrap();
+  drop(stream);
+
+  // a binary frame header promising 126 bytes, after a call was started
+  let mut socket = connect(&addr).await;
+  socket.send(Message::binary(text_frame("orphan", "orphan", 100))).await.unwrap();
+  let mut stream = match socket.into_inner() {
+    MaybeTlsStream::Plain(stream) => stream,
+    _ => unreachable!(),
+  };
+  stream.write_all(&[0x82, 0xfe, 0x00, 0x7e, 0, 0, 0, 0, b'c', b'u', b't']).await.unwrap();
+  drop(stream);
+
+  let mut next = connect(&addr).await;
+  next.send(Message::binary(text_frame("next", "still serving", 0))).await.unwrap();
+  assert_eq!(receive(&mut next).await, ("next".to_owned(), "still serving".to_owned()));
+}
@@ -1,112 +1,758 @@
 use convert_case::{Case, Casing};
 use quote::{quote, format_ident};
-use crate::parser::{DeclType, Statements};
-use crate::ir::{TableDeclaration, RpcDeclaration, Type, ValueType, StructDeclaration};
+use crate::parser::{DeclType, Statements, RPC_ERROR};
+use crate::ir::{unqualified, TableDeclaration, RpcDeclaration, Type, ValueType, DefaultValue, StructDeclaration, EnumDeclaration, EnumValue, UnionDeclaration, Streaming};
+
+
+/** Derives added to every content type */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Derive {
+  Debug,
+  Clone,
+  PartialEq,
+  Serialize,
+  Deserialize,
+}
+
+impl Derive {
+  pub fn path(&self) -> &'static str {
+    match self {
+      Derive::Debug => "Debug",
+      Derive::Clone => "Clone",
+      Derive::PartialEq => "PartialEq",
+      Derive::Serialize => "serde::Serialize",
+      Derive::Deserialize => "serde::Deserialize",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RsOptions {
+  pub derives: Vec<Derive>,
+}
 
+impl RsOptions {
+  pub fn has_derive(&self, derive: Derive) -> bool {
+    self.derives.contains(&derive)
+  }
 
-pub fn generate_rust_server_side_code(statements: &Statements) -> String {
-  let mut generated = vec![generate_header(statements)];
+  pub fn has_serde(&self) -> bool {
+    self.has_derive(Derive::Serialize) || self.has_derive(Derive::Deserialize)
+  }
+}
+
+
+pub fn generate_rust_server_side_code(statements: &Statements, options: &RsOptions) -> String {
+  let mut generated = vec![generate_header(statements), generate_rpc_error_type(statements, options), generate_fixed_array_serde(statements, options)];
+
+  for enum_decl in statements.enum_declarations.values() {
+    generated.push(enum_decl.generate_discriminant_check());
+    generated.push(enum_decl.generate_serde_impl(statements, options));
+  }
+  for union in statements.unions.values() {
+    generated.push(union.generate_discriminant_check());
+  }
+
+  for union in statements.unions.values() {
+    generated.push(union.to_rs_string(statements, options));
+  }
 
   for struct_def in statements.struct_declaration.values() {
-    generated.push(struct_def.to_rs_string(statements));
-    generated.push(struct_def.generate_into_protocol_struct_impl());
+    generated.push(struct_def.to_rs_string(statements, options));
+    generated.push(struct_def.generate_default_impl(statements));
+    generated.push(struct_def.generate_into_protocol_struct_impl(statements));
+    generated.push(struct_def.generate_from_protocol_impl(statements));
   };
   for interface in statements.table_declaration.values() {
-    generated.push(interface.to_rs_string(statements));
+    generated.push(interface.to_rs_string(statements, options));
+    generated.push(interface.generate_default_impl(statements));
     generated.push(interface.generate_into_offset_impl(statements));
     generated.push(interface.generate_into_byte_vec_impl(statements));
+    generated.push(interface.generate_from_protocol_impl(statements));
   }
+  generated.push(generate_request_types(statements, options));
   for rpc in statements.rpc_declarations.values() {
-    generated.push(rpc.to_rs_string(statements));
+    generated.push(rpc.to_rs_string(statements, options));
   }
   generated.push(generate_process_request_fn(statements));
+  if statements.has_streaming_requests() {
+    generated.push(generate_request_stream_fns(statements));
+  }
+  generated.push(generate_process_request_frames_fn(statements));
+  generated.push(generate_service_impl(statements));
   generated.join("\n")
 }
 
 
-pub fn generate_header(statements: &Statements) -> String {
-  let mut imports = vec![];
+pub fn generate_rust_client_side_code(statements: &Statements, options: &RsOptions) -> String {
+  let mut generated = vec![generate_header(statements), generate_rpc_error_type(statements, options), generate_fixed_array_serde(statements, options)];
 
-  for table_declaration in statements.table_declaration.values() {
-    imports.push(table_declaration.name.to_owned());
-    imports.push(format!("{}Args", table_declaration.name));
-    imports.push(format!("{}T", table_declaration.name));
+  for enum_decl in statements.enum_declarations.values() {
+    generated.push(enum_decl.generate_discriminant_check());
+    generated.push(enum_decl.generate_serde_impl(statements, options));
+  }
+  for union in statements.unions.values() {
+    generated.push(union.generate_discriminant_check());
   }
 
-  for struct_declaration in statements.struct_declaration.values() {
-    imports.push(struct_declaration.name.to_owned());
-    imports.push(format!("{}T", struct_declaration.name));
+  for union in statements.unions.values() {
+    generated.push(union.to_rs_string(statements, options));
   }
 
-  for enum_declaration in statements.enum_declarations.values() {
-    imports.push(enum_declaration.name.to_owned());
+  for struct_def in statements.struct_declaration.values() {
+    generated.push(struct_def.to_rs_string(statements, options));
+    generated.push(struct_def.generate_default_impl(statements));
+    generated.push(struct_def.generate_into_protocol_struct_impl(statements));
+    generated.push(struct_def.generate_from_protocol_impl(statements));
+  };
+  for interface in statements.table_declaration.values() {
+    generated.push(interface.to_rs_string(statements, options));
+    generated.push(interface.generate_default_impl(statements));
+    generated.push(interface.generate_into_offset_impl(statements));
+    generated.push(interface.generate_into_byte_vec_impl(statements));
+    generated.push(interface.generate_from_protocol_impl(statements));
+  }
+  generated.push(generate_transport_trait(statements));
+  generated.push(generate_request_id_fn());
+  if statements.has_streaming() {
+    generated.push(generate_response_stream_type());
   }
+  if statements.has_streaming_requests() {
+    generated.push(generate_error_frame_fn(statements, options));
+    generated.push(generate_end_of_stream_frame_fn(statements));
+    generated.push(generate_request_sink_type());
+  }
+  for rpc in statements.rpc_declarations.values() {
+    generated.push(rpc.generate_client_impl(statements));
+  }
+  generated.join("\n")
+}
 
 
-  for name in statements.unions.keys() {
-    imports.push(name.to_owned());
+/** flatc nests generated code into a module per namespace segment, `com.acme.storage` becomes `com::acme::storage` */
+pub fn rust_module_path(namespace: Option<&str>) -> String {
+  let mut path = vec!["crate".to_owned(), "schema_generated".to_owned()];
+  if let Some(namespace) = namespace {
+    path.extend(namespace.split('.').map(|segment| segment.to_case(Case::Snake)));
   }
+  path.join("::")
+}
+
+pub fn generate_header(statements: &Statements) -> String {
+  let declarations = statements.table_declaration.keys()
+    .chain(statements.struct_declaration.keys())
+    .chain(statements.enum_declarations.keys())
+    .chain(statements.unions.keys());
+  let root_type_name = statements.root_type_name.as_ref().unwrap();
+
+  let mut uses = vec![];
+  for (namespace, names) in statements.group_by_namespace(declarations) {
+    let mut imports = vec![];
+
+    for qualified_name in names {
+      let name = unqualified(qualified_name);
+      match protocol_type_name(name) {
+        alias if alias != name => imports.push(format!("{name} as {alias}")),
+        _ => imports.push(name.to_owned()),
+      }
+      match statements.resolve_decl_by_name(qualified_name) {
+        DeclType::Table(_) => {
+          imports.push(format!("{name}Args"));
+          imports.push(format!("{name}T"));
+        }
+        DeclType::Struct(_) => imports.push(format!("{name}T")),
+        _ => {}
+      }
+    }
 
+    if namespace == statements.namespace_of(root_type_name) {
+      imports.push(format!("root_as_{}", unqualified(root_type_name).to_case(Case::Snake)));
+    }
+
+    uses.push(format!("pub use {}::{{ {} }};", rust_module_path(namespace), imports.join(", ")));
+  }
 
   format!(r#"
-use tokio::task::JoinHandle;
-pub use crate::schema_generated::protocol::{{ {}, root_as_root_table }};
+{}
 use flatbuffers::{{FlatBufferBuilder, WIPOffset, UnionWIPOffset}};
 pub trait IntoOffset<T: 'static> {{
   fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<T>;
 }}
 
-pub fn into_root_type(binary: &[u8]) -> RootTable {{
-  let root_type = root_as_root_table(binary);
-  if root_type.is_err() {{
-    println!("Protocol error: can't parse binary as a root type");
+/** Decoding a protocol table into its content type failed */
+#[derive(Debug, Clone)]
+pub struct DecodeError {{
+  pub message: String,
+}}
+
+impl std::fmt::Display for DecodeError {{
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
+    write!(f, "can't decode protocol table: {{}}", self.message)
   }}
-  root_type.unwrap()
 }}
-"#, imports.join(", "))
+
+impl std::error::Error for DecodeError {{}}
+"#, uses.join("\n"))
+}
+
+/** The generated `RpcError` type takes the name, so flatc's table of the same name is imported as `RpcErrorTable` */
+pub fn protocol_type_name(name: &str) -> String {
+  let name = unqualified(name);
+  if name == RPC_ERROR {
+    format!("{name}Table")
+  } else {
+    name.to_owned()
+  }
+}
+
+/** Context shared by every handler and the frames sent back on failure and at the end of a stream */
+pub fn generate_request_types(statements: &Statements, options: &RsOptions) -> String {
+  let end_of_stream_frame = if statements.has_streaming_responses() {
+    generate_end_of_stream_frame_fn(statements)
+  } else {
+    "".to_owned()
+  };
+
+  let imp = [
+    "use std::future::Future;".to_owned(),
+    r#"
+/** Per request data passed to every handler */
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+  /** id of the incoming root table, echoed back in the response */
+  pub id: String,
+  pub service: &'static str,
+  pub method: &'static str,
+}
+"#.to_owned(),
+    generate_error_frame_fn(statements, options),
+    end_of_stream_frame,
+  ];
+
+  imp.join("\n")
+}
+
+/** Failed requests are answered with an RpcError payload, clients end a failed request stream with one too */
+pub fn generate_error_frame_fn(statements: &Statements, options: &RsOptions) -> String {
+  let root_type = statements.table_declaration.get(statements.root_type_name.as_ref().unwrap()).unwrap();
+  let root_type_name = unqualified(&root_type.name);
+  let root_union_name = unqualified(&statements.get_available_commands_union().unwrap().name);
+  let rest_of_args = if root_type.active_fields().count() > 2 { "\n    ..Default::default()" } else { "" };
+
+  match statements.rpc_error_table() {
+    Some(error_table) => {
+      let mut fields = vec!["id: id.to_owned(),".to_owned()];
+      for (field_name, field_type) in error_table.active_fields() {
+        match field_name.as_str() {
+          "code" => fields.push(format!("code: error.code{},", cast_from_u32(field_type, statements, options))),
+          "message" | "details" => fields.push(format!("{field_name}: error.{field_name}.to_owned(),")),
+          _ => {}
+        }
+      }
+
+      format!(r#"
+/** Failed requests are answered with an {root_union_name}::RpcError payload */
+fn error_frame(id: &str, error: &RpcError) -> Vec<u8> {{
+  Vec::from(RpcErrorContent {{
+    {}
+  }})
+}}
+"#, fields.join("\n    "))
+    }
+    None => format!(r#"
+/** A root table with an empty payload marks a failed request, declare an RpcError table in {root_union_name} to send the error itself */
+fn error_frame(id: &str, _error: &RpcError) -> Vec<u8> {{
+  let mut builder = FlatBufferBuilder::new();
+  let id = builder.create_string(id);
+  let root_type = {root_type_name}::create(&mut builder, &{root_type_name}Args {{
+    id: Some(id),
+    payload_type: {root_union_name}::NONE,
+    payload: None,{rest_of_args}
+  }});
+  builder.finish(root_type, None);
+
+  Vec::from(builder.finished_data())
+}}
+"#),
+  }
+}
+
+/** A root table with an empty payload ends a stream of either side */
+pub fn generate_end_of_stream_frame_fn(statements: &Statements) -> String {
+  let root_type = statements.table_declaration.get(statements.root_type_name.as_ref().unwrap()).unwrap();
+  let root_type_name = unqualified(&root_type.name);
+  let root_union_name = unqualified(&statements.get_available_commands_union().unwrap().name);
+  let rest_of_args = if root_type.active_fields().count() > 2 { "\n    ..Default::default()" } else { "" };
+
+  format!(r#"
+/** A root table with an empty payload ends a stream, failed streams end with an {RPC_ERROR} payload instead */
+fn end_of_stream_frame(id: &str) -> Vec<u8> {{
+  let mut builder = FlatBufferBuilder::new();
+  let id = builder.create_string(id);
+  let root_type = {root_type_name}::create(&mut builder, &{root_type_name}Args {{
+    id: Some(id),
+    payload_type: {root_union_name}::NONE,
+    payload: None,{rest_of_args}
+  }});
+  builder.finish(root_type, None);
+
+  Vec::from(builder.finished_data())
+}}
+"#)
+}
+
+/** Error type of handlers and clients, mirrors the optional RpcError table */
+pub fn generate_rpc_error_type(statements: &Statements, options: &RsOptions) -> String {
+  let mut imp = vec![r#"
+#[derive(Debug, Clone, Default)]
+pub struct RpcError {
+  pub code: u32,
+  pub message: String,
+  pub details: String,
+}
+
+impl RpcError {
+  pub const BAD_REQUEST: u32 = 400;
+  pub const CANCELLED: u32 = 499;
+  pub const INTERNAL: u32 = 500;
+  pub const NOT_IMPLEMENTED: u32 = 501;
+  pub const BAD_RESPONSE: u32 = 502;
+  pub const UNAVAILABLE: u32 = 503;
+
+  pub fn new(code: u32, message: impl Into<String>) -> Self {
+    RpcError { code, message: message.into(), details: String::new() }
+  }
+
+  pub fn internal(message: impl Into<String>) -> Self {
+    RpcError::new(RpcError::INTERNAL, message)
+  }
+
+  pub fn with_details(mut self, details: impl Into<String>) -> Self {
+    self.details = details.into();
+    self
+  }
+}
+
+impl std::fmt::Display for RpcError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "rpc error {}: {}", self.code, self.message)
+  }
+}
+
+impl std::error::Error for RpcError {}
+
+impl From<DecodeError> for RpcError {
+  fn from(error: DecodeError) -> Self {
+    RpcError::new(RpcError::BAD_REQUEST, error.to_string())
+  }
+}
+"#.to_owned()];
+
+  if let Some(error_table) = statements.rpc_error_table() {
+    let mut fields = vec![];
+    for (field_name, field_type) in error_table.active_fields() {
+      match field_name.as_str() {
+        "code" => fields.push(format!("code: error.code(){},", cast_into_u32(field_type, statements, options))),
+        "message" | "details" => fields.push(format!("{field_name}: error.{field_name}().unwrap_or_default().to_owned(),")),
+        _ => {}
+      }
+    }
+
+    imp.push(format!(r#"
+impl From<{RPC_ERROR}Table<'_>> for RpcError {{
+  fn from(error: {RPC_ERROR}Table<'_>) -> Self {{
+    RpcError {{
+      {}{}
+    }}
+  }}
+}}
+"#, fields.join("\n      "), if fields.len() < 3 { "\n      ..Default::default()" } else { "" }));
+  }
+
+  imp.join("\n")
+}
+
+fn cast_from_u32(field_type: &Type, statements: &Statements, options: &RsOptions) -> String {
+  match field_type.to_rs_string(statements, options).as_str() {
+    "u32" => "".to_owned(),
+    rust_type => format!(" as {rust_type}"),
+  }
+}
+
+fn cast_into_u32(field_type: &Type, statements: &Statements, options: &RsOptions) -> String {
+  match field_type.to_rs_string(statements, options).as_str() {
+    "u32" => "".to_owned(),
+    _ => " as u32".to_owned(),
+  }
+}
+
+/** Sets the request id on responses, unless the handler set one or the output's `id` can't carry it */
+fn generate_into_frame_closure(output: &str, statements: &Statements) -> &'static str {
+  // a declared non string `id` can't carry the request id back
+  let echo_id = match statements.table_declaration.get(output).and_then(|output| output.fields.get("id")) {
+    Some(field_type) => matches!(field_type.value_type, ValueType::String) && !field_type.is_array,
+    None => true,
+  };
+
+  if echo_id {
+    "|mut response| {\nif response.id.is_empty() {\nresponse.id = id.to_owned();\n}\nVec::from(response)\n}"
+  } else {
+    "Vec::from"
+  }
 }
 
 pub fn generate_process_request_fn(statements: &Statements) -> String {
+  let root_type_name = unqualified(statements.root_type_name.as_ref().unwrap());
+  let root_union_name = unqualified(&statements.get_available_commands_union().unwrap().name);
+  let handler_traits = statements.rpc_declarations.values()
+    .map(|rpc| format!("{}Handler", unqualified(&rpc.name)))
+    .collect::<Vec<String>>()
+    .join(" + ");
+
   let mut imp = vec![
-    "pub async fn process_request<RequestHandlerStruct: RequestHandler>(buffer: Vec<u8>) -> Vec<u8> {".to_owned(),
-    "let root_type = into_root_type(&buffer);".to_owned(),
-    "match root_type.payload_type() {".to_owned(),
+    format!("pub trait RequestHandler: {handler_traits} {{}}"),
+    format!("impl<T: {handler_traits}> RequestHandler for T {{}}"),
+    "pub async fn process_request(handler: &impl RequestHandler, buffer: Vec<u8>) -> Vec<u8> {".to_owned(),
+    format!("let root_type = match root_as_{}(&buffer) {{", root_type_name.to_case(Case::Snake)),
+    "Ok(root_type) => root_type,".to_owned(),
+    r#"Err(e) => return error_frame("", &RpcError::new(RpcError::BAD_REQUEST, format!("can't parse binary as a root type: {e}"))),"#.to_owned(),
+    "};".to_owned(),
+    "let id = root_type.id().unwrap_or_default();".to_owned(),
+    "let response = match root_type.payload_type() {".to_owned(),
   ];
 
-
   for rpc in statements.rpc_declarations.values() {
+    let rpc_name = unqualified(&rpc.name);
     for method in rpc.methods.values() {
-      imp.push(format!(r#"AvailableItems::{} => RequestHandlerStruct::{}(buffer).await.expect("error while handling {}").into(),"#,
-                       method.input,
-                       method.name.to_case(Case::Snake),
-                       method.name.to_case(Case::Snake)
-      ))
+      let method_name = method.name.to_case(Case::Snake);
+      let input = unqualified(&method.input);
+
+      if method.is_streaming() {
+        imp.push(format!(r#"{root_union_name}::{input} => Err(RpcError::new(RpcError::BAD_REQUEST, "{rpc_name}::{} is a streaming method, serve it with process_request_frames")),"#, method.name));
+        continue;
+      }
+
+      let into_frame = generate_into_frame_closure(&method.output, statements);
+      imp.push(format!("{root_union_name}::{input} => match root_type.payload_as_{}().map({input}Content::try_from) {{", input.to_case(Case::Snake)));
+      imp.push("Some(Ok(request)) => {".to_owned());
+      imp.push(format!(r#"let ctx = RequestContext {{ id: id.to_owned(), service: "{rpc_name}", method: "{}" }};"#, method.name));
+      imp.push(format!("{rpc_name}Handler::{method_name}(handler, &ctx, request).await.map({into_frame})"));
+      imp.push("}".to_owned());
+      imp.push("Some(Err(error)) => Err(RpcError::from(error)),".to_owned());
+      imp.push(format!(r#"None => Err(RpcError::new(RpcError::BAD_REQUEST, "payload of {input} is absent")),"#));
+      imp.push("},".to_owned());
     }
   }
 
+  // ends of request streams reach here once the runtime has no stream open under their id
+  if statements.has_streaming_requests() {
+    imp.push(format!(r#"{root_union_name}::NONE | {root_union_name}::{RPC_ERROR} => Err(RpcError::new(RpcError::BAD_REQUEST, format!("no request stream is open with id {{id:?}}"))),"#));
+  }
+  imp.push(r#"unknown_variant => Err(RpcError::new(RpcError::NOT_IMPLEMENTED, format!("UNKNOWN PAYLOAD TYPE {:?}", unknown_variant))),"#.to_owned());
+  imp.push("};".to_owned());
+  imp.push(String::new());
+  imp.push("match response {".to_owned());
+  imp.push("Ok(frame) => frame,".to_owned());
+  imp.push("Err(error) => error_frame(id, &error),".to_owned());
+  imp.push("}\n}".to_owned());
+
+  imp.join("\n")
+}
 
-  imp.push(r#"
-  unknown_variant => {
-       let msg = format!("UNKNOWN PAYLOAD TYPE {:?}", unknown_variant);
-       panic!("{}", msg);
-     }
+/** Like `process_request`, but also serves streaming methods: their items are sent as they come and followed
+by an end of stream marker or an error frame. `requests` are the frames of the request stream after `buffer`.
+Sending stops once `responses` is closed, dropping the handler's stream */
+pub fn generate_process_request_frames_fn(statements: &Statements) -> String {
+  let requests = if statements.has_streaming_requests() { "requests" } else { "_requests" };
+  let mut imp = vec![
+    format!("pub async fn process_request_frames(handler: &impl RequestHandler, buffer: Vec<u8>, {requests}: flat_rpc_runtime::FrameStream, responses: &flat_rpc_runtime::FrameSink) {{"),
+  ];
 
-  "#.to_owned());
+  if !statements.has_streaming() {
+    imp.push("responses.send(process_request(handler, buffer).await).await;".to_owned());
+    imp.push("}".to_owned());
+    return imp.join("\n");
+  }
+
+  let root_type_name = unqualified(statements.root_type_name.as_ref().unwrap());
+  let root_union_name = unqualified(&statements.get_available_commands_union().unwrap().name);
+  imp.push(format!("let root_type = match root_as_{}(&buffer) {{", root_type_name.to_case(Case::Snake)));
+  imp.push("Ok(root_type) => root_type,".to_owned());
+  imp.push("Err(_) => {\nresponses.send(process_request(handler, buffer).await).await;\nreturn;\n}".to_owned());
+  imp.push("};".to_owned());
+  imp.push("let id = root_type.id().unwrap_or_default();".to_owned());
+  imp.push("match root_type.payload_type() {".to_owned());
+
+  for rpc in statements.rpc_declarations.values() {
+    let rpc_name = unqualified(&rpc.name);
+    for method in rpc.methods.values().filter(|method| method.is_streaming()) {
+      let method_name = method.name.to_case(Case::Snake);
+      let input = unqualified(&method.input);
+      let into_frame = generate_into_frame_closure(&method.output, statements);
+      let ctx = format!(r#"let ctx = RequestContext {{ id: id.to_owned(), service: "{rpc_name}", method: "{}" }};"#, method.name);
+
+      let mut respond = vec![];
+      if method.streams_responses() {
+        respond.push(format!("let mut items = std::pin::pin!({rpc_name}Handler::{method_name}(handler, &ctx, request));"));
+        respond.push("while let Some(item) = std::future::poll_fn(|cx| flat_rpc_runtime::Stream::poll_next(items.as_mut(), cx)).await {".to_owned());
+        respond.push(format!("let frame = match item.map({into_frame}) {{"));
+        respond.push("Ok(frame) => frame,".to_owned());
+        respond.push("Err(error) => {\nresponses.send(error_frame(id, &error)).await;\nreturn;\n}".to_owned());
+        respond.push("};".to_owned());
+        respond.push("if !responses.send(frame).await {\nreturn;\n}".to_owned());
+        respond.push("}".to_owned());
+        respond.push("responses.send(end_of_stream_frame(id)).await;".to_owned());
+      } else {
+        respond.push(format!("let frame = match {rpc_name}Handler::{method_name}(handler, &ctx, request).await.map({into_frame}) {{"));
+        respond.push("Ok(frame) => frame,".to_owned());
+        respond.push("Err(error) => error_frame(id, &error),".to_owned());
+        respond.push("};".to_owned());
+        respond.push("responses.send(frame).await;".to_owned());
+      }
+
+      // the opening frame is the first item of a request stream
+      if method.streams_requests() {
+        let decode = format!("decode_{}_{method_name}_request", rpc_name.to_case(Case::Snake));
+        imp.push(format!("{root_union_name}::{input} => {{"));
+        imp.push(ctx);
+        imp.push(format!("let request = RequestStream::new({decode}(&buffer), requests, {decode});"));
+        imp.extend(respond);
+        imp.push("}".to_owned());
+        continue;
+      }
+
+      imp.push(format!("{root_union_name}::{input} => match root_type.payload_as_{}().map({input}Content::try_from) {{", input.to_case(Case::Snake)));
+      imp.push("Some(Ok(request)) => {".to_owned());
+      imp.push(ctx);
+      imp.extend(respond);
+      imp.push("}".to_owned());
+      imp.push("Some(Err(error)) => {\nresponses.send(error_frame(id, &RpcError::from(error))).await;\n}".to_owned());
+      imp.push(format!("None => {{\nresponses.send(error_frame(id, &RpcError::new(RpcError::BAD_REQUEST, \"payload of {input} is absent\"))).await;\n}}"));
+      imp.push("},".to_owned());
+    }
+  }
+
+  imp.push("_ => {\nresponses.send(process_request(handler, buffer).await).await;\n}".to_owned());
   imp.push("}\n}".to_owned());
+  imp.join("\n")
+}
+
+/** Requests of a streaming call decoded one by one, `route_request` tells the runtime which frames continue a stream */
+pub fn generate_request_stream_fns(statements: &Statements) -> String {
+  let root_union_name = format_ident!("{}", unqualified(&statements.get_available_commands_union().unwrap().name));
+  let root_as = format_ident!("root_as_{}", unqualified(statements.root_type_name.as_ref().unwrap()).to_case(Case::Snake));
+  let error_variant = format_ident!("{}", RPC_ERROR);
+  let error_accessor = format_ident!("payload_as_{}", RPC_ERROR.to_case(Case::Snake));
+  let mut streamed_inputs = vec![];
+  let mut imp = vec![];
+
+  for rpc in statements.rpc_declarations.values() {
+    for method in rpc.methods.values().filter(|method| method.streams_requests()) {
+      let input_name = unqualified(&method.input);
+      let decode_name = format_ident!("decode_{}_{}_request", unqualified(&rpc.name).to_case(Case::Snake), method.name.to_case(Case::Snake));
+      let input = format_ident!("{input_name}Content");
+      let input_variant = format_ident!("{input_name}");
+      let payload_accessor = format_ident!("payload_as_{}", input_name.to_case(Case::Snake));
+      let payload_absent = format!("payload of {input_name} is absent");
+
+      streamed_inputs.push(quote! { #root_union_name::#input_variant });
+      imp.push((quote! {
+        fn #decode_name(frame: &[u8]) -> Result<Option<#input>, RpcError> {
+          let root_type = #root_as(frame).map_err(|e| RpcError::new(RpcError::BAD_REQUEST, format!("can't parse binary as a root type: {e}")))?;
+
+          match root_type.payload_type() {
+            #root_union_name::#input_variant => {
+              let payload = root_type.#payload_accessor().ok_or_else(|| RpcError::new(RpcError::BAD_REQUEST, #payload_absent))?;
+              let mut request = #input::try_from(payload)?;
+              request.id = root_type.id().unwrap_or_default().to_owned();
+              Ok(Some(request))
+            }
+            #root_union_name::NONE => Ok(None),
+            #root_union_name::#error_variant => Err(root_type.#error_accessor().map(RpcError::from).unwrap_or_default()),
+            unexpected => Err(RpcError::new(RpcError::BAD_REQUEST, format!("request stream item must be {}, got {:?}", #input_name, unexpected))),
+          }
+        }
+      }).to_string());
+    }
+  }
+
+  imp.push((quote! {
+    /** Frames continuing a request stream are told apart by their payload type */
+    pub fn route_request(frame: &[u8]) -> flat_rpc_runtime::Route {
+      let root_type = match #root_as(frame) {
+        Ok(root_type) => root_type,
+        Err(_) => return flat_rpc_runtime::Route::Call,
+      };
+
+      let id = root_type.id().unwrap_or_default().to_owned();
+      match root_type.payload_type() {
+        #(#streamed_inputs)|* => flat_rpc_runtime::Route::Stream(id),
+        #root_union_name::NONE | #root_union_name::#error_variant => flat_rpc_runtime::Route::End(id),
+        _ => flat_rpc_runtime::Route::Call,
+      }
+    }
+
+    /** Requests of a streaming call, it ends after the end of stream marker or the first error, a client cancelling
+    the stream included. A handler leaving `STREAM_BUFFER` requests unread gets an UNAVAILABLE error instead of the rest */
+    pub struct RequestStream<T> {
+      first: Option<Result<T, RpcError>>,
+      frames: Option<flat_rpc_runtime::FrameStream>,
+      decode: fn(&[u8]) -> Result<Option<T>, RpcError>,
+    }
+
+    impl<T> RequestStream<T> {
+      fn new(first: Result<Option<T>, RpcError>, frames: flat_rpc_runtime::FrameStream, decode: fn(&[u8]) -> Result<Option<T>, RpcError>) -> Self {
+        RequestStream { first: first.transpose(), frames: Some(frames), decode }
+      }
+
+      pub async fn next(&mut self) -> Option<Result<T, RpcError>> where T: Unpin {
+        std::future::poll_fn(|cx| flat_rpc_runtime::Stream::poll_next(std::pin::Pin::new(&mut *self), cx)).await
+      }
+    }
+
+    impl<T: Unpin> flat_rpc_runtime::Stream for RequestStream<T> {
+      type Item = Result<T, RpcError>;
+
+      fn poll_next(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Option<Self::Item>> {
+        let item = match self.first.take() {
+          Some(first) => Some(first),
+          None => {
+            let frames = match self.frames.as_mut() {
+              Some(frames) => frames,
+              None => return std::task::Poll::Ready(None),
+            };
+
+            match std::task::ready!(flat_rpc_runtime::Stream::poll_next(std::pin::Pin::new(&mut *frames), cx)) {
+              Some(frame) => (self.decode)(&frame).transpose(),
+              None if frames.fell_behind() => Some(Err(RpcError::new(RpcError::UNAVAILABLE, format!("more than {} requests were waiting, the handler fell behind", flat_rpc_runtime::STREAM_BUFFER)))),
+              None => Some(Err(RpcError::new(RpcError::UNAVAILABLE, "connection closed before the request stream ended"))),
+            }
+          }
+        };
+        if !matches!(item, Some(Ok(_))) {
+          self.frames = None;
+        }
+        std::task::Poll::Ready(item)
+      }
+    }
+  }).to_string());
 
   imp.join("\n")
 }
 
+/** Plugs `process_request_frames` into the servers of flat-rpc-runtime */
+pub fn generate_service_impl(statements: &Statements) -> String {
+  let root_as = format_ident!("root_as_{}", unqualified(statements.root_type_name.as_ref().unwrap()).to_case(Case::Snake));
+  let root_union_name = format_ident!("{}", unqualified(&statements.get_available_commands_union().unwrap().name));
+  let route = if statements.has_streaming_requests() {
+    quote! {
+      fn route(&self, frame: &[u8]) -> flat_rpc_runtime::Route {
+        route_request(frame)
+      }
+    }
+  } else {
+    quote! {}
+  };
+
+  let streams_responses = if statements.has_streaming_responses() {
+    let streamed_calls = statements.rpc_declarations.values()
+      .flat_map(|rpc| rpc.methods.values())
+      .filter(|method| method.streams_responses())
+      .map(|method| format_ident!("{}", unqualified(&method.input)));
+    quote! {
+      fn streams_responses(&self, frame: &[u8]) -> bool {
+        match #root_as(frame) {
+          Ok(root_type) => matches!(root_type.payload_type(), #(#root_union_name::#streamed_calls)|*),
+          Err(_) => false,
+        }
+      }
+    }
+  } else {
+    quote! {}
+  };
+
+  let (inputs, methods): (Vec<_>, Vec<_>) = statements.rpc_declarations.values()
+    .flat_map(|rpc| rpc.methods.values().map(move |method| {
+      (format_ident!("{}", unqualified(&method.input)), format!("{}/{}", unqualified(&rpc.name), method.name))
+    }))
+    .unzip();
+
+  (quote! {
+    pub struct RpcServer<H>(pub H);
+
+    impl<H: RequestHandler + Send + Sync + 'static> flat_rpc_runtime::Service for RpcServer<H> {
+      async fn call(&self, frame: Vec<u8>, requests: flat_rpc_runtime::FrameStream, responses: flat_rpc_runtime::FrameSink) {
+        process_request_frames(&self.0, frame, requests, &responses).await
+      }
+
+      #route
+
+      #streams_responses
+
+      fn match_method(&self, frame: &[u8], method: &str) -> flat_rpc_runtime::MethodMatch {
+        let payload_type = match #root_as(frame) {
+          Ok(root_type) => Some(root_type.payload_type()),
+          Err(_) => None,
+        };
+
+        match (payload_type, method) {
+          #((Some(#root_union_name::#inputs), #methods))|* => flat_rpc_runtime::MethodMatch::Called,
+          (_, #(#methods)|*) => flat_rpc_runtime::MethodMatch::Other,
+          _ => flat_rpc_runtime::MethodMatch::Unknown,
+        }
+      }
+    }
+  }).to_string()
+}
+
+/** Transports of flat-rpc-runtime match responses to requests with `frame_id` */
+pub fn generate_transport_trait(statements: &Statements) -> String {
+  let root_as = format_ident!("root_as_{}", unqualified(statements.root_type_name.as_ref().unwrap()).to_case(Case::Snake));
+  (quote! {
+    pub use flat_rpc_runtime::Transport;
+
+    pub fn frame_id(frame: &[u8]) -> Option<String> {
+      #root_as(frame).ok()?.id().map(str::to_owned)
+    }
+  }).to_string()
+}
+
+/** Responses are matched by id, so calls leaving it unset get one no other call of the process uses */
+pub fn generate_request_id_fn() -> String {
+  (quote! {
+    fn next_request_id() -> String {
+      static NEXT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+      format!("{}-{}", std::process::id(), NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+    }
+  }).to_string()
+}
+
 pub trait ToRsStatement {
-  fn to_rs_string(&self, statements: &Statements) -> String;
+  fn to_rs_string(&self, statements: &Statements, options: &RsOptions) -> String;
+}
+
+pub trait GenerateSerdeImpl {
+  fn generate_serde_impl(&self, statements: &Statements, options: &RsOptions) -> String;
 }
 
-pub trait ToTsString {
-  fn to_ts_string(&self, statements: &Statements) -> String;
+pub trait GenerateFromProtocolImpl {
+  fn generate_from_protocol_impl(&self, statements: &Statements) -> String;
+}
+
+pub trait GenerateDefaultImpl {
+  fn generate_default_impl(&self, statements: &Statements) -> String;
+}
+
+pub trait GenerateDiscriminantCheck {
+  fn generate_discriminant_check(&self) -> String;
+}
+
+pub trait GenerateClientImpl {
+  fn generate_client_impl(&self, statements: &Statements) -> String;
 }
 
 pub trait GenerateIntoProtocolStructImpl {
-  fn generate_into_protocol_struct_impl(&self) -> String;
+  fn generate_into_protocol_struct_impl(&self, statements: &Statements) -> String;
 }
 
 pub trait GenerateIntoOffsetImpl {
@@ -118,7 +764,7 @@ pub trait GenerateIntoByteVecImpl {
 }
 
 impl ToRsStatement for ValueType {
-  fn to_rs_string(&self, _: &Statements) -> String {
+  fn to_rs_string(&self, statements: &Statements, _: &RsOptions) -> String {
     match self {
       ValueType::Bool => "bool".to_owned(),
       ValueType::Byte => "i8".to_owned(),
@@ -142,19 +788,22 @@ impl ToRsStatement for ValueType {
       ValueType::Float32 => "f32".to_owned(),
       ValueType::Float64 => "f64".to_owned(),
       ValueType::String => "String".to_owned(),
-      ValueType::CompoundType(compound) => {
-        format!("{}Content", compound)
+      ValueType::CompoundType(compound) => match statements.resolve_decl_by_name(compound) {
+        DeclType::Enum(_) => unqualified(compound).to_owned(),
+        _ => format!("{}Content", unqualified(compound)),
       }
     }
   }
 }
 
 impl ToRsStatement for Type {
-  fn to_rs_string(&self, statements: &Statements) -> String {
+  fn to_rs_string(&self, statements: &Statements, options: &RsOptions) -> String {
     if self.is_array {
-      format!("Vec<{}>", self.value_type.to_rs_string(statements))
+      format!("Vec<{}>", self.value_type.to_rs_string(statements, options))
+    } else if let Some(length) = self.fixed_length {
+      format!("[{}; {length}]", self.value_type.to_rs_string(statements, options))
     } else {
-      self.value_type.to_rs_string(statements)
+      self.value_type.to_rs_string(statements, options)
     }
   }
 }
@@ -162,8 +811,8 @@ impl ToRsStatement for Type {
 impl GenerateIntoOffsetImpl for TableDeclaration {
   fn generate_into_offset_impl(&self, statements: &Statements) -> String {
     let mut imp = vec![
-      format!("impl IntoOffset<{}<'static>> for {}Content {{", &self.name, &self.name),
-      format!("fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<{}<'static>> {{", &self.name),
+      format!("impl IntoOffset<{}<'static>> for {}Content {{", protocol_type_name(&self.name), unqualified(&self.name)),
+      format!("fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<{}<'static>> {{", protocol_type_name(&self.name)),
     ];
 
     let mut field_offsets = vec![];
@@ -172,13 +821,18 @@ impl GenerateIntoOffsetImpl for TableDeclaration {
     let mut structs = vec![];
     let mut enums = vec![];
 
-    for (field_name, field_type) in self.fields.iter() {
+    for (field_name, field_type) in self.active_fields() {
       match (&field_type.value_type, field_type.is_array) {
         (ValueType::CompoundType(value), true) => {
+          let type_name = unqualified(value);
+          if let DeclType::Union(_) = statements.resolve_decl_by_name(value) {
+            field_offsets.push(format!("{field_name}_type"));
+            imp.push(format!("let {field_name}_type_offset = builder.create_vector(&self.{field_name}.iter().map({type_name}Content::union_type).collect::<Vec<{type_name}>>());"));
+          }
           field_offsets.push(field_name.to_string());
 
           let field_name_ident = format_ident!("{}", field_name);
-          let type_name = format_ident!("{}", value);
+          let type_name = format_ident!("{}", type_name);
 
           imp.push("let mut offset_vec = vec![];".to_string());
 
@@ -215,9 +869,9 @@ impl GenerateIntoOffsetImpl for TableDeclaration {
         (ValueType::CompoundType(name), false) => {
           match statements.resolve_decl_by_name(name) {
             DeclType::Union(_) => {
-              imp.push(format!("let {field_name}_offset = self.{field_name}.unwrap();"));
+              imp.push(format!("let {field_name}_type = self.{field_name}.as_ref().map({}Content::union_type).unwrap_or_default();", unqualified(name)));
+              imp.push(format!("let {field_name}_offset = self.{field_name}.map(|value| value.into_offset(builder));"));
               unions.push(field_name.to_owned());
-              field_offsets.push(field_name.to_string());
             }
             DeclType::Struct(_) => {
               imp.push(format!("let {field_name} = self.{field_name}.into();"));
@@ -267,7 +921,7 @@ impl GenerateIntoOffsetImpl for TableDeclaration {
     }
 
     let unions_as_args = unions.into_iter().map(|item| {
-      format!("{item}_type: self.{item}_type,")
+      format!("{item}_type, {item}: {item}_offset,")
     }).collect::<Vec<String>>().join("\n");
     let offsets_as_args = field_offsets.into_iter().map(|field| {
       format!("{field}: Some({field}_offset),")
@@ -284,8 +938,8 @@ impl GenerateIntoOffsetImpl for TableDeclaration {
       format!("{field}: self.{field},")
     }).collect::<Vec<String>>().join("\n");
 
-    imp.push(format!("let args = &{}Args {{ \n {unions_as_args} {offsets_as_args} {straight_as_args} {structs_as_args} {enums_as_args} }};", self.name));
-    imp.push(format!("{}::create(builder, args)", &self.name));
+    imp.push(format!("let args = &{}Args {{ \n {unions_as_args} {offsets_as_args} {straight_as_args} {structs_as_args} {enums_as_args} }};", unqualified(&self.name)));
+    imp.push(format!("{}::create(builder, args)", protocol_type_name(&self.name)));
 
     imp.push("}\n}\n\n".to_owned());
     imp.join("\n")
@@ -299,15 +953,17 @@ impl GenerateIntoByteVecImpl for TableDeclaration {
     }
 
     let root_type_available_payload = statements.get_available_commands_union().unwrap();
-    if !root_type_available_payload.items.contains(&self.name) {
+    if !root_type_available_payload.contains(&self.name) {
       return "".to_owned();
     }
 
 
-    let struct_name = format_ident!("{}Content", &self.name);
-    let origin_name = format_ident!("{}", &self.name);
-    let root_union_name = format_ident!("{}", &root_type_available_payload.name);
-    let root_type_name = format_ident!("{}Content", &statements.root_type_name.clone().unwrap());
+    let struct_name = format_ident!("{}Content", unqualified(&self.name));
+    let origin_name = format_ident!("{}", unqualified(&self.name));
+    let root_union_content = format_ident!("{}Content", unqualified(&root_type_available_payload.name));
+    let root_type = statements.table_declaration.get(statements.root_type_name.as_ref().unwrap()).unwrap();
+    let root_type_name = format_ident!("{}Content", unqualified(&root_type.name));
+    let rest_of_fields = if root_type.active_fields().count() > 2 { quote! { ..Default::default() } } else { quote! {} };
 
 
     let quote_attempt = quote! {
@@ -315,11 +971,10 @@ impl GenerateIntoByteVecImpl for TableDeclaration {
         fn from(content: #struct_name) -> Self {
           let mut builder = FlatBufferBuilder::new();
           let id = content.id.to_owned();
-          let payload = content.into_offset(&mut builder).as_union_value();
           let root_type = #root_type_name {
             id,
-            payload_type: #root_union_name::#origin_name,
-            payload: Some(payload),
+            payload: Some(#root_union_content::#origin_name(content)),
+            #rest_of_fields
           };
 
           let offset = root_type.into_offset(&mut builder);
@@ -335,26 +990,26 @@ impl GenerateIntoByteVecImpl for TableDeclaration {
 }
 
 impl ToRsStatement for TableDeclaration {
-  fn to_rs_string(&self, statements: &Statements) -> String {
-    let struct_name = format!("{}Content", self.name);
+  fn to_rs_string(&self, statements: &Statements, options: &RsOptions) -> String {
+    let struct_name = format!("{}Content", unqualified(&self.name));
 
     let mut table_statement = vec![];
+    table_statement.push(derive_attribute(options));
     table_statement.push(format!("pub struct {} {{", struct_name));
     let mut is_id_exist = false;
 
-    for (field_name, field_type) in self.fields.iter() {
+    for (field_name, field_type) in self.active_fields() {
       match (&field_type.value_type, &field_type.is_array) {
         (ValueType::CompoundType(name), false) => {
           match statements.resolve_decl_by_name(name) {
-            DeclType::Union(union) => {
-              table_statement.push(format!("\tpub {}_type: {},", field_name, union.name));
-              table_statement.push(format!("\tpub {}: Option<WIPOffset<UnionWIPOffset>>,", field_name))
+            DeclType::Union(_) => {
+              table_statement.push(format!("\tpub {}: Option<{}>,", field_name, field_type.to_rs_string(statements, options)))
             }
             DeclType::Enum(enum_decl) => {
-              table_statement.push(format!("\tpub {}: {},", field_name, enum_decl.name));
+              table_statement.push(format!("\tpub {}: {},", field_name, optional_rs_type(unqualified(&enum_decl.name).to_owned(), field_type)));
             }
             DeclType::Table(_) | DeclType::Struct(_) => {
-              table_statement.push(format!("\tpub {}: {},", field_name, field_type.to_rs_string(statements)))
+              table_statement.push(format!("\tpub {}: {},", field_name, field_type.to_rs_string(statements, options)))
             }
             DeclType::Rpc(_) => {}
             DeclType::Null => {}
@@ -363,14 +1018,14 @@ impl ToRsStatement for TableDeclaration {
         (ValueType::CompoundType(name), true) => {
           match statements.resolve_decl_by_name(name) {
             DeclType::Enum(enum_decl) => {
-              table_statement.push(format!("\tpub {}: Vec<{}>,", field_name, enum_decl.name));
+              table_statement.push(format!("\tpub {}: Vec<{}>,", field_name, unqualified(&enum_decl.name)));
             }
             _ => {
-              table_statement.push(format!("\tpub {}: {},", field_name, field_type.to_rs_string(statements)))
+              table_statement.push(format!("\tpub {}: {},", field_name, field_type.to_rs_string(statements, options)))
             }
           };
         }
-        _ => { table_statement.push(format!("\tpub {}: {},", field_name, field_type.to_rs_string(statements))); }
+        _ => { table_statement.push(format!("\tpub {}: {},", field_name, optional_rs_type(field_type.to_rs_string(statements, options), field_type))); }
       }
 
 
@@ -379,7 +1034,7 @@ impl ToRsStatement for TableDeclaration {
       }
     }
 
-    if !is_id_exist && statements.get_available_commands_union().unwrap().items.contains(&self.name) {
+    if !is_id_exist && statements.get_available_commands_union().unwrap().contains(&self.name) {
       table_statement.push("\tpub id: String".to_owned());
     }
     table_statement.push("}\n\n".to_owned());
@@ -388,16 +1043,25 @@ impl ToRsStatement for TableDeclaration {
 }
 
 impl ToRsStatement for RpcDeclaration {
-  fn to_rs_string(&self, _: &Statements) -> String {
+  fn to_rs_string(&self, _: &Statements, _: &RsOptions) -> String {
     let mut imp = vec![
-      "pub trait RequestHandler {".to_owned()
+      format!("pub trait {}Handler {{", unqualified(&self.name))
     ];
 
 
     for method in self.methods.values() {
       let method_name = method.name.to_owned().to_case(Case::Snake);
-      imp.push(format!("/** incoming must be {} */", method.input));
-      imp.push(format!("fn {method_name}(incoming: Vec<u8>) -> JoinHandle<{}Content>;", method.output));
+      let output = unqualified(&method.output);
+      let input = if method.streams_requests() {
+        format!("reqs: RequestStream<{}Content>", unqualified(&method.input))
+      } else {
+        format!("req: {}Content", unqualified(&method.input))
+      };
+      if method.streams_responses() {
+        imp.push(format!("fn {method_name}(&self, ctx: &RequestContext, {input}) -> impl flat_rpc_runtime::Stream<Item = Result<{output}Content, RpcError>> + Send;"));
+      } else {
+        imp.push(format!("fn {method_name}(&self, ctx: &RequestContext, {input}) -> impl Future<Output = Result<{output}Content, RpcError>> + Send;"));
+      }
     };
 
     imp.push("}".to_owned());
@@ -406,12 +1070,21 @@ impl ToRsStatement for RpcDeclaration {
 }
 
 impl ToRsStatement for StructDeclaration {
-  fn to_rs_string(&self, statements: &Statements) -> String {
-    let mut imp = vec![];
-    imp.push(format!("pub struct {}Content {{", self.name));
+  fn to_rs_string(&self, statements: &Statements, options: &RsOptions) -> String {
+    let mut imp = vec![derive_attribute(options)];
+    imp.push(format!("pub struct {}Content {{", unqualified(&self.name)));
 
     for (name, type_def) in self.fields.iter() {
-      imp.push(format!("\t pub {}: {},", name, type_def.to_rs_string(statements)));
+      // serde implements its traits only for arrays up to 32 elements
+      if type_def.fixed_length.is_some_and(|length| length > 32) {
+        if options.has_derive(Derive::Serialize) {
+          imp.push(r#"#[serde(serialize_with = "fixed_array::serialize")]"#.to_owned());
+        }
+        if options.has_derive(Derive::Deserialize) {
+          imp.push(r#"#[serde(deserialize_with = "fixed_array::deserialize")]"#.to_owned());
+        }
+      }
+      imp.push(format!("\t pub {}: {},", name, type_def.to_rs_string(statements, options)));
     }
 
     imp.push("}".to_owned());
@@ -419,17 +1092,102 @@ impl ToRsStatement for StructDeclaration {
   }
 }
 
+/** Members of a union as a sum type, flatc keeps the discriminant and the value in two separate fields */
+impl ToRsStatement for UnionDeclaration {
+  fn to_rs_string(&self, _: &Statements, options: &RsOptions) -> String {
+    let name = unqualified(&self.name);
+    let mut variants = vec![];
+    let mut union_type_arms = vec![];
+    let mut into_offset_arms = vec![];
+    let mut from_table_arms = vec![];
+
+    for item in &self.items {
+      let member = unqualified(&item.name);
+      variants.push(format!("{member}({member}Content),"));
+      union_type_arms.push(format!("{name}Content::{member}(_) => {name}::{member},"));
+      into_offset_arms.push(format!("{name}Content::{member}(value) => value.into_offset(builder).as_union_value(),"));
+      from_table_arms.push(format!(
+        "{name}::{member} => {member}Content::try_from(unsafe {{ {}::init_from_table(table) }}).map(|value| Some({name}Content::{member}(value))),",
+        protocol_type_name(member)
+      ));
+    }
+
+    // adjacently tagged, `{{ "type": "Circle", "value": {{ .. }} }}` mirrors TS content unions
+    let serde_tag = if options.has_serde() { "\n#[serde(tag = \"type\", content = \"value\")]" } else { "" };
+
+    format!(r#"
+{}{serde_tag}
+pub enum {name}Content {{
+{}
+}}
+
+impl {name}Content {{
+  pub fn union_type(&self) -> {name} {{
+    match self {{
+      {}
+    }}
+  }}
+
+  /** `table` must be the value stored next to `union_type` in a buffer checked by the verifier, which verifies union values against their type.
+  NONE and members unknown to this schema version decode as None */
+  #[allow(dead_code)]
+  fn from_table(union_type: {name}, table: flatbuffers::Table<'_>) -> Result<Option<Self>, DecodeError> {{
+    match union_type {{
+      {}
+      _ => Ok(None),
+    }}
+  }}
+
+  /** Decodes a vector of unions out of its parallel type and value vectors, NONE and unknown members are skipped */
+  #[allow(dead_code)]
+  fn from_vectors<'a>(types: impl IntoIterator<Item = {name}>, tables: impl IntoIterator<Item = flatbuffers::Table<'a>>) -> Result<Vec<Self>, DecodeError> {{
+    let mut values = vec![];
+    for (union_type, table) in types.into_iter().zip(tables) {{
+      if let Some(value) = Self::from_table(union_type, table)? {{
+        values.push(value);
+      }}
+    }}
+    Ok(values)
+  }}
+}}
+
+impl IntoOffset<UnionWIPOffset> for {name}Content {{
+  fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<UnionWIPOffset> {{
+    match self {{
+      {}
+    }}
+  }}
+}}
+"#,
+      derive_attribute(options),
+      variants.join("\n"),
+      union_type_arms.join("\n      "),
+      from_table_arms.join("\n      "),
+      into_offset_arms.join("\n      "),
+    )
+  }
+}
+
 impl GenerateIntoProtocolStructImpl for StructDeclaration {
-  fn generate_into_protocol_struct_impl(&self) -> String {
-    let name = &self.name;
+  fn generate_into_protocol_struct_impl(&self, statements: &Statements) -> String {
+    let name = unqualified(&self.name);
     let mut imp = vec![
       format!("impl From<{name}Content> for {name} {{"),
       format!("fn from(struct_def: {name}Content) -> Self {{"),
       format!("{name}T {{"),
     ];
 
-    for field_name in self.fields.keys() {
-      imp.push(format!("{field_name}: struct_def.{field_name},"));
+    // the object API nests other structs as their `T` types
+    for (field_name, field_type) in self.fields.iter() {
+      match (&field_type.value_type, field_type.fixed_length) {
+        (ValueType::CompoundType(type_name), None) if statements.struct_declaration.contains_key(type_name) => {
+          imp.push(format!("{field_name}: {}::from(struct_def.{field_name}).unpack(),", unqualified(type_name)));
+        }
+        (ValueType::CompoundType(type_name), Some(_)) if statements.struct_declaration.contains_key(type_name) => {
+          imp.push(format!("{field_name}: struct_def.{field_name}.map(|value| {}::from(value).unpack()),", unqualified(type_name)));
+        }
+        _ => imp.push(format!("{field_name}: struct_def.{field_name},")),
+      }
     };
 
     imp.push("}.pack()\n}\n}".to_owned());
@@ -438,3 +1196,608 @@ impl GenerateIntoProtocolStructImpl for StructDeclaration {
     imp.join("\n")
   }
 }
+
+impl GenerateFromProtocolImpl for StructDeclaration {
+  fn generate_from_protocol_impl(&self, statements: &Statements) -> String {
+    let name = unqualified(&self.name);
+    let mut imp = vec![
+      format!("impl From<&{name}> for {name}Content {{"),
+      format!("fn from(struct_def: &{name}) -> Self {{"),
+      format!("{name}Content {{"),
+    ];
+
+    for field_name in &self.fields_order {
+      let field = self.fields.get(field_name).unwrap();
+      if field.fixed_length.is_some() {
+        match &field.value_type {
+          ValueType::CompoundType(type_name) if statements.struct_declaration.contains_key(type_name) => {
+            imp.push(format!("{field_name}: std::array::from_fn(|index| {}Content::from(struct_def.{field_name}().get(index))),", unqualified(type_name)));
+          }
+          _ => imp.push(format!("{field_name}: std::array::from_fn(|index| struct_def.{field_name}().get(index)),")),
+        }
+        continue;
+      }
+
+      match &field.value_type {
+        ValueType::CompoundType(type_name) => match statements.resolve_decl_by_name(type_name) {
+          DeclType::Struct(_) => imp.push(format!("{field_name}: {}Content::from(struct_def.{field_name}()),", unqualified(type_name))),
+          _ => imp.push(format!("{field_name}: struct_def.{field_name}(),")),
+        },
+        _ => imp.push(format!("{field_name}: struct_def.{field_name}(),")),
+      }
+    }
+
+    imp.push("}\n}\n}".to_owned());
+    imp.join("\n")
+  }
+}
+
+fn required_field_from_protocol(field_name: &str, field: &Type, statements: &Statements) -> String {
+  let type_name = match &field.value_type {
+    ValueType::CompoundType(type_name) => unqualified(type_name),
+    _ => "",
+  };
+
+  match (&field.value_type, field.is_array) {
+    (ValueType::CompoundType(compound), false) => match statements.resolve_decl_by_name(compound) {
+      DeclType::Union(_) => format!("{field_name}: {type_name}Content::from_table(table.{field_name}_type(), table.{field_name}())?,"),
+      DeclType::Table(_) => format!("{field_name}: {type_name}Content::try_from(table.{field_name}())?,"),
+      _ => format!("{field_name}: {type_name}Content::from(table.{field_name}()),"),
+    },
+    (ValueType::CompoundType(compound), true) => match statements.resolve_decl_by_name(compound) {
+      DeclType::Table(_) => format!("{field_name}: table.{field_name}().iter().map({type_name}Content::try_from).collect::<Result<Vec<{type_name}Content>, DecodeError>>()?,"),
+      DeclType::Struct(_) => format!("{field_name}: table.{field_name}().iter().map({type_name}Content::from).collect(),"),
+      DeclType::Union(_) => format!("{field_name}: {type_name}Content::from_vectors(table.{field_name}_type(), table.{field_name}())?,"),
+      _ => format!("{field_name}: table.{field_name}().iter().collect(),"),
+    },
+    (ValueType::String, false) => format!("{field_name}: table.{field_name}().to_owned(),"),
+    (ValueType::String, true) => format!("{field_name}: table.{field_name}().iter().map(|value| value.to_owned()).collect(),"),
+    _ => format!("{field_name}: table.{field_name}().iter().collect(),"),
+  }
+}
+
+impl GenerateFromProtocolImpl for TableDeclaration {
+  fn generate_from_protocol_impl(&self, statements: &Statements) -> String {
+    if &self.name == statements.root_type_name.as_ref().unwrap() {
+      return "".to_owned();
+    }
+
+    let name = unqualified(&self.name);
+    let mut imp = vec![
+      format!("impl TryFrom<{}<'_>> for {name}Content {{", protocol_type_name(name)),
+      "type Error = DecodeError;".to_owned(),
+      format!("fn try_from(table: {}<'_>) -> Result<Self, Self::Error> {{", protocol_type_name(name)),
+      format!("Ok({name}Content {{"),
+    ];
+
+    for field_name in &self.fields_order {
+      let field = self.fields.get(field_name).unwrap();
+      if field.metadata.is_deprecated() {
+        continue;
+      }
+
+      // flatc accessors of required fields return the value itself, the verifier already checked it's present
+      if field.metadata.is_required() {
+        imp.push(required_field_from_protocol(field_name, field, statements));
+        continue;
+      }
+
+      match (&field.value_type, field.is_array) {
+        (ValueType::CompoundType(compound), false) => {
+          let type_name = unqualified(compound);
+          match statements.resolve_decl_by_name(compound) {
+            // flatc builders leave optional tables and structs out, they decode to the default content
+            DeclType::Table(_) => {
+              imp.push(format!("{field_name}: table.{field_name}().map({type_name}Content::try_from).transpose()?.unwrap_or_default(),"));
+            }
+            DeclType::Struct(_) => {
+              imp.push(format!("{field_name}: table.{field_name}().map({type_name}Content::from).unwrap_or_default(),"));
+            }
+            DeclType::Enum(_) => {
+              imp.push(format!("{field_name}: table.{field_name}(),"));
+            }
+            DeclType::Union(_) => {
+              imp.push(format!("{field_name}: table.{field_name}().map(|value| {type_name}Content::from_table(table.{field_name}_type(), value)).transpose()?.flatten(),"));
+            }
+            DeclType::Rpc(_) => {}
+            DeclType::Null => {}
+          };
+        }
+        (ValueType::CompoundType(compound), true) => {
+          let type_name = unqualified(compound);
+          match statements.resolve_decl_by_name(compound) {
+            DeclType::Table(_) => {
+              imp.push(format!(
+                "{field_name}: table.{field_name}().map(|vector| vector.iter().map({type_name}Content::try_from).collect::<Result<Vec<{type_name}Content>, DecodeError>>()).transpose()?.unwrap_or_default(),"
+              ));
+            }
+            DeclType::Struct(_) => {
+              imp.push(format!("{field_name}: table.{field_name}().map(|vector| vector.iter().map({type_name}Content::from).collect()).unwrap_or_default(),"));
+            }
+            DeclType::Enum(_) => {
+              imp.push(format!("{field_name}: table.{field_name}().map(|vector| vector.iter().collect()).unwrap_or_default(),"));
+            }
+            DeclType::Union(_) => {
+              imp.push(format!("{field_name}: {type_name}Content::from_vectors(table.{field_name}_type().into_iter().flatten(), table.{field_name}().into_iter().flatten())?,"));
+            }
+            DeclType::Rpc(_) => {}
+            DeclType::Null => {}
+          };
+        }
+        (ValueType::String, false) => {
+          imp.push(format!("{field_name}: table.{field_name}().unwrap_or_default().to_owned(),"));
+        }
+        (ValueType::String, true) => {
+          imp.push(format!("{field_name}: table.{field_name}().map(|vector| vector.iter().map(|value| value.to_owned()).collect()).unwrap_or_default(),"));
+        }
+        (_, true) => {
+          imp.push(format!("{field_name}: table.{field_name}().map(|vector| vector.iter().collect()).unwrap_or_default(),"));
+        }
+        (_, false) => {
+          imp.push(format!("{field_name}: table.{field_name}(),"));
+        }
+      }
+    }
+
+    if !self.fields.contains_key("id") && statements.get_available_commands_union().unwrap().contains(&self.name) {
+      imp.push("id: String::new(),".to_owned());
+    }
+
+    imp.push("})\n}\n}".to_owned());
+    imp.join("\n")
+  }
+}
+
+impl GenerateClientImpl for RpcDeclaration {
+  fn generate_client_impl(&self, statements: &Statements) -> String {
+    let root_union_name = format_ident!("{}", unqualified(&statements.get_available_commands_union().unwrap().name));
+    let root_as = format_ident!("root_as_{}", unqualified(statements.root_type_name.as_ref().unwrap()).to_case(Case::Snake));
+    let mut imp = vec![];
+    let mut client_methods = vec![];
+
+    for method in self.methods.values() {
+      let output_name = unqualified(&method.output);
+      let method_name = format_ident!("{}", method.name.to_case(Case::Snake));
+      let function_name = format_ident!("{}_{}", unqualified(&self.name).to_case(Case::Snake), method.name.to_case(Case::Snake));
+      let input = format_ident!("{}Content", unqualified(&method.input));
+      let output = format_ident!("{output_name}Content");
+      let payload_accessor = format_ident!("payload_as_{}", output_name.to_case(Case::Snake));
+      let payload_absent = format!("payload of {output_name} is absent");
+      let output_variant = format_ident!("{output_name}");
+
+      // a stream item carries the id of the request like the root table around it
+      let (output_id, decoded) = if method.is_streaming() {
+        (quote! { root_type.id().unwrap_or_default().to_owned() }, quote! { Ok(Some(output)) })
+      } else {
+        (quote! { id }, quote! { Ok(output) })
+      };
+      let mut payload_arms = vec![quote! {
+        #root_union_name::#output_variant => {
+          let payload = root_type.#payload_accessor().ok_or_else(|| RpcError::new(RpcError::BAD_RESPONSE, #payload_absent))?;
+          let mut output = #output::try_from(payload).map_err(|e| RpcError::new(RpcError::BAD_RESPONSE, e.to_string()))?;
+          output.id = #output_id;
+          #decoded
+        }
+      }];
+      if method.is_streaming() {
+        payload_arms.push(quote! {
+          #root_union_name::NONE => Ok(None),
+        });
+      }
+      if statements.rpc_error_table().is_some() {
+        let error_variant = format_ident!("{}", RPC_ERROR);
+        let error_accessor = format_ident!("payload_as_{}", RPC_ERROR.to_case(Case::Snake));
+        payload_arms.push(quote! {
+          #root_union_name::#error_variant => Err(root_type.#error_accessor().map(RpcError::from).unwrap_or_default()),
+        });
+      }
+
+      let decode_name = format_ident!("decode_{}", function_name);
+      if method.is_streaming() {
+        imp.push((quote! {
+          fn #decode_name(response: &[u8]) -> Result<Option<#output>, RpcError> {
+            let root_type = #root_as(response).map_err(|e| RpcError::new(RpcError::BAD_RESPONSE, format!("can't parse binary as a root type: {e}")))?;
+
+            match root_type.payload_type() {
+              #(#payload_arms)*
+              unexpected => Err(RpcError::new(RpcError::BAD_RESPONSE, format!("stream item must be {}, got {:?}", #output_name, unexpected))),
+            }
+          }
+        }).to_string());
+      }
+
+      // every request of a stream carries the id of the call
+      let encode = quote! {
+        |mut content: #input, id: &str| {
+          content.id = id.to_owned();
+          Vec::from(content)
+        }
+      };
+      let (returned, opened) = match method.streaming() {
+        Some(Streaming::Server) => (
+          quote! { ResponseStream<#output> },
+          quote! {
+            let frames = transport.open_stream(content.into(), id).await
+              .map_err(|e| RpcError::new(RpcError::UNAVAILABLE, e.to_string()))?;
+            Ok(ResponseStream::new(frames, #decode_name))
+          },
+        ),
+        Some(Streaming::Client) => (
+          quote! { ClientStreamingCall<#input, #output> },
+          quote! {
+            let (requests, responses) = transport.open_call(content.into(), id.to_owned()).await
+              .map_err(|e| RpcError::new(RpcError::UNAVAILABLE, e.to_string()))?;
+            Ok(ClientStreamingCall {
+              requests: RequestSink::new(requests, id, #encode),
+              response: ResponseStream::new(responses, #decode_name),
+            })
+          },
+        ),
+        Some(Streaming::Bidi) => (
+          quote! { (RequestSink<#input>, ResponseStream<#output>) },
+          quote! {
+            let (requests, responses) = transport.open_call(content.into(), id.to_owned()).await
+              .map_err(|e| RpcError::new(RpcError::UNAVAILABLE, e.to_string()))?;
+            Ok((RequestSink::new(requests, id, #encode), ResponseStream::new(responses, #decode_name)))
+          },
+        ),
+        _ => (quote! {}, quote! {}),
+      };
+
+      if method.is_streaming() {
+        imp.push((quote! {
+          pub async fn #function_name<T: Transport>(transport: &T, mut content: #input) -> Result<#returned, RpcError> {
+            if content.id.is_empty() {
+              content.id = next_request_id();
+            }
+            let id = content.id.to_owned();
+            #opened
+          }
+        }).to_string());
+
+        client_methods.push(quote! {
+          pub async fn #method_name(&self, content: #input) -> Result<#returned, RpcError> {
+            #function_name(&self.transport, content).await
+          }
+        });
+        continue;
+      }
+
+      imp.push((quote! {
+        pub async fn #function_name<T: Transport>(transport: &T, mut content: #input) -> Result<#output, RpcError> {
+          if content.id.is_empty() {
+            content.id = next_request_id();
+          }
+          let id = content.id.to_owned();
+          let response = transport.send_message(content.into(), id.to_owned()).await
+            .map_err(|e| RpcError::new(RpcError::UNAVAILABLE, e.to_string()))?;
+          let root_type = #root_as(&response).map_err(|e| RpcError::new(RpcError::BAD_RESPONSE, format!("can't parse binary as a root type: {e}")))?;
+
+          match root_type.payload_type() {
+            #(#payload_arms)*
+            unexpected => Err(RpcError::new(RpcError::BAD_RESPONSE, format!("response payload must be {}, got {:?}", #output_name, unexpected))),
+          }
+        }
+      }).to_string());
+
+      client_methods.push(quote! {
+        pub async fn #method_name(&self, content: #input) -> Result<#output, RpcError> {
+          #function_name(&self.transport, content).await
+        }
+      });
+    }
+
+    let client_name = format_ident!("{}Client", unqualified(&self.name));
+    imp.push((quote! {
+      pub struct #client_name<T: Transport> {
+        pub transport: T,
+      }
+
+      impl<T: Transport> #client_name<T> {
+        pub fn new(transport: T) -> Self {
+          #client_name { transport }
+        }
+
+        #(#client_methods)*
+      }
+    }).to_string());
+
+    imp.join("\n")
+  }
+}
+
+/** Responses of a streaming call, it ends after the end of stream marker or the first error
+and lets the transport forget the request right then. Leaving `STREAM_BUFFER` responses unread fails it with UNAVAILABLE */
+pub fn generate_response_stream_type() -> String {
+  (quote! {
+    pub struct ResponseStream<T> {
+      frames: Option<flat_rpc_runtime::FrameStream>,
+      decode: fn(&[u8]) -> Result<Option<T>, RpcError>,
+    }
+
+    impl<T> ResponseStream<T> {
+      fn new(frames: flat_rpc_runtime::FrameStream, decode: fn(&[u8]) -> Result<Option<T>, RpcError>) -> Self {
+        ResponseStream { frames: Some(frames), decode }
+      }
+
+      pub async fn next(&mut self) -> Option<Result<T, RpcError>> {
+        std::future::poll_fn(|cx| flat_rpc_runtime::Stream::poll_next(std::pin::Pin::new(&mut *self), cx)).await
+      }
+    }
+
+    impl<T> flat_rpc_runtime::Stream for ResponseStream<T> {
+      type Item = Result<T, RpcError>;
+
+      fn poll_next(mut self: std::pin::Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<Option<Self::Item>> {
+        let frames = match self.frames.as_mut() {
+          Some(frames) => frames,
+          None => return std::task::Poll::Ready(None),
+        };
+
+        let item = match std::task::ready!(flat_rpc_runtime::Stream::poll_next(std::pin::Pin::new(&mut *frames), cx)) {
+          Some(frame) => (self.decode)(&frame).transpose(),
+          None if frames.fell_behind() => Some(Err(RpcError::new(RpcError::UNAVAILABLE, format!("more than {} responses were waiting, the stream fell behind", flat_rpc_runtime::STREAM_BUFFER)))),
+          None => Some(Err(RpcError::new(RpcError::UNAVAILABLE, "connection closed before the stream ended"))),
+        };
+        if !matches!(item, Some(Ok(_))) {
+          self.frames = None;
+        }
+        std::task::Poll::Ready(item)
+      }
+    }
+  }).to_string()
+}
+
+/** Requests of a call streaming them, and the call answered once after them */
+pub fn generate_request_sink_type() -> String {
+  (quote! {
+    /** Sends the requests after the one opening the call, waiting while the connection is busy.
+    Dropping it before `finish` cancels the stream. Keep reading the responses of a bidirectional call,
+    responses left unread fail the call once `STREAM_BUFFER` of them are waiting */
+    pub struct RequestSink<T> {
+      frames: flat_rpc_runtime::FrameSink,
+      id: String,
+      encode: fn(T, &str) -> Vec<u8>,
+      ended: bool,
+    }
+
+    impl<T> RequestSink<T> {
+      fn new(frames: flat_rpc_runtime::FrameSink, id: String, encode: fn(T, &str) -> Vec<u8>) -> Self {
+        RequestSink { frames, id, encode, ended: false }
+      }
+
+      pub async fn send(&mut self, content: T) -> Result<(), RpcError> {
+        let frame = (self.encode)(content, &self.id);
+        self.send_frame(frame).await
+      }
+
+      /** Ends the stream with the end of stream marker */
+      pub async fn finish(mut self) -> Result<(), RpcError> {
+        self.ended = true;
+        let frame = end_of_stream_frame(&self.id);
+        self.send_frame(frame).await
+      }
+
+      /** Ends the stream with `error`, the server's request stream yields it */
+      pub async fn fail(mut self, error: RpcError) -> Result<(), RpcError> {
+        self.ended = true;
+        let frame = error_frame(&self.id, &error);
+        self.send_frame(frame).await
+      }
+
+      async fn send_frame(&self, frame: Vec<u8>) -> Result<(), RpcError> {
+        if self.frames.send(frame).await {
+          Ok(())
+        } else {
+          Err(RpcError::new(RpcError::UNAVAILABLE, format!("connection closed before request stream {} ended", self.id)))
+        }
+      }
+    }
+
+    impl<T> Drop for RequestSink<T> {
+      fn drop(&mut self) {
+        if !self.ended {
+          self.frames.send_detached(error_frame(&self.id, &RpcError::new(RpcError::CANCELLED, "request stream dropped before it ended")));
+        }
+      }
+    }
+
+    /** A call streaming its requests and answered once, the response is awaited after the end of stream marker */
+    pub struct ClientStreamingCall<T, R> {
+      requests: RequestSink<T>,
+      response: ResponseStream<R>,
+    }
+
+    impl<T, R> ClientStreamingCall<T, R> {
+      pub async fn send(&mut self, content: T) -> Result<(), RpcError> {
+        self.requests.send(content).await
+      }
+
+      pub async fn finish(self) -> Result<R, RpcError> {
+        self.requests.finish().await?;
+        let mut response = self.response;
+        response.next().await.unwrap_or_else(|| Err(RpcError::new(RpcError::BAD_RESPONSE, "stream ended without a response")))
+      }
+    }
+  }).to_string()
+}
+
+fn generate_discriminant_asserts(type_name: &str, discriminants: &[(&EnumValue, i64)]) -> String {
+  let mut imp = vec!["const _: () = {".to_owned()];
+
+  for (item, value) in discriminants {
+    let item_name = unqualified(&item.name);
+    imp.push(format!(r#"assert!({type_name}::{item_name}.0 == {value}, "{type_name}::{item_name} must be {value} to stay wire compatible with the schema");"#));
+  }
+
+  imp.push("};".to_owned());
+  imp.join("\n")
+}
+
+impl GenerateDiscriminantCheck for EnumDeclaration {
+  fn generate_discriminant_check(&self) -> String {
+    generate_discriminant_asserts(unqualified(&self.name), &self.discriminants())
+  }
+}
+
+impl GenerateDiscriminantCheck for UnionDeclaration {
+  fn generate_discriminant_check(&self) -> String {
+    generate_discriminant_asserts(unqualified(&self.name), &self.discriminants())
+  }
+}
+
+
+fn derive_attribute(options: &RsOptions) -> String {
+  if options.derives.is_empty() {
+    return "".to_owned();
+  }
+
+  let paths = options.derives.iter().map(Derive::path).collect::<Vec<&str>>();
+  format!("#[derive({})]", paths.join(", "))
+}
+
+/** serde helpers for struct arrays longer than 32 elements, emitted only when some struct has one */
+pub fn generate_fixed_array_serde(statements: &Statements, options: &RsOptions) -> String {
+  let has_long_arrays = statements.struct_declaration.values()
+    .flat_map(|struct_decl| struct_decl.fields.values())
+    .any(|field_type| field_type.fixed_length.is_some_and(|length| length > 32));
+  if !options.has_serde() || !has_long_arrays {
+    return "".to_owned();
+  }
+
+  let mut imp = vec!["mod fixed_array {".to_owned()];
+  if options.has_derive(Derive::Serialize) {
+    imp.push(r#"
+  pub fn serialize<S: serde::Serializer, T: serde::Serialize, const N: usize>(value: &[T; N], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(value)
+  }"#.to_owned());
+  }
+  if options.has_derive(Derive::Deserialize) {
+    imp.push(r#"
+  pub fn deserialize<'de, D: serde::Deserializer<'de>, T: serde::Deserialize<'de>, const N: usize>(deserializer: D) -> Result<[T; N], D::Error> {
+    let values = <Vec<T> as serde::Deserialize>::deserialize(deserializer)?;
+    let length = values.len();
+    values.try_into().map_err(|_| serde::de::Error::invalid_length(length, &"an array of the declared length"))
+  }"#.to_owned());
+  }
+  imp.push("}".to_owned());
+  imp.join("\n")
+}
+
+/** flatc enums are newtypes over their underlying integer, they go to JSON as variant names and come back from names or numbers.
+Conflicts with flatc's own `--rust-serialize` */
+impl GenerateSerdeImpl for EnumDeclaration {
+  fn generate_serde_impl(&self, statements: &Statements, options: &RsOptions) -> String {
+    let name = unqualified(&self.name);
+    let mut imp = vec![];
+
+    if options.has_derive(Derive::Serialize) {
+      imp.push(format!(r#"
+impl serde::Serialize for {name} {{
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {{
+    match self.variant_name() {{
+      Some(variant) => serializer.serialize_str(variant),
+      None => serde::Serialize::serialize(&self.0, serializer),
+    }}
+  }}
+}}
+"#));
+    }
+
+    if options.has_derive(Derive::Deserialize) {
+      imp.push(format!(r#"
+impl<'de> serde::Deserialize<'de> for {name} {{
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Repr {{
+      Variant(String),
+      Value({}),
+    }}
+
+    match Repr::deserialize(deserializer)? {{
+      Repr::Value(value) => Ok({name}(value)),
+      Repr::Variant(variant) => {name}::ENUM_VALUES.iter()
+        .find(|value| value.variant_name() == Some(variant.as_str()))
+        .copied()
+        .ok_or_else(|| serde::de::Error::custom(format!("unknown {name} variant {{variant}}"))),
+    }}
+  }}
+}}
+"#, self.type_def.to_rs_string(statements, options)));
+    }
+
+    imp.join("\n")
+  }
+}
+
+/** flatc generates `Option<T>` accessors and builder args for optional scalars */
+fn optional_rs_type(rust_type: String, field_type: &Type) -> String {
+  if field_type.is_optional() {
+    format!("Option<{rust_type}>")
+  } else {
+    rust_type
+  }
+}
+
+fn default_value_to_rs(field_type: &Type) -> String {
+  let default_value = match &field_type.default_value {
+    Some(default_value) => default_value,
+    None => return "Default::default()".to_owned(),
+  };
+
+  match (&field_type.value_type, default_value) {
+    (_, DefaultValue::Null) => "None".to_owned(),
+    (ValueType::CompoundType(enum_name), DefaultValue::Identifier(variant)) => format!("{}::{variant}", unqualified(enum_name)),
+    (ValueType::CompoundType(enum_name), DefaultValue::Integer(value)) => format!("{}({value})", unqualified(enum_name)),
+    (ValueType::Float | ValueType::Float32 | ValueType::Double | ValueType::Float64, DefaultValue::Integer(value)) => format!("{value}.0"),
+    (_, DefaultValue::Integer(value)) => value.to_string(),
+    (_, DefaultValue::Float(value)) => format!("{value:?}"),
+    (_, DefaultValue::Bool(value)) => value.to_string(),
+    (_, DefaultValue::Identifier(_)) => "Default::default()".to_owned(),
+  }
+}
+
+impl GenerateDefaultImpl for TableDeclaration {
+  fn generate_default_impl(&self, statements: &Statements) -> String {
+    let name = unqualified(&self.name);
+    let mut imp = vec![
+      format!("impl Default for {name}Content {{"),
+      "fn default() -> Self {".to_owned(),
+      format!("{name}Content {{"),
+    ];
+
+    for (field_name, field_type) in self.active_fields() {
+      imp.push(format!("{field_name}: {},", default_value_to_rs(field_type)));
+    }
+
+    if !self.fields.contains_key("id") && statements.get_available_commands_union().unwrap().contains(&self.name) {
+      imp.push("id: Default::default(),".to_owned());
+    }
+
+    imp.push("}\n}\n}".to_owned());
+    imp.join("\n")
+  }
+}
+
+impl GenerateDefaultImpl for StructDeclaration {
+  fn generate_default_impl(&self, _: &Statements) -> String {
+    let name = unqualified(&self.name);
+    let mut imp = vec![
+      format!("impl Default for {name}Content {{"),
+      "fn default() -> Self {".to_owned(),
+      format!("{name}Content {{"),
+    ];
+
+    // `Default` is implemented only for arrays up to 32 elements
+    for (field_name, field_type) in self.fields.iter() {
+      match field_type.fixed_length {
+        Some(_) => imp.push(format!("{field_name}: std::array::from_fn(|_| Default::default()),")),
+        None => imp.push(format!("{field_name}: Default::default(),")),
+      }
+    }
+
+    imp.push("}\n}\n}".to_owned());
+    imp.join("\n")
+  }
+}
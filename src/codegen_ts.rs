@@ -1,78 +1,872 @@
 use convert_case::{Case, Casing};
 
-use crate::parser::{DeclType, Statements};
-use crate::ir::{TableDeclaration, RpcDeclaration, Type, UnionDeclaration, ValueType, StructDeclaration};
+use crate::parser::{DeclType, Statements, RPC_ERROR};
+use crate::ir::{unqualified, TableDeclaration, RpcDeclaration, Type, DefaultValue, UnionDeclaration, ValueType, StructDeclaration, EnumDeclaration, EnumValue};
 
 
-pub fn generate_ts_client_side_code(statements: &Statements) -> String {
+/** How 64-bit integers look in content types, flatc generated classes always use bigint */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Int64Repr {
+  #[default]
+  Bigint,
+  Number,
+  String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TsOptions {
+  pub int64: Int64Repr,
+}
+
+
+pub fn generate_ts_client_side_code(statements: &Statements, options: &TsOptions) -> String {
   let mut generated = vec![generate_header(statements)];
 
+  for enum_decl in statements.enum_declarations.values() {
+    generated.push(enum_decl.generate_values_statement());
+  }
+  for union in statements.unions.values() {
+    generated.push(union.generate_values_statement());
+  }
+
   for table in statements.table_declaration.values() {
-    generated.push(table.to_ts_string(statements));
-    generated.push(table.generate_into_function_statement(statements));
+    generated.push(table.to_ts_string(statements, options));
+    generated.push(table.generate_into_function_statement(statements, options));
+    generated.push(table.generate_from_function_statement(statements, options));
+    generated.push(generate_required_check(table, statements));
   };
 
   for struct_decl in statements.struct_declaration.values() {
-    generated.push(struct_decl.to_ts_string(statements));
-    generated.push(struct_decl.generate_into_function_statement(statements));
+    generated.push(struct_decl.to_ts_string(statements, options));
+    generated.push(struct_decl.generate_into_function_statement(statements, options));
+    generated.push(struct_decl.generate_from_function_statement(statements, options));
   }
 
   for union in statements.unions.values() {
-    generated.push(union.to_ts_string(statements));
+    generated.push(union.to_ts_string(statements, options));
+    generated.push(union.generate_into_function_statement(statements, options));
+    generated.push(union.generate_from_function_statement(statements, options));
+  }
+
+  generated.push(generate_rpc_error_class());
+  generated.push(generate_transport_type(statements));
+  generated.push(generate_frame_queue_class());
+  generated.push(generate_websocket_transport_class(statements));
+  generated.push(generate_fetch_transport_class(statements));
+  if statements.has_streaming_requests() {
+    generated.push(generate_error_frame_fn(statements, options));
+    generated.push(generate_end_of_stream_frame_fn(statements));
+    generated.push(generate_start_call_fn(statements));
   }
 
   for rpc in statements.rpc_declarations.values() {
-    generated.push(rpc.to_ts_string(statements));
+    generated.push(rpc.to_ts_string(statements, options));
   }
 
+  generated.push(generate_api_object(statements));
+
   generated.join("\n")
 }
 
 
-pub fn generate_header(statements: &Statements) -> String {
-  let mut imports = vec![];
+pub fn generate_ts_server_side_code(statements: &Statements, options: &TsOptions) -> String {
+  let mut generated = vec![generate_header(statements)];
 
-  for table in statements.table_declaration.values() {
-    imports.push(table.name.to_owned());
-    imports.push(format!("{}T", table.name.to_owned()));
+  for enum_decl in statements.enum_declarations.values() {
+    generated.push(enum_decl.generate_values_statement());
+  }
+  for union in statements.unions.values() {
+    generated.push(union.generate_values_statement());
   }
 
+  for table in statements.table_declaration.values() {
+    generated.push(table.to_ts_string(statements, options));
+    generated.push(table.generate_into_function_statement(statements, options));
+    generated.push(table.generate_from_function_statement(statements, options));
+    generated.push(generate_required_check(table, statements));
+  };
 
   for struct_decl in statements.struct_declaration.values() {
-    imports.push(struct_decl.name.to_owned());
-    imports.push(format!("{}T", struct_decl.name.to_owned()));
+    generated.push(struct_decl.to_ts_string(statements, options));
+    generated.push(struct_decl.generate_into_function_statement(statements, options));
+    generated.push(struct_decl.generate_from_function_statement(statements, options));
+  }
+
+  for union in statements.unions.values() {
+    generated.push(union.to_ts_string(statements, options));
+    generated.push(union.generate_into_function_statement(statements, options));
+    generated.push(union.generate_from_function_statement(statements, options));
+  }
+
+  for rpc in statements.rpc_declarations.values() {
+    generated.push(rpc.to_ts_server_string(statements));
   }
 
-  for name in statements.unions.keys() {
-    imports.push(name.to_owned().to_string());
+  generated.push(generate_rpc_error_class());
+  generated.push(generate_request_handlers_type(statements));
+  generated.push(generate_error_frame_fn(statements, options));
+  if statements.has_streaming_responses() {
+    generated.push(generate_end_of_stream_frame_fn(statements));
+  }
+  generated.push(generate_process_request_fn(statements));
+  if statements.has_streaming_requests() {
+    generated.push(generate_request_contents_fn(statements));
   }
+  generated.push(generate_process_request_frames_fn(statements));
+  if statements.has_streaming_requests() {
+    generated.push(generate_frame_queue_class());
+    generated.push(generate_request_router_class(statements));
+  }
+
+  generated.join("\n")
+}
+
+
+/** flatc emits a barrel file per namespace, `com.acme.storage` is re-exported from `./com/acme/storage` */
+pub fn ts_import_path(namespace: Option<&str>) -> String {
+  match namespace {
+    Some(namespace) => {
+      let segments = namespace.split('.').map(|segment| segment.to_case(Case::Kebab)).collect::<Vec<String>>();
+      format!("./{}", segments.join("/"))
+    }
+    None => "./schema_generated".to_owned(),
+  }
+}
+
+pub fn generate_header(statements: &Statements) -> String {
+  let declarations = statements.table_declaration.keys()
+    .chain(statements.struct_declaration.keys())
+    .chain(statements.unions.keys())
+    .chain(statements.enum_declarations.keys());
 
-  for name in statements.enum_declarations.keys() {
-    imports.push(name.to_owned().to_string());
+  let mut imports = vec![];
+  for (namespace, names) in statements.group_by_namespace(declarations) {
+    let mut names_to_import = vec![];
+
+    for qualified_name in names {
+      let name = unqualified(qualified_name);
+      match protocol_type_name(name) {
+        alias if alias != name => names_to_import.push(format!("{name} as {alias}")),
+        _ => names_to_import.push(name.to_owned()),
+      }
+      if let DeclType::Table(_) | DeclType::Struct(_) = statements.resolve_decl_by_name(qualified_name) {
+        names_to_import.push(format!("{name}T"));
+      }
+    }
+
+    imports.push(format!("import {{ {}  }} from \"{}\";", names_to_import.join(", "), ts_import_path(namespace)));
   }
 
   format!(r#"import * as fb from "flatbuffers";
 const {{Builder}} = fb;
-import {{ {}  }} from "./schema_generated";
-export type Transport = {{
-	sendMessage: (msg: Uint8Array, id: string) => Promise<RootTable>
+{}
+
+"#, imports.join("\n"))
+}
+
+
+/** The generated `RpcError` exception takes the name, so flatc's class of the same name is imported as `RpcErrorTable` */
+pub fn protocol_type_name(name: &str) -> String {
+  let name = unqualified(name);
+  if name == RPC_ERROR {
+    format!("{name}Table")
+  } else {
+    name.to_owned()
+  }
+}
+
+/** Thrown by handlers and rejected with by clients, mirrors the optional RpcError table */
+pub fn generate_rpc_error_class() -> String {
+  r#"export class RpcError extends Error {
+	static readonly BAD_REQUEST = 400;
+	static readonly CANCELLED = 499;
+	static readonly INTERNAL = 500;
+	static readonly NOT_IMPLEMENTED = 501;
+	static readonly BAD_RESPONSE = 502;
+	static readonly UNAVAILABLE = 503;
+
+	constructor(public readonly code: number, message: string, public readonly details: string = "") {
+		super(message);
+		this.name = "RpcError";
+	}
+}
+"#.to_owned()
+}
+
+/** Checks the response carries `output`, an RpcError payload or anything else is thrown as RpcError */
+fn generate_response_check(output: &str, statements: &Statements) -> String {
+  generate_payload_check("response", output, "BAD_RESPONSE", "response payload", statements)
+}
+
+/** Checks `root` carries `expected`, a payload of another type is thrown with the `mismatch` code */
+fn generate_payload_check(root: &str, expected: &str, mismatch: &str, what: &str, statements: &Statements) -> String {
+  let expected = unqualified(expected);
+  let root_union_name = unqualified(&statements.get_available_commands_union().unwrap().name);
+  let mut imp = vec![
+    format!("switch ({root}.payloadType()) {{"),
+    format!("case {root_union_name}.{expected}:"),
+    "break;".to_owned(),
+  ];
+
+  if let Some(error_table) = statements.rpc_error_table() {
+    let details = if error_table.fields.contains_key("details") { r#", error.details() ?? """# } else { "" };
+    imp.push(format!("case {root_union_name}.{RPC_ERROR}: {{"));
+    imp.push(format!("const error = new {}();", protocol_type_name(RPC_ERROR)));
+    imp.push(format!("{root}.payload(error);"));
+    imp.push(format!(r#"throw new RpcError(Number(error.code()), error.message() ?? ""{details});"#));
+    imp.push("}".to_owned());
+  }
+
+  imp.push("default:".to_owned());
+  imp.push(format!("throw new RpcError(RpcError.{mismatch}, `{what} must be {expected}, got ${{{root_union_name}[{root}.payloadType()]}}`);"));
+  imp.push("}".to_owned());
+  imp.join("\n")
+}
+
+/** Encodes a failure as an RpcError payload */
+fn generate_error_frame_fn(statements: &Statements, options: &TsOptions) -> String {
+  let root_union_name = unqualified(&statements.get_available_commands_union().unwrap().name);
+  let root_table_name = unqualified(statements.root_type_name.as_ref().unwrap());
+  let error_table = match statements.rpc_error_table() {
+    Some(error_table) => error_table,
+    None => return "".to_owned(),
+  };
+
+  let mut fields = vec![];
+  for (field_name, field_type) in error_table.active_fields() {
+    match (field_name.as_str(), field_type.to_ts_string(statements, options).as_str()) {
+      ("code", "bigint") => fields.push("code: BigInt(rpcError.code)".to_owned()),
+      ("code", _) => fields.push("code: rpcError.code".to_owned()),
+      ("message", _) | ("details", _) => fields.push(format!("{field_name}: rpcError.{field_name}")),
+      _ => {}
+    }
+  }
+
+  let into_function_prefix = RPC_ERROR.to_case(Case::Camel);
+  let mut imp = vec![
+    "const errorFrame = (id: string, error: unknown): Uint8Array => {".to_owned(),
+    "const rpcError = error instanceof RpcError ? error : new RpcError(RpcError.INTERNAL, String(error));".to_owned(),
+    "const builder = new Builder();".to_owned(),
+    format!("const payloadOffset = {into_function_prefix}ContentIntoProtocolClass({{ {} }}).pack(builder);", fields.join(", ")),
+    "const idOffset = builder.createString(id);".to_owned(),
+    format!("const responseRoot = {root_table_name}.create{root_table_name}(builder, idOffset, {root_union_name}.{RPC_ERROR}, payloadOffset);"),
+    "builder.finish(responseRoot);".to_owned(),
+    "return builder.asUint8Array();".to_owned(),
+  ];
+  imp.push("}".to_owned());
+  imp.join("\n")
+}
+
+/** `method` is `Service/Method`, for transports routing by it. Transports without `openStream` or `openCall`
+can't call methods streaming their responses or requests */
+pub fn generate_transport_type(statements: &Statements) -> String {
+  format!(r#"export type Transport = {{
+	sendMessage: (msg: Uint8Array, id: string, method: string) => Promise<{0}>
+	openStream?: (msg: Uint8Array, id: string, method: string) => AsyncIterable<{0}>
+	openCall?: (msg: Uint8Array, id: string, method: string) => Promise<StreamingCall>
 }}
 
-"#, imports.join(", "))
+/** A call opened with its first request, the rest go through `send` and end with an end of stream marker or an error frame */
+export type StreamingCall = {{
+	send: (msg: Uint8Array) => Promise<void>
+	responses: AsyncIterable<{0}>
+}}
+"#, unqualified(statements.root_type_name.as_ref().unwrap()))
 }
 
+/** Sockets can't stop reading in browsers and Node alike, so a stream fails once its consumer falls this far behind */
+pub fn generate_frame_queue_class() -> String {
+  r#"class FrameQueue<T> implements AsyncIterable<T> {
+	static readonly LIMIT = 64;
+
+	private readonly frames: T[] = [];
+	private error: Error | undefined;
+	private stopped = false;
+	private wake = () => {};
+
+	/** `false` once the consumer stopped or fell behind, the frame is dropped then */
+	push(frame: T): boolean {
+		if (this.stopped || this.error !== undefined) {
+			return false;
+		}
+		if (this.frames.length >= FrameQueue.LIMIT) {
+			this.fail(new RpcError(RpcError.UNAVAILABLE, `more than ${FrameQueue.LIMIT} frames are waiting, the consumer fell behind`));
+			return false;
+		}
+		this.frames.push(frame);
+		this.wake();
+		return true;
+	}
+
+	/** The consumer gets `error` next, frames still waiting are dropped */
+	fail(error: Error): void {
+		this.error ??= error;
+		this.frames.length = 0;
+		this.wake();
+	}
+
+	async *[Symbol.asyncIterator](): AsyncIterator<T> {
+		try {
+			while (true) {
+				const frame = this.frames.shift();
+				if (frame !== undefined) {
+					yield frame;
+					continue;
+				}
+				if (this.error !== undefined) {
+					throw this.error;
+				}
+				await new Promise<void>((resolve) => this.wake = resolve);
+			}
+		} finally {
+			this.stopped = true;
+			this.frames.length = 0;
+		}
+	}
+}
+"#.to_owned()
+}
+
+/** Multiplexes requests over one socket, a response settles the request with the same root table `id`,
+a stream receives every frame with its `id` until the caller stops iterating */
+pub fn generate_websocket_transport_class(statements: &Statements) -> String {
+  let root_table_name = unqualified(statements.root_type_name.as_ref().unwrap());
+  format!(r#"export class WebSocketTransport implements Transport {{
+	/** Bytes the socket may buffer before sending requests of a stream waits, browsers would buffer without limit */
+	static readonly HIGH_WATER_MARK = 1 << 20;
+
+	// `receive` tells whether the request keeps waiting for more responses
+	private readonly pending = new Map<string, {{ receive: (response: {root_table_name}) => boolean, reject: (error: Error) => void }}>();
+	private readonly opened: Promise<void>;
+
+	constructor(private readonly socket: WebSocket) {{
+		socket.binaryType = "arraybuffer";
+		this.opened = new Promise((resolve, reject) => {{
+			if (socket.readyState === WebSocket.OPEN) {{
+				resolve();
+				return;
+			}}
+			socket.addEventListener("open", () => resolve(), {{ once: true }});
+			socket.addEventListener("close", () => reject(new RpcError(RpcError.UNAVAILABLE, "WebSocket closed before opening")), {{ once: true }});
+		}});
+		// rejections surface from sendMessage, openStream and openCall
+		this.opened.catch(() => {{}});
+
+		socket.addEventListener("message", (event: MessageEvent) => this.onMessage(event));
+		socket.addEventListener("close", () => this.rejectAll("WebSocket closed"));
+		socket.addEventListener("error", () => this.rejectAll("WebSocket failed"));
+	}}
+
+	static connect(url: string | URL, protocols?: string | string[]): WebSocketTransport {{
+		return new WebSocketTransport(new WebSocket(url, protocols));
+	}}
+
+	async sendMessage(msg: Uint8Array, id: string): Promise<{root_table_name}> {{
+		await this.opened;
+		this.checkCanSend(id);
+
+		return new Promise((resolve, reject) => {{
+			this.pending.set(id, {{
+				receive: (response) => {{
+					resolve(response);
+					return false;
+				}},
+				reject,
+			}});
+			this.socket.send(msg);
+		}});
+	}}
+
+	async *openStream(msg: Uint8Array, id: string): AsyncIterable<{root_table_name}> {{
+		const responses = await this.open(msg, id);
+		try {{
+			yield* responses;
+		}} finally {{
+			this.pending.delete(id);
+		}}
+	}}
+
+	async openCall(msg: Uint8Array, id: string): Promise<StreamingCall> {{
+		const responses = await this.open(msg, id);
+		const pending = this.pending;
+		return {{
+			send: (msg) => this.sendWhenDrained(msg),
+			responses: (async function* () {{
+				try {{
+					yield* responses;
+				}} finally {{
+					pending.delete(id);
+				}}
+			}})(),
+		}};
+	}}
+
+	close(): void {{
+		this.socket.close();
+	}}
+
+	private async open(msg: Uint8Array, id: string): Promise<FrameQueue<{root_table_name}>> {{
+		await this.opened;
+		this.checkCanSend(id);
+
+		const responses = new FrameQueue<{root_table_name}>();
+		this.pending.set(id, {{
+			receive: (response) => responses.push(response),
+			reject: (error) => responses.fail(error),
+		}});
+		this.socket.send(msg);
+		return responses;
+	}}
+
+	private async sendWhenDrained(msg: Uint8Array): Promise<void> {{
+		while (this.socket.readyState === WebSocket.OPEN && this.socket.bufferedAmount > WebSocketTransport.HIGH_WATER_MARK) {{
+			await new Promise((resolve) => setTimeout(resolve, 10));
+		}}
+		if (this.socket.readyState !== WebSocket.OPEN) {{
+			throw new RpcError(RpcError.UNAVAILABLE, "WebSocket is not open");
+		}}
+		this.socket.send(msg);
+	}}
+
+	private checkCanSend(id: string): void {{
+		if (this.socket.readyState !== WebSocket.OPEN) {{
+			throw new RpcError(RpcError.UNAVAILABLE, "WebSocket is not open");
+		}}
+		if (this.pending.has(id)) {{
+			throw new RpcError(RpcError.BAD_REQUEST, `request ${{id}} is already in flight`);
+		}}
+	}}
+
+	private onMessage(event: MessageEvent): void {{
+		if (!(event.data instanceof ArrayBuffer)) {{
+			return;
+		}}
+		const response = {root_table_name}.getRootAs{root_table_name}(new fb.ByteBuffer(new Uint8Array(event.data)));
+		const id = response.id();
+		// responses nobody waits for anymore, or without an id, are dropped
+		const request = id == null ? undefined : this.pending.get(id);
+		if (id == null || request === undefined) {{
+			return;
+		}}
+		if (!request.receive(response)) {{
+			this.pending.delete(id);
+		}}
+	}}
+
+	private rejectAll(message: string): void {{
+		const error = new RpcError(RpcError.UNAVAILABLE, message);
+		for (const {{ reject }} of this.pending.values()) {{
+			reject(error);
+		}}
+		this.pending.clear();
+	}}
+}}
+"#)
+}
+
+/** POSTs every request to `{baseUrl}/Service/Method`, a non 2xx status is thrown as RpcError with that code */
+pub fn generate_fetch_transport_class(statements: &Statements) -> String {
+  let root_table_name = unqualified(statements.root_type_name.as_ref().unwrap());
+  format!(r#"export class FetchTransport implements Transport {{
+	static readonly CONTENT_TYPE = "application/x-flatbuffers";
+
+	constructor(private readonly baseUrl: string, private readonly init: RequestInit = {{}}) {{}}
+
+	async sendMessage(msg: Uint8Array, id: string, method: string): Promise<{root_table_name}> {{
+		const headers = new Headers(this.init.headers);
+		headers.set("content-type", FetchTransport.CONTENT_TYPE);
+
+		let response: Response;
+		try {{
+			response = await fetch(`${{this.baseUrl.replace(/\/+$/, "")}}/${{method}}`, {{ ...this.init, method: "POST", headers, body: msg }});
+		}} catch (e) {{
+			throw new RpcError(RpcError.UNAVAILABLE, `request ${{id}} failed: ${{e}}`);
+		}}
+		if (!response.ok) {{
+			throw new RpcError(response.status, `request ${{id}} failed with HTTP ${{response.status}}`, await response.text());
+		}}
+
+		const buffer = new Uint8Array(await response.arrayBuffer());
+		return {root_table_name}.getRootAs{root_table_name}(new fb.ByteBuffer(buffer));
+	}}
+}}
+"#)
+}
+
+
+/** Opens a call with the first of `requests` and sends the rest while the responses are read, then the end of stream marker.
+A failure to produce a request cancels the stream and fails the responses with that error */
+pub fn generate_start_call_fn(statements: &Statements) -> String {
+  let root_table_name = unqualified(statements.root_type_name.as_ref().unwrap());
+  format!(r#"const startCall = async <T extends {{ id?: string }}>(
+	transport: Transport,
+	method: string,
+	requests: AsyncIterable<T> | Iterable<T>,
+	encode: (content: T, id: string) => Uint8Array,
+): Promise<{{ id: string, responses: AsyncIterable<{root_table_name}> }}> => {{
+	if (transport.openCall === undefined) {{
+		throw new RpcError(RpcError.NOT_IMPLEMENTED, "transport can't stream requests");
+	}}
+	const contents = (async function* () {{
+		yield* requests;
+	}})();
+	const first = await contents.next();
+	if (first.done) {{
+		throw new RpcError(RpcError.BAD_REQUEST, `${{method}} is opened with its first request, got none`);
+	}}
+	const id = first.value.id || globalThis.crypto.randomUUID();
+	const call = await transport.openCall(encode(first.value, id), id, method);
+
+	// stops once the responses aren't read anymore, the server has answered then
+	let answered = false;
+	const sending = (async () => {{
+		try {{
+			for (let next = await contents.next(); !next.done && !answered; next = await contents.next()) {{
+				await call.send(encode(next.value, id));
+			}}
+		}} catch (error) {{
+			await call.send(errorFrame(id, error instanceof RpcError ? error : new RpcError(RpcError.CANCELLED, String(error)))).catch(() => {{}});
+			throw error;
+		}}
+		await contents.return(undefined);
+		await call.send(endOfStreamFrame(id));
+	}})();
+	const failed = new Promise<never>((_, reject) => sending.catch(reject));
+	failed.catch(() => {{}});
+
+	const responses = async function* () {{
+		const iterator = call.responses[Symbol.asyncIterator]();
+		try {{
+			while (true) {{
+				const next = await Promise.race([iterator.next(), failed]);
+				if (next.done) {{
+					return;
+				}}
+				yield next.value;
+			}}
+		}} finally {{
+			answered = true;
+			await iterator.return?.();
+		}}
+	}};
+	return {{ id, responses: responses() }};
+}}
+"#)
+}
+
+pub fn generate_api_object(statements: &Statements) -> String {
+  let mut imp = vec![
+    "export const createApiObject = (transport: Transport) => {".to_owned(),
+    "return {".to_owned(),
+  ];
+
+  for rpc in statements.rpc_declarations.values() {
+    let rpc_name = unqualified(&rpc.name);
+    imp.push(format!("{}: create{}Api(transport),", rpc_name.to_case(Case::Camel), rpc_name.to_case(Case::Pascal)));
+  }
+
+  imp.push("}".to_owned());
+  imp.push("}".to_owned());
+  imp.join("\n")
+}
+
+
+pub fn generate_request_handlers_type(statements: &Statements) -> String {
+  let mut imp = vec![
+    "export type RequestHandlers = {".to_owned(),
+  ];
+
+  for rpc in statements.rpc_declarations.values() {
+    let rpc_name = unqualified(&rpc.name);
+    imp.push(format!("{}: {}Handler,", rpc_name.to_case(Case::Camel), rpc_name.to_case(Case::Pascal)));
+  }
+
+  imp.push("}".to_owned());
+  imp.join("\n")
+}
+
+
+pub fn generate_process_request_fn(statements: &Statements) -> String {
+  let root_union_name = unqualified(&statements.get_available_commands_union().unwrap().name);
+  let root_table_name = unqualified(statements.root_type_name.as_ref().unwrap());
+
+  let mut imp = vec![
+    "export const processRequest = async (handlers: RequestHandlers, buffer: Uint8Array): Promise<Uint8Array> => {".to_owned(),
+    format!("const root = {root_table_name}.getRootAs{root_table_name}(new fb.ByteBuffer(buffer));"),
+    "const id = root.id() ?? \"\";".to_owned(),
+  ];
+  let catches_errors = statements.rpc_error_table().is_some();
+  if catches_errors {
+    imp.push("try {".to_owned());
+  }
+  imp.push("switch (root.payloadType()) {".to_owned());
+
+  for rpc in statements.rpc_declarations.values() {
+    let service_name = unqualified(&rpc.name).to_case(Case::Camel);
+    for method in rpc.methods.values() {
+      let method_name = method.name.to_case(Case::Camel);
+      let input = unqualified(&method.input);
+      let output = unqualified(&method.output);
+      let into_function_prefix = output.to_case(Case::Camel);
+
+      if method.is_streaming() {
+        imp.push(format!("case {root_union_name}.{input}:"));
+        imp.push(format!(r#"throw new RpcError(RpcError.BAD_REQUEST, "{}::{} is a streaming method, serve it with processRequestFrames");"#, unqualified(&rpc.name), method.name));
+        continue;
+      }
+
+      imp.push(format!("case {root_union_name}.{input}: {{"));
+      imp.push(format!("const requestData = new {}();", protocol_type_name(input)));
+      imp.push("root.payload(requestData);".to_owned());
+      if has_required_fields(&method.input, statements) {
+        imp.push(format!("check{input}RequiredFields(requestData);"));
+      }
+      imp.push(format!("const content: {input}Content = {{ ...{}ContentFromProtocolClass(requestData.unpack()), id }};", input.to_case(Case::Camel)));
+      imp.push(format!("const response = await handlers.{service_name}.{method_name}(content);"));
+      imp.push("const builder = new Builder();".to_owned());
+      imp.push(format!("const payloadOffset = {into_function_prefix}ContentIntoProtocolClass(response).pack(builder);"));
+      imp.push("const idOffset = builder.createString(id);".to_owned());
+      imp.push(format!("const responseRoot = {root_table_name}.create{root_table_name}(builder, idOffset, {root_union_name}.{output}, payloadOffset);"));
+      imp.push("builder.finish(responseRoot);".to_owned());
+      imp.push("return builder.asUint8Array();".to_owned());
+      imp.push("}".to_owned());
+    }
+  }
+
+  // ends of request streams reach here once the router has no stream open under their id
+  if statements.has_streaming_requests() {
+    imp.push(format!("case {root_union_name}.NONE:"));
+    imp.push(format!("case {root_union_name}.{RPC_ERROR}:"));
+    imp.push("throw new RpcError(RpcError.BAD_REQUEST, `no request stream is open with id ${JSON.stringify(id)}`);".to_owned());
+  }
+  imp.push("default:".to_owned());
+  imp.push(format!("throw new RpcError(RpcError.NOT_IMPLEMENTED, `UNKNOWN PAYLOAD TYPE ${{{root_union_name}[root.payloadType()]}}`);"));
+  imp.push("}".to_owned());
+  if catches_errors {
+    imp.push("} catch (error) {".to_owned());
+    imp.push("return errorFrame(id, error);".to_owned());
+    imp.push("}".to_owned());
+  }
+  imp.push("}".to_owned());
+
+  imp.join("\n")
+}
+
+
+/** Like `processRequest`, but also serves streaming methods: their items are yielded as they come and followed
+by an end of stream marker or an error frame. `requests` are the frames of the request stream after `buffer`.
+Stopping the iteration stops the handler's iterable too */
+pub fn generate_process_request_frames_fn(statements: &Statements) -> String {
+  let requests = if statements.has_streaming_requests() { ", requests: AsyncIterable<Uint8Array> | Iterable<Uint8Array> = []" } else { "" };
+  let mut imp = vec![
+    format!("export async function* processRequestFrames(handlers: RequestHandlers, buffer: Uint8Array{requests}): AsyncGenerator<Uint8Array> {{"),
+  ];
+
+  if !statements.has_streaming() {
+    imp.push("yield await processRequest(handlers, buffer);".to_owned());
+    imp.push("}".to_owned());
+    return imp.join("\n");
+  }
+
+  let root_union_name = unqualified(&statements.get_available_commands_union().unwrap().name);
+  let root_table_name = unqualified(statements.root_type_name.as_ref().unwrap());
+  imp.push(format!("const root = {root_table_name}.getRootAs{root_table_name}(new fb.ByteBuffer(buffer));"));
+  imp.push("const id = root.id() ?? \"\";".to_owned());
+  imp.push("switch (root.payloadType()) {".to_owned());
+
+  for rpc in statements.rpc_declarations.values() {
+    let service_name = unqualified(&rpc.name).to_case(Case::Camel);
+    for method in rpc.methods.values().filter(|method| method.is_streaming()) {
+      let method_name = method.name.to_case(Case::Camel);
+      let input = unqualified(&method.input);
+      let output = unqualified(&method.output);
+
+      imp.push(format!("case {root_union_name}.{input}: {{"));
+      imp.push("try {".to_owned());
+      let argument = if method.streams_requests() {
+        imp.push(format!("const contents = requestContents(buffer, requests, (root): {input}Content => {{"));
+        imp.push(generate_payload_check("root", input, "BAD_REQUEST", "request stream item", statements));
+        "contents"
+      } else {
+        "content"
+      };
+      imp.push(format!("const requestData = new {}();", protocol_type_name(input)));
+      imp.push("root.payload(requestData);".to_owned());
+      if has_required_fields(&method.input, statements) {
+        imp.push(format!("check{input}RequiredFields(requestData);"));
+      }
+      if method.streams_requests() {
+        imp.push(format!("return {{ ...{}ContentFromProtocolClass(requestData.unpack()), id: root.id() ?? \"\" }};", input.to_case(Case::Camel)));
+        imp.push("});".to_owned());
+      } else {
+        imp.push(format!("const content: {input}Content = {{ ...{}ContentFromProtocolClass(requestData.unpack()), id }};", input.to_case(Case::Camel)));
+      }
+
+      let response_frame = [
+        "const builder = new Builder();".to_owned(),
+        format!("const payloadOffset = {}ContentIntoProtocolClass(response).pack(builder);", output.to_case(Case::Camel)),
+        "const idOffset = builder.createString(id);".to_owned(),
+        format!("const responseRoot = {root_table_name}.create{root_table_name}(builder, idOffset, {root_union_name}.{output}, payloadOffset);"),
+        "builder.finish(responseRoot);".to_owned(),
+        "yield builder.asUint8Array();".to_owned(),
+      ];
+      if method.streams_responses() {
+        imp.push(format!("for await (const response of handlers.{service_name}.{method_name}({argument})) {{"));
+        imp.extend(response_frame);
+        imp.push("}".to_owned());
+      } else {
+        imp.push(format!("const response = await handlers.{service_name}.{method_name}({argument});"));
+        imp.extend(response_frame);
+      }
+      imp.push("} catch (error) {".to_owned());
+      imp.push("yield errorFrame(id, error);".to_owned());
+      imp.push("return;".to_owned());
+      imp.push("}".to_owned());
+      if method.streams_responses() {
+        imp.push("yield endOfStreamFrame(id);".to_owned());
+      }
+      imp.push("return;".to_owned());
+      imp.push("}".to_owned());
+    }
+  }
+
+  imp.push("default:".to_owned());
+  imp.push("yield await processRequest(handlers, buffer);".to_owned());
+  imp.push("}".to_owned());
+  imp.push("}".to_owned());
+  imp.join("\n")
+}
+
+/** Contents of a request stream: the opening frame, then every frame routed to the call up to the end of stream marker */
+fn generate_request_contents_fn(statements: &Statements) -> String {
+  let root_union_name = unqualified(&statements.get_available_commands_union().unwrap().name);
+  let root_table_name = unqualified(statements.root_type_name.as_ref().unwrap());
+  format!(r#"async function* requestContents<T>(buffer: Uint8Array, requests: AsyncIterable<Uint8Array> | Iterable<Uint8Array>, decode: (root: {root_table_name}) => T): AsyncGenerator<T> {{
+	yield decode({root_table_name}.getRootAs{root_table_name}(new fb.ByteBuffer(buffer)));
+	for await (const frame of requests) {{
+		const root = {root_table_name}.getRootAs{root_table_name}(new fb.ByteBuffer(frame));
+		if (root.payloadType() === {root_union_name}.NONE) {{
+			return;
+		}}
+		yield decode(root);
+	}}
+	throw new RpcError(RpcError.UNAVAILABLE, "connection closed before the request stream ended");
+}}
+"#)
+}
+
+/** Serves the frames of one connection with `processRequestFrames`, frames continuing a request stream
+are told apart by their payload type and routed to the call with their root table `id` */
+pub fn generate_request_router_class(statements: &Statements) -> String {
+  let root_union_name = unqualified(&statements.get_available_commands_union().unwrap().name);
+  let root_table_name = unqualified(statements.root_type_name.as_ref().unwrap());
+  let streamed_inputs = statements.rpc_declarations.values()
+    .flat_map(|rpc| rpc.methods.values())
+    .filter(|method| method.streams_requests())
+    .map(|method| format!("\t\t\tcase {root_union_name}.{}:", unqualified(&method.input)))
+    .collect::<Vec<String>>()
+    .join("\n");
+
+  format!(r#"/** Every response frame waits for the promise `send` returns, so a slow connection slows its calls down.
+A call reading its requests too slowly fails instead of buffering them without limit */
+export class RequestRouter {{
+	// `null` once the call stopped reading, the rest of its stream is dropped
+	private readonly streams = new Map<string, FrameQueue<Uint8Array> | null>();
+
+	constructor(private readonly handlers: RequestHandlers, private readonly send: (frame: Uint8Array) => void | Promise<void>) {{}}
+
+	receive(buffer: Uint8Array): void {{
+		const root = {root_table_name}.getRootAs{root_table_name}(new fb.ByteBuffer(buffer));
+		const id = root.id() ?? "";
+		switch (root.payloadType()) {{
+{streamed_inputs} {{
+				const requests = this.streams.get(id);
+				if (requests === undefined) {{
+					const requests = new FrameQueue<Uint8Array>();
+					this.streams.set(id, requests);
+					void this.serve(buffer, requests);
+				}} else if (requests !== null && !requests.push(buffer)) {{
+					this.streams.set(id, null);
+				}}
+				return;
+			}}
+			case {root_union_name}.NONE:
+			case {root_union_name}.{RPC_ERROR}: {{
+				const requests = this.streams.get(id);
+				this.streams.delete(id);
+				if (requests === undefined) {{
+					// answered with an error, nobody else would answer the sender
+					void this.serve(buffer, []);
+				}} else {{
+					requests?.push(buffer);
+				}}
+				return;
+			}}
+			default:
+				void this.serve(buffer, []);
+		}}
+	}}
+
+	/** Fails the request streams still open, for a closed connection */
+	close(): void {{
+		for (const requests of this.streams.values()) {{
+			requests?.fail(new RpcError(RpcError.UNAVAILABLE, "connection closed before the request stream ended"));
+		}}
+		this.streams.clear();
+	}}
+
+	private async serve(buffer: Uint8Array, requests: AsyncIterable<Uint8Array> | Iterable<Uint8Array>): Promise<void> {{
+		try {{
+			for await (const frame of processRequestFrames(this.handlers, buffer, requests)) {{
+				await this.send(frame);
+			}}
+		}} catch {{
+			// the connection is gone, nobody is left to answer
+		}}
+	}}
+}}
+"#)
+}
+
+/** A root table with an empty payload ends a stream of either side, failed streams end with an RpcError payload instead */
+fn generate_end_of_stream_frame_fn(statements: &Statements) -> String {
+  let root_union_name = unqualified(&statements.get_available_commands_union().unwrap().name);
+  let root_table_name = unqualified(statements.root_type_name.as_ref().unwrap());
+  let imp = [
+    "const endOfStreamFrame = (id: string): Uint8Array => {".to_owned(),
+    "const builder = new Builder();".to_owned(),
+    "const idOffset = builder.createString(id);".to_owned(),
+    format!("const responseRoot = {root_table_name}.create{root_table_name}(builder, idOffset, {root_union_name}.NONE, 0);"),
+    "builder.finish(responseRoot);".to_owned(),
+    "return builder.asUint8Array();".to_owned(),
+    "}".to_owned(),
+  ];
+  imp.join("\n")
+}
 
 pub trait ToTsStatement {
-  fn to_ts_string(&self, statements: &Statements) -> String;
+  fn to_ts_string(&self, statements: &Statements, options: &TsOptions) -> String;
+}
+
+pub trait ToTsServerStatement {
+  fn to_ts_server_string(&self, statements: &Statements) -> String;
+}
+
+pub trait GenerateValuesStatement {
+  fn generate_values_statement(&self) -> String;
+}
+
+pub trait GenerateFromFunctionStatement {
+  fn generate_from_function_statement(&self, statements: &Statements, options: &TsOptions) -> String;
 }
 
 pub trait GenerateIntoFunctionStatement {
-  fn generate_into_function_statement(&self, statements: &Statements) -> String;
+  fn generate_into_function_statement(&self, statements: &Statements, options: &TsOptions) -> String;
 }
 
 
 impl ToTsStatement for ValueType {
-  fn to_ts_string(&self, _: &Statements) -> String {
+  fn to_ts_string(&self, statements: &Statements, options: &TsOptions) -> String {
     match self {
       ValueType::Bool => "boolean".to_owned(),
       ValueType::String => "string".to_owned(),
@@ -90,63 +884,167 @@ impl ToTsStatement for ValueType {
       ValueType::Uint16 |
       ValueType::Int32 |
       ValueType::Uint32 |
-      ValueType::Float32 => "number".to_owned(),
+      ValueType::Float32 |
+      ValueType::Double |
+      ValueType::Float64 => "number".to_owned(),
 
       ValueType::Int64 |
       ValueType::Uint64 |
       ValueType::Long |
-      ValueType::Ulong |
-      ValueType::Double |
-      ValueType::Float64 => "bigint".to_owned(),
-
-      ValueType::CompoundType(compound) => format!("{compound}Content")
+      ValueType::Ulong => match options.int64 {
+        Int64Repr::Bigint => "bigint".to_owned(),
+        Int64Repr::Number => "number".to_owned(),
+        Int64Repr::String => "string".to_owned(),
+      },
+
+      ValueType::CompoundType(compound) => match statements.resolve_decl_by_name(compound) {
+        DeclType::Enum(_) => unqualified(compound).to_owned(),
+        _ => format!("{}Content", unqualified(compound)),
+      }
     }
   }
 }
 
 impl ToTsStatement for Type {
-  fn to_ts_string(&self, statements: &Statements) -> String {
+  fn to_ts_string(&self, statements: &Statements, options: &TsOptions) -> String {
     if self.is_array {
-      format!("Array<{}>", self.value_type.to_ts_string(statements))
+      format!("Array<{}>", self.value_type.to_ts_string(statements, options))
     } else {
-      self.value_type.to_ts_string(statements)
+      self.value_type.to_ts_string(statements, options)
     }
   }
 }
 
+fn is_int64(value_type: &ValueType) -> bool {
+  matches!(value_type, ValueType::Int64 | ValueType::Uint64 | ValueType::Long | ValueType::Ulong)
+}
+
+/** Numeric elements of fixed length struct arrays are held in typed arrays */
+fn typed_array_name(value_type: &ValueType, options: &TsOptions) -> Option<&'static str> {
+  match value_type {
+    ValueType::Byte | ValueType::Int8 => Some("Int8Array"),
+    ValueType::Ubyte | ValueType::Uint8 => Some("Uint8Array"),
+    ValueType::Short | ValueType::Int16 => Some("Int16Array"),
+    ValueType::Ushort | ValueType::Uint16 => Some("Uint16Array"),
+    ValueType::Int | ValueType::Int32 => Some("Int32Array"),
+    ValueType::Uint | ValueType::Uint32 => Some("Uint32Array"),
+    ValueType::Float | ValueType::Float32 => Some("Float32Array"),
+    ValueType::Double | ValueType::Float64 => Some("Float64Array"),
+    ValueType::Long | ValueType::Int64 if options.int64 == Int64Repr::Bigint => Some("BigInt64Array"),
+    ValueType::Ulong | ValueType::Uint64 if options.int64 == Int64Repr::Bigint => Some("BigUint64Array"),
+    _ => None,
+  }
+}
+
+/** Converts a content value of `field_type` into what flatc generated classes expect */
+fn into_protocol_value(value: String, field_type: &Type, options: &TsOptions) -> String {
+  let is_array = field_type.is_array || field_type.fixed_length.is_some();
+  match (is_int64(&field_type.value_type), options.int64, is_array) {
+    (true, Int64Repr::Number | Int64Repr::String, false) => format!("BigInt({value})"),
+    (true, Int64Repr::Number | Int64Repr::String, true) => format!("{value}.map(BigInt)"),
+    _ => value,
+  }
+}
+
+/** Converts a value read from flatc generated classes into its content representation */
+fn from_protocol_value(value: String, field_type: &Type, options: &TsOptions) -> String {
+  let is_array = field_type.is_array || field_type.fixed_length.is_some();
+  match (is_int64(&field_type.value_type), options.int64, is_array) {
+    (true, Int64Repr::Number, false) => format!("Number({value})"),
+    (true, Int64Repr::Number, true) => format!("{value}.map(Number)"),
+    (true, Int64Repr::String, false) => format!("{value}.toString()"),
+    (true, Int64Repr::String, true) => format!("{value}.map(String)"),
+    _ => value,
+  }
+}
+
+/** Fields with a schema default may be omitted from content types */
+fn optional_marker(field_type: &Type) -> &'static str {
+  if field_type.default_value.is_some() {
+    "?"
+  } else {
+    ""
+  }
+}
+
+/** flatc reads absent optional scalars as null */
+fn nullable_ts_type(ts_type: String, field_type: &Type) -> String {
+  if field_type.is_optional() {
+    format!("{ts_type} | null")
+  } else {
+    ts_type
+  }
+}
+
+/** Applies a conversion of an optional scalar only when it's set */
+fn convert_nullable(value: String, converted: String, field_type: &Type) -> String {
+  if field_type.is_optional() && converted != value {
+    format!("{value} == null ? null : {converted}")
+  } else {
+    converted
+  }
+}
+
+fn field_with_default(field_name: &str, field_type: &Type, statements: &Statements, options: &TsOptions) -> String {
+  let default_value = match &field_type.default_value {
+    Some(default_value) => default_value,
+    None => return into_protocol_value(format!("content.{field_name}"), field_type, options),
+  };
+
+  let literal = match (&field_type.value_type, default_value) {
+    (_, DefaultValue::Null) => {
+      let value = format!("content.{field_name}");
+      return match into_protocol_value(value.to_owned(), field_type, options) {
+        converted if converted != value => convert_nullable(value, converted, field_type),
+        _ => format!("{value} ?? null"),
+      };
+    }
+    (ValueType::CompoundType(enum_name), DefaultValue::Identifier(variant)) => format!("{}.{variant}", unqualified(enum_name)),
+    (_, DefaultValue::Integer(value)) => match field_type.to_ts_string(statements, options).as_str() {
+      "bigint" => format!("{value}n"),
+      "string" => format!("\"{value}\""),
+      _ => value.to_string(),
+    },
+    (_, DefaultValue::Float(value)) => format!("{value:?}"),
+    (_, DefaultValue::Bool(value)) => value.to_string(),
+    (_, DefaultValue::Identifier(variant)) => variant.to_owned(),
+  };
+
+  into_protocol_value(format!("content.{field_name} ?? {literal}"), field_type, options)
+}
+
 impl ToTsStatement for TableDeclaration {
-  fn to_ts_string(&self, statements: &Statements) -> String {
-    let struct_name = format!("{}Content", self.name);
+  fn to_ts_string(&self, statements: &Statements, options: &TsOptions) -> String {
+    let struct_name = format!("{}Content", unqualified(&self.name));
 
     let mut type_definition = vec![];
     type_definition.push(format!("export type {} = {{", struct_name));
     let mut is_id_exist = false;
 
-    for (field_name, field_type) in self.fields.iter() {
+    for (field_name, field_type) in self.active_fields() {
       match (&field_type.value_type, &field_type.is_array) {
         (ValueType::CompoundType(name), false) => {
           if let Some(union) = statements.unions.get(name) {
-            type_definition.push(format!("\t{}Type: {},", field_name, union.name));
-            type_definition.push(format!("\t{}: {}Content,", field_name, union.name))
+            type_definition.push(format!("\t{}: {}Content | null,", field_name, unqualified(&union.name)))
           } else if let Some(enum_decl) = statements.enum_declarations.get(name) {
-            type_definition.push(format!("\t{}: {},", field_name, enum_decl.name))
+            type_definition.push(format!("\t{}{}: {},", field_name, optional_marker(field_type), nullable_ts_type(unqualified(&enum_decl.name).to_owned(), field_type)))
           } else {
-            type_definition.push(format!("\t{}: {},", field_name, field_type.to_ts_string(statements)))
+            type_definition.push(format!("\t{}: {},", field_name, field_type.to_ts_string(statements, options)))
           }
         }
         (ValueType::CompoundType(name), true) => {
           if let Some(enum_decl) = statements.enum_declarations.get(name) {
-            type_definition.push(format!("\t{}: Array<{}>,", field_name, enum_decl.name))
+            type_definition.push(format!("\t{}: Array<{}>,", field_name, unqualified(&enum_decl.name)))
           } else {
-            type_definition.push(format!("\t{}: {},", field_name, field_type.to_ts_string(statements)))
+            type_definition.push(format!("\t{}: {},", field_name, field_type.to_ts_string(statements, options)))
           }
         }
         _ => {
           if field_name == "id" {
             is_id_exist = true;
-            type_definition.push(format!("\t{}?: {},", field_name, field_type.to_ts_string(statements)));
+            type_definition.push(format!("\t{}?: {},", field_name, field_type.to_ts_string(statements, options)));
           } else {
-            type_definition.push(format!("\t{}: {},", field_name, field_type.to_ts_string(statements)));
+            type_definition.push(format!("\t{}{}: {},", field_name, optional_marker(field_type), nullable_ts_type(field_type.to_ts_string(statements, options), field_type)));
           }
         }
       }
@@ -160,9 +1058,9 @@ impl ToTsStatement for TableDeclaration {
 }
 
 impl GenerateIntoFunctionStatement for TableDeclaration {
-  fn generate_into_function_statement(&self, statements: &Statements) -> String {
-    let origin_name = self.name.as_str();
-    let name = format!("{}Content", self.name);
+  fn generate_into_function_statement(&self, statements: &Statements, options: &TsOptions) -> String {
+    let origin_name = unqualified(&self.name);
+    let name = format!("{origin_name}Content");
     let function_name_prefix = name.to_case(Case::Camel);
     let mut imp = vec![
       format!("const {function_name_prefix}IntoProtocolClass = (content: {name}): {origin_name}T => {{"),
@@ -173,23 +1071,34 @@ impl GenerateIntoFunctionStatement for TableDeclaration {
 
     for field_name in &self.fields_order {
       let field = self.fields.get(field_name).unwrap();
+      if field.metadata.is_deprecated() {
+        continue;
+      }
+
       match (&field.value_type, field.is_array) {
-        (ValueType::CompoundType(name), false) => {
+        (ValueType::CompoundType(compound), false) => {
+          let name = unqualified(compound);
           let generated_name = format!("{name}Content");
 
-          match statements.resolve_decl_by_name(name) {
+          match statements.resolve_decl_by_name(compound) {
             DeclType::Table(_) | DeclType::Struct(_) => {
               let function_name_prefix = generated_name.to_case(Case::Camel);
               fields_as_args.push(format!("{function_name_prefix}IntoProtocolClass(content.{field_name})"))
             }
             DeclType::Enum(_) => {
-              fields_as_args.push(format!("content.{field_name}"))
+              fields_as_args.push(field_with_default(field_name, field, statements, options))
+            }
+            DeclType::Union(_) => {
+              let function_name_prefix = generated_name.to_case(Case::Camel);
+              fields_as_args.push(format!("content.{field_name}?.type ?? {name}.NONE"));
+              fields_as_args.push(format!("content.{field_name} ? {function_name_prefix}IntoProtocolClass(content.{field_name}) : null"))
             }
             _ => {}
           };
         }
-        (ValueType::CompoundType(name), true) => {
-          match statements.resolve_decl_by_name(name) {
+        (ValueType::CompoundType(compound), true) => {
+          let name = unqualified(compound);
+          match statements.resolve_decl_by_name(compound) {
             DeclType::Table(_) | DeclType::Struct(_) => {
               let generated_name = format!("{name}Content");
               let function_name_prefix = generated_name.to_case(Case::Camel);
@@ -198,11 +1107,16 @@ impl GenerateIntoFunctionStatement for TableDeclaration {
             DeclType::Enum(_) => {
               fields_as_args.push(format!("content.{field_name}"))
             }
+            DeclType::Union(_) => {
+              let function_name_prefix = format!("{name}Content").to_case(Case::Camel);
+              fields_as_args.push(format!("content.{field_name}.map((item) => item.type)"));
+              fields_as_args.push(format!("content.{field_name}.map({function_name_prefix}IntoProtocolClass)"))
+            }
             _ => {}
           };
         }
         _ => {
-          fields_as_args.push(format!("content.{field_name}"))
+          fields_as_args.push(field_with_default(field_name, field, statements, options))
         }
       }
     };
@@ -217,16 +1131,130 @@ impl GenerateIntoFunctionStatement for TableDeclaration {
 }
 
 
+/** flatc's object API names fields in camel case, content types keep schema names */
+fn from_protocol_field(field_name: &str, field_type: &Type, statements: &Statements, options: &TsOptions) -> String {
+  let value = format!("value.{}", field_name.to_case(Case::Camel));
+
+  if field_type.fixed_length.is_some() {
+    return match (&field_type.value_type, typed_array_name(&field_type.value_type, options)) {
+      (_, Some(typed_array)) => format!("{field_name}: {typed_array}.from({value}),"),
+      (ValueType::CompoundType(type_name), None) if statements.struct_declaration.contains_key(type_name) => {
+        format!("{field_name}: {value}.map({}ContentFromProtocolClass),", unqualified(type_name).to_case(Case::Camel))
+      }
+      _ => format!("{field_name}: {},", from_protocol_value(value, field_type, options)),
+    };
+  }
+
+  match (&field_type.value_type, field_type.is_array) {
+    (ValueType::CompoundType(type_name), false) => match statements.resolve_decl_by_name(type_name) {
+      // flatc builders leave optional tables and structs out, they decode to the default content
+      DeclType::Table(_) | DeclType::Struct(_) => {
+        format!("{field_name}: {}ContentFromProtocolClass({value} ?? new {}T()),", unqualified(type_name).to_case(Case::Camel), unqualified(type_name))
+      }
+      DeclType::Union(_) => format!("{field_name}: {}ContentFromProtocolClass({value}Type, {value}),", unqualified(type_name).to_case(Case::Camel)),
+      _ => format!("{field_name}: {value},"),
+    },
+    (ValueType::CompoundType(type_name), true) => match statements.resolve_decl_by_name(type_name) {
+      DeclType::Table(_) | DeclType::Struct(_) => format!("{field_name}: {value}.map({}ContentFromProtocolClass),", unqualified(type_name).to_case(Case::Camel)),
+      DeclType::Union(_) => {
+        format!("{field_name}: {value}.flatMap((item, index) => {}ContentFromProtocolClass({value}Type[index], item) ?? []),", unqualified(type_name).to_case(Case::Camel))
+      }
+      _ => format!("{field_name}: {value},"),
+    },
+    (ValueType::String, false) => format!(r#"{field_name}: {value} ?? "","#),
+    _ => format!("{field_name}: {},", convert_nullable(value.to_owned(), from_protocol_value(value, field_type, options), field_type)),
+  }
+}
+
+impl GenerateFromFunctionStatement for TableDeclaration {
+  fn generate_from_function_statement(&self, statements: &Statements, options: &TsOptions) -> String {
+    let name = unqualified(&self.name);
+    let function_name_prefix = name.to_case(Case::Camel);
+    let mut imp = vec![
+      format!("export const {function_name_prefix}ContentFromProtocolClass = (value: {name}T): {name}Content => ({{"),
+    ];
+
+    for field_name in &self.fields_order {
+      let field_type = self.fields.get(field_name).unwrap();
+      if !field_type.metadata.is_deprecated() {
+        imp.push(from_protocol_field(field_name, field_type, statements, options));
+      }
+    }
+
+    imp.push("});".to_owned());
+    imp.join("\n")
+  }
+}
+
+impl GenerateFromFunctionStatement for StructDeclaration {
+  fn generate_from_function_statement(&self, statements: &Statements, options: &TsOptions) -> String {
+    let name = unqualified(&self.name);
+    let function_name_prefix = name.to_case(Case::Camel);
+    let mut imp = vec![
+      format!("export const {function_name_prefix}ContentFromProtocolClass = (value: {name}T): {name}Content => ({{"),
+    ];
+
+    for field_name in &self.fields_order {
+      let field_type = self.fields.get(field_name).unwrap();
+      imp.push(from_protocol_field(field_name, field_type, statements, options));
+    }
+
+    imp.push("});".to_owned());
+    imp.join("\n")
+  }
+}
+
+
+/** flatc's TS accessors return null for absent required fields, so presence is checked
+straight in the vtable, the slot of a field is its position with union fields and vectors of unions taking two */
+fn generate_required_check(table: &TableDeclaration, statements: &Statements) -> String {
+  let name = unqualified(&table.name);
+  let mut checks = vec![];
+  let mut slot = 0;
+
+  for field_name in &table.fields_order {
+    let field = table.fields.get(field_name).unwrap();
+    if let ValueType::CompoundType(type_name) = &field.value_type {
+      if statements.unions.contains_key(type_name) {
+        slot += 1;
+      }
+    }
+
+    if field.metadata.is_required() {
+      let vtable_offset = 4 + 2 * slot;
+      checks.push(format!("if (table.bb!.__offset(table.bb_pos, {vtable_offset}) === 0) throw new Error(\"required field {field_name} is absent in {name}\");"));
+    }
+    slot += 1;
+  }
+
+  if checks.is_empty() {
+    return "".to_owned();
+  }
+
+  let mut imp = vec![format!("const check{name}RequiredFields = (table: {}): void => {{", protocol_type_name(name))];
+  imp.extend(checks);
+  imp.push("}".to_owned());
+  imp.join("\n")
+}
+
+fn has_required_fields(table_name: &str, statements: &Statements) -> bool {
+  statements.table_declaration.get(table_name)
+    .map(|table| table.fields.values().any(|field| field.metadata.is_required()))
+    .unwrap_or(false)
+}
+
+/** Discriminated by the flatc generated union enum, `{ type: Shape.Circle, value: CircleContent }` */
 impl ToTsStatement for UnionDeclaration {
-  fn to_ts_string(&self, _: &Statements) -> String {
-    let enum_name = self.name.to_owned();
+  fn to_ts_string(&self, _: &Statements, _: &TsOptions) -> String {
+    let enum_name = unqualified(&self.name);
 
     let mut enum_definition = vec![];
     enum_definition.push(format!("export type {enum_name}Content = "));
 
 
     for variant in self.items.iter() {
-      enum_definition.push(format!("\t | {variant}"));
+      let member = unqualified(&variant.name);
+      enum_definition.push(format!("\t| {{ type: {enum_name}.{member}, value: {member}Content }}"));
     }
 
     enum_definition.push("\n\n".to_owned());
@@ -234,52 +1262,159 @@ impl ToTsStatement for UnionDeclaration {
   }
 }
 
+/** flatc's object API holds union values as a plain union of member classes */
+fn union_protocol_classes(union: &UnionDeclaration) -> String {
+  union.items.iter().map(|item| format!("{}T", unqualified(&item.name))).collect::<Vec<String>>().join(" | ")
+}
+
+impl GenerateIntoFunctionStatement for UnionDeclaration {
+  fn generate_into_function_statement(&self, _: &Statements, _: &TsOptions) -> String {
+    let name = unqualified(&self.name);
+    let function_name_prefix = name.to_case(Case::Camel);
+    let mut imp = vec![
+      format!("const {function_name_prefix}ContentIntoProtocolClass = (content: {name}Content): {} => {{", union_protocol_classes(self)),
+      "switch (content.type) {".to_owned(),
+    ];
+
+    for item in &self.items {
+      let member = unqualified(&item.name);
+      imp.push(format!("case {name}.{member}: return {}ContentIntoProtocolClass(content.value);", member.to_case(Case::Camel)));
+    }
+
+    imp.push("}".to_owned());
+    imp.push("}".to_owned());
+    imp.join("\n")
+  }
+}
+
+impl GenerateFromFunctionStatement for UnionDeclaration {
+  fn generate_from_function_statement(&self, _: &Statements, _: &TsOptions) -> String {
+    let name = unqualified(&self.name);
+    let function_name_prefix = name.to_case(Case::Camel);
+    let mut imp = vec![
+      format!("export const {function_name_prefix}ContentFromProtocolClass = (type: {name}, value: {} | null): {name}Content | null => {{", union_protocol_classes(self)),
+      "if (value === null) return null;".to_owned(),
+      "switch (type) {".to_owned(),
+    ];
+
+    for item in &self.items {
+      let member = unqualified(&item.name);
+      imp.push(format!("case {name}.{member}: return {{ type: {name}.{member}, value: {}ContentFromProtocolClass(value as {member}T) }};", member.to_case(Case::Camel)));
+    }
+
+    // NONE and members unknown to this schema version
+    imp.push("default: return null;".to_owned());
+    imp.push("}".to_owned());
+    imp.push("}".to_owned());
+    imp.join("\n")
+  }
+}
+
 
 impl ToTsStatement for RpcDeclaration {
-  fn to_ts_string(&self, statements: &Statements) -> String {
+  fn to_ts_string(&self, statements: &Statements, _: &TsOptions) -> String {
     let mut methods = vec![];
     let mut imp = vec![];
 
 
     for method in self.methods.values() {
       let method_name = method.name.to_owned().to_case(Case::Camel);
-      let input = &method.input;
-      let output = &method.output;
+      let function_name = format!("{}{}", unqualified(&self.name).to_case(Case::Camel), method.name.to_case(Case::Pascal));
+      let input = unqualified(&method.input);
+      let output = unqualified(&method.output);
 
-      let root_union_name = statements.get_available_commands_union().unwrap().name.as_str();
-      let root_table_name = statements.root_type_name.as_ref().unwrap();
+      let root_union_name = unqualified(&statements.get_available_commands_union().unwrap().name);
+      let root_table_name = unqualified(statements.root_type_name.as_ref().unwrap());
 
-      let input_protocol_interface = statements.table_declaration.get(input).unwrap();
+      let input_protocol_interface = statements.table_declaration.get(&method.input).unwrap();
 
-      methods.push(method_name.to_owned());
-      imp.push(format!("export const {method_name} = async (transport: Transport, content: {input}Content): Promise<{output}Content> => {{", ));
-      imp.push("const builder = new Builder();".to_owned());
+      methods.push((method_name.to_owned(), function_name.to_owned()));
+      let into_function_prefix = unqualified(&input_protocol_interface.name).to_case(Case::Camel);
+      let call_name = format!("{}/{}", unqualified(&self.name), method.name);
 
-      let into_function_prefix = input_protocol_interface.name.as_str().to_case(Case::Camel);
+      if method.streams_requests() {
+        if method.streams_responses() {
+          imp.push(format!("export async function* {function_name}(transport: Transport, requests: AsyncIterable<{input}Content> | Iterable<{input}Content>): AsyncIterable<{output}Content> {{"));
+        } else {
+          imp.push(format!("export const {function_name} = async (transport: Transport, requests: AsyncIterable<{input}Content> | Iterable<{input}Content>): Promise<{output}Content> => {{"));
+        }
+        imp.push(format!(r#"const {{ id, responses }} = await startCall(transport, "{call_name}", requests, (content: {input}Content, id: string) => {{"#));
+        imp.push("const builder = new Builder();".to_owned());
+        imp.push(format!("const payloadOffset = {into_function_prefix}ContentIntoProtocolClass(content).pack(builder);"));
+        imp.push("const idOffset = builder.createString(id);".to_owned());
+        imp.push(format!("const root = {root_table_name}.create{root_table_name}(builder, idOffset, {root_union_name}.{input}, payloadOffset);"));
+        imp.push("builder.finish(root);".to_owned());
+        imp.push("return builder.asUint8Array();".to_owned());
+        imp.push("});".to_owned());
+        imp.push("for await (const response of responses) {".to_owned());
+      } else {
+        if method.streams_responses() {
+          imp.push(format!("export async function* {function_name}(transport: Transport, content: {input}Content): AsyncIterable<{output}Content> {{"));
+          imp.push("if (transport.openStream === undefined) {".to_owned());
+          imp.push(r#"throw new RpcError(RpcError.NOT_IMPLEMENTED, "transport can't open streams");"#.to_owned());
+          imp.push("}".to_owned());
+        } else {
+          imp.push(format!("export const {function_name} = async (transport: Transport, content: {input}Content): Promise<{output}Content> => {{", ));
+        }
+        imp.push("const builder = new Builder();".to_owned());
 
-      imp.push(format!("let protocolPackage = {into_function_prefix}ContentIntoProtocolClass(content);"));
-      imp.push("let payloadOffset = protocolPackage.pack(builder);".to_owned());
-      imp.push("let id = content.id ?? self.crypto.randomUUID();".to_owned());
-      imp.push("const idOffset = builder.createString(id);".to_owned());
-      imp.push(format!("const root = {root_table_name}.create{root_table_name}(builder, idOffset, {root_union_name}.{input}, payloadOffset);"));
+        imp.push(format!("let protocolPackage = {into_function_prefix}ContentIntoProtocolClass(content);"));
+        imp.push("let payloadOffset = protocolPackage.pack(builder);".to_owned());
+        imp.push("let id = content.id || globalThis.crypto.randomUUID();".to_owned());
+        imp.push("const idOffset = builder.createString(id);".to_owned());
+        imp.push(format!("const root = {root_table_name}.create{root_table_name}(builder, idOffset, {root_union_name}.{input}, payloadOffset);"));
 
-      imp.push("builder.finish(root)".to_owned());
-      imp.push(format!("const response: {root_table_name} = await transport.sendMessage(builder.asUint8Array(), id);"));
-      imp.push(format!("const responseData = new {output}();"));
+        imp.push("builder.finish(root)".to_owned());
+        if method.streams_responses() {
+          imp.push(format!(r#"for await (const response of transport.openStream(builder.asUint8Array(), id, "{call_name}")) {{"#));
+        }
+      }
+
+      if method.is_streaming() {
+        if method.streams_responses() {
+          imp.push(format!("if (response.payloadType() === {root_union_name}.NONE) {{"));
+          imp.push("return;".to_owned());
+          imp.push("}".to_owned());
+        }
+        imp.push(generate_response_check(output, statements));
+        imp.push(format!("const responseData = new {}();", protocol_type_name(output)));
+        imp.push("response.payload(responseData);".to_owned());
+        if has_required_fields(&method.output, statements) {
+          imp.push(format!("check{output}RequiredFields(responseData);"));
+        }
+        imp.push(format!(r#"{} {{
+        ...{}ContentFromProtocolClass(responseData.unpack()),
+        id
+      }};"#, if method.streams_responses() { "yield" } else { "return" }, output.to_case(Case::Camel)));
+        imp.push("}".to_owned());
+        if method.streams_responses() {
+          imp.push("throw new RpcError(RpcError.UNAVAILABLE, `connection closed before stream ${id} ended`);".to_owned());
+        } else {
+          imp.push("throw new RpcError(RpcError.UNAVAILABLE, `connection closed before request ${id} was answered`);".to_owned());
+        }
+        imp.push("}".to_owned());
+        continue;
+      }
+      imp.push(format!(r#"const response: {root_table_name} = await transport.sendMessage(builder.asUint8Array(), id, "{call_name}");"#));
+      imp.push(generate_response_check(output, statements));
+      imp.push(format!("const responseData = new {}();", protocol_type_name(output)));
       imp.push("response.payload(responseData);".to_owned());
+      if has_required_fields(&method.output, statements) {
+        imp.push(format!("check{output}RequiredFields(responseData);"));
+      }
       imp.push(format!(r#"return {{
-        ...responseData.unpack(),
+        ...{}ContentFromProtocolClass(responseData.unpack()),
         id
-      }} as unknown as {output}Content"#).to_owned());
+      }}"#, output.to_case(Case::Camel)));
 
       imp.push("}".to_owned());
     };
 
 
-    imp.push("export const createApiObject = (transport: Transport) => {".to_owned());
+    imp.push(format!("export const create{}Api = (transport: Transport) => {{", unqualified(&self.name).to_case(Case::Pascal)));
     imp.push("return {".to_owned());
 
-    imp.push(methods.into_iter().map(|method| format!("{method}: {method}.bind(null, transport),")).collect::<Vec<String>>().join("\n"));
+    imp.push(methods.into_iter().map(|(method, function)| format!("{method}: {function}.bind(null, transport),")).collect::<Vec<String>>().join("\n"));
 
     imp.push("}".to_owned());
     imp.push("}".to_owned());
@@ -288,14 +1423,46 @@ impl ToTsStatement for RpcDeclaration {
 }
 
 
+impl ToTsServerStatement for RpcDeclaration {
+  fn to_ts_server_string(&self, _: &Statements) -> String {
+    let mut imp = vec![
+      format!("export interface {}Handler {{", unqualified(&self.name).to_case(Case::Pascal))
+    ];
+
+    for method in self.methods.values() {
+      let method_name = method.name.to_case(Case::Camel);
+      let output = unqualified(&method.output);
+      let input = if method.streams_requests() {
+        format!("contents: AsyncIterable<{}Content>", unqualified(&method.input))
+      } else {
+        format!("content: {}Content", unqualified(&method.input))
+      };
+      if method.streams_responses() {
+        imp.push(format!("{method_name}({input}): AsyncIterable<{output}Content>;"));
+      } else {
+        imp.push(format!("{method_name}({input}): Promise<{output}Content>;"));
+      }
+    };
+
+    imp.push("}".to_owned());
+    imp.join("\n")
+  }
+}
+
+
 impl ToTsStatement for StructDeclaration {
-  fn to_ts_string(&self, statements: &Statements) -> String {
-    let name = &self.name;
+  fn to_ts_string(&self, statements: &Statements, options: &TsOptions) -> String {
+    let name = unqualified(&self.name);
     let mut imp = vec![
       format!("export type {name}Content = {{"),
     ];
     for (name, type_def) in self.fields.iter() {
-      imp.push(format!("{name}: {};", type_def.value_type.to_ts_string(statements)));
+      let ts_type = match (type_def.fixed_length, typed_array_name(&type_def.value_type, options)) {
+        (Some(_), Some(typed_array)) => typed_array.to_owned(),
+        (Some(_), None) => format!("Array<{}>", type_def.value_type.to_ts_string(statements, options)),
+        (None, _) => type_def.value_type.to_ts_string(statements, options),
+      };
+      imp.push(format!("{name}: {ts_type};"));
     }
     imp.push("};".to_owned());
 
@@ -305,15 +1472,25 @@ impl ToTsStatement for StructDeclaration {
 }
 
 impl GenerateIntoFunctionStatement for StructDeclaration {
-  fn generate_into_function_statement(&self, _: &Statements) -> String {
-    let name = &self.name;
+  fn generate_into_function_statement(&self, statements: &Statements, options: &TsOptions) -> String {
+    let name = unqualified(&self.name);
     let function_name_prefix = name.to_case(Case::Camel);
     let mut imp = vec![
       format!("export const {function_name_prefix}ContentIntoProtocolClass = (content: {name}Content): {name}T => {{")
     ];
 
     let fields_as_args = self.fields_order.iter().map(|field_name| {
-      format!("content.{field_name},")
+      let field_type = self.fields.get(field_name).unwrap();
+      match (&field_type.value_type, field_type.fixed_length) {
+        (ValueType::CompoundType(type_name), None) if statements.struct_declaration.contains_key(type_name) => {
+          format!("{}ContentIntoProtocolClass(content.{field_name}),", unqualified(type_name).to_case(Case::Camel))
+        }
+        (ValueType::CompoundType(type_name), Some(_)) if statements.struct_declaration.contains_key(type_name) => {
+          format!("content.{field_name}.map({}ContentIntoProtocolClass),", unqualified(type_name).to_case(Case::Camel))
+        }
+        (value_type, Some(_)) if typed_array_name(value_type, options).is_some() => format!("Array.from(content.{field_name}),"),
+        _ => format!("{},", into_protocol_value(format!("content.{field_name}"), field_type, options)),
+      }
     }).collect::<Vec<String>>().join("\n");
 
     imp.push(format!("return new {name}T({fields_as_args})"));
@@ -323,3 +1500,31 @@ impl GenerateIntoFunctionStatement for StructDeclaration {
     imp.join("\n")
   }
 }
+
+
+/** Discriminants as declared in the schema, `satisfies` keeps the names in sync with flatc generated enum */
+fn generate_values_map(type_name: &str, discriminants: &[(&EnumValue, i64)], with_none: bool) -> String {
+  let mut imp = vec![format!("export const {type_name}Values = {{")];
+
+  if with_none {
+    imp.push("NONE: 0,".to_owned());
+  }
+  for (item, value) in discriminants {
+    imp.push(format!("{}: {value},", unqualified(&item.name)));
+  }
+
+  imp.push(format!("}} as const satisfies Record<keyof typeof {type_name}, number>;"));
+  imp.join("\n")
+}
+
+impl GenerateValuesStatement for EnumDeclaration {
+  fn generate_values_statement(&self) -> String {
+    generate_values_map(unqualified(&self.name), &self.discriminants(), false)
+  }
+}
+
+impl GenerateValuesStatement for UnionDeclaration {
+  fn generate_values_statement(&self) -> String {
+    generate_values_map(unqualified(&self.name), &self.discriminants(), true)
+  }
+}
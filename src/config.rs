@@ -0,0 +1,363 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::codegen_rs::{generate_rust_client_side_code, generate_rust_server_side_code, Derive, RsOptions};
+use crate::codegen_ts::{generate_ts_client_side_code, generate_ts_server_side_code, Int64Repr, TsOptions};
+use crate::diagnostic::Diagnostic;
+use crate::parser::parse_fbs_file_tracked;
+use crate::typechecker::type_check;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+  Rust,
+  Ts,
+}
+
+impl Lang {
+  fn extension(&self) -> &'static str {
+    match self {
+      Lang::Rust => "rs",
+      Lang::Ts => "ts",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+  Client,
+  Server,
+}
+
+impl Side {
+  fn name(&self) -> &'static str {
+    match self {
+      Side::Client => "client",
+      Side::Server => "server",
+    }
+  }
+}
+
+
+#[derive(Debug)]
+pub enum Error {
+  /** A required builder option wasn't set */
+  MissingOption(&'static str),
+  /** The schema or one of its includes can't be read or parsed */
+  Parse(Vec<Diagnostic>),
+  /** The schema parsed but doesn't pass semantic checks */
+  TypeCheck(Vec<Diagnostic>),
+  Io(std::io::Error),
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Error::MissingOption(option) => write!(f, "`{option}` must be set to generate code"),
+      Error::Parse(diagnostics) | Error::TypeCheck(diagnostics) => {
+        let diagnostics = diagnostics.iter().map(Diagnostic::to_string).collect::<Vec<String>>();
+        write!(f, "{}", diagnostics.join("\n"))
+      }
+      Error::Io(e) => write!(f, "can't write generated code: {e}"),
+    }
+  }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+  fn from(e: std::io::Error) -> Self {
+    Error::Io(e)
+  }
+}
+
+
+/** Code generation settings, meant to be driven from `build.rs`:
+
+```ignore
+flat_rpc::Config::new()
+  .input("schema.fbs")
+  .lang(flat_rpc::Lang::Rust)
+  .side(flat_rpc::Side::Server)
+  .out_dir(std::env::var("OUT_DIR")?)
+  .generate()?;
+```
+*/
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+  input: Option<PathBuf>,
+  lang: Option<Lang>,
+  side: Option<Side>,
+  out_dir: Option<PathBuf>,
+  output_file: Option<PathBuf>,
+  rs_options: RsOptions,
+  ts_options: TsOptions,
+}
+
+impl Config {
+  pub fn new() -> Self {
+    Config::default()
+  }
+
+  /** Root schema file, included files are found relative to it */
+  pub fn input(mut self, path: impl AsRef<Path>) -> Self {
+    self.input = Some(path.as_ref().to_path_buf());
+    self
+  }
+
+  pub fn lang(mut self, lang: Lang) -> Self {
+    self.lang = Some(lang);
+    self
+  }
+
+  pub fn side(mut self, side: Side) -> Self {
+    self.side = Some(side);
+    self
+  }
+
+  /** Directory to write into, the file is named after the schema and side, `schema_server.rs` */
+  pub fn out_dir(mut self, path: impl AsRef<Path>) -> Self {
+    self.out_dir = Some(path.as_ref().to_path_buf());
+    self
+  }
+
+  /** Exact path to write into, takes precedence over `out_dir` */
+  pub fn output_file(mut self, path: impl AsRef<Path>) -> Self {
+    self.output_file = Some(path.as_ref().to_path_buf());
+    self
+  }
+
+  /** Derives of Rust content types */
+  pub fn derives(mut self, derives: impl IntoIterator<Item = Derive>) -> Self {
+    self.rs_options.derives = derives.into_iter().collect();
+    self
+  }
+
+  /** TypeScript representation of 64-bit integers */
+  pub fn ts_int64(mut self, int64: Int64Repr) -> Self {
+    self.ts_options.int64 = int64;
+    self
+  }
+
+  /** Parses and checks the schema, returning generated code without writing anything */
+  pub fn generate_code(&self) -> Result<String, Error> {
+    self.parse().0
+  }
+
+  /** Writes generated code and returns the path written to.
+  `cargo:rerun-if-changed` is printed for the schema and every include reached first,
+  so a build failing on a schema error reruns once the schema is fixed */
+  pub fn generate(&self) -> Result<PathBuf, Error> {
+    let (code, files) = self.parse();
+    for file in &files {
+      println!("cargo:rerun-if-changed={}", file.display());
+    }
+
+    let code = code?;
+    let output_file = self.output_path()?;
+    fs::write(&output_file, code)?;
+
+    Ok(output_file)
+  }
+
+  /** Generated code along with every schema file reached, which is known even when parsing fails */
+  fn parse(&self) -> (Result<String, Error>, Vec<PathBuf>) {
+    let (input, lang, side) = match self.required_options() {
+      Ok(options) => options,
+      Err(e) => return (Err(e), vec![]),
+    };
+
+    let (statements, files) = parse_fbs_file_tracked(input);
+    let mut statements = match statements {
+      Ok(statements) => statements,
+      Err(diagnostics) => return (Err(Error::Parse(diagnostics)), files),
+    };
+    if let Some(diagnostics) = type_check(&statements) {
+      return (Err(Error::TypeCheck(diagnostics)), files);
+    }
+    statements.retain_used();
+
+    let code = match (side, lang) {
+      (Side::Server, Lang::Rust) => generate_rust_server_side_code(&statements, &self.rs_options),
+      (Side::Client, Lang::Rust) => generate_rust_client_side_code(&statements, &self.rs_options),
+      (Side::Client, Lang::Ts) => generate_ts_client_side_code(&statements, &self.ts_options),
+      (Side::Server, Lang::Ts) => generate_ts_server_side_code(&statements, &self.ts_options),
+    };
+
+    (Ok(code), files)
+  }
+
+  fn required_options(&self) -> Result<(&Path, Lang, Side), Error> {
+    let input = self.input.as_ref().ok_or(Error::MissingOption("input"))?;
+    let lang = self.lang.ok_or(Error::MissingOption("lang"))?;
+    let side = self.side.ok_or(Error::MissingOption("side"))?;
+
+    Ok((input, lang, side))
+  }
+
+  fn output_path(&self) -> Result<PathBuf, Error> {
+    if let Some(output_file) = &self.output_file {
+      return Ok(output_file.to_owned());
+    }
+
+    let out_dir = self.out_dir.as_ref().ok_or(Error::MissingOption("out_dir"))?;
+    let (input, lang, side) = self.required_options()?;
+    let stem = input.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+
+    Ok(out_dir.join(format!("{stem}_{}.{}", side.name(), lang.extension())))
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PAYLOAD: &str = "table Request { n: int; }\ntable Response { n: int; }\n\
+    table RpcError { code: uint; message: string; details: string; }\n\
+    union Payload { Request, Response, RpcError }\n";
+
+  const SCHEMA: &str = "table Request { n: int; }\ntable Response { n: int; }\n\
+    table RpcError { code: uint; message: string; details: string; }\n\
+    union Payload { Request, Response, RpcError }\ntable Envelope { id: string; payload: Payload; }\n\
+    root_type Envelope;\nrpc_service Greeter { Greet(Request): Response; }\n";
+
+  /** Writes `files` into a fresh directory under the system temp dir */
+  fn schema_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("flat-rpc-config-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+
+    for (file, contents) in files {
+      let path = dir.join(file);
+      fs::create_dir_all(path.parent().unwrap()).unwrap();
+      fs::write(path, contents).unwrap();
+    }
+
+    fs::canonicalize(dir).unwrap()
+  }
+
+  fn missing_option(result: Result<impl fmt::Debug, Error>) -> &'static str {
+    match result {
+      Err(Error::MissingOption(option)) => option,
+      other => panic!("expected a missing option, got {other:?}"),
+    }
+  }
+
+  #[test]
+  fn missing_options_are_reported_before_reading_the_schema() {
+    let config = Config::new();
+    assert_eq!(missing_option(config.generate_code()), "input");
+
+    let config = config.input("does/not/exist.fbs");
+    assert_eq!(missing_option(config.generate_code()), "lang");
+
+    let config = config.lang(Lang::Rust);
+    assert_eq!(missing_option(config.generate_code()), "side");
+    assert!(config.parse().1.is_empty());
+
+    let config = config.side(Side::Server);
+    assert!(matches!(config.generate_code(), Err(Error::Parse(_))));
+  }
+
+  #[test]
+  fn generate_needs_an_out_dir_or_an_output_file() {
+    let dir = schema_dir("no-output", &[("greeter.fbs", SCHEMA)]);
+    let config = Config::new().input(dir.join("greeter.fbs")).lang(Lang::Rust).side(Side::Server);
+
+    assert!(config.generate_code().is_ok());
+    assert_eq!(missing_option(config.generate()), "out_dir");
+  }
+
+  #[test]
+  fn out_dir_names_the_file_after_the_schema_side_and_lang() {
+    let dir = schema_dir("out-dir", &[("greeter.fbs", SCHEMA)]);
+    let config = Config::new().input(dir.join("greeter.fbs")).out_dir(&dir);
+
+    let written = config.clone().lang(Lang::Rust).side(Side::Server).generate().unwrap();
+    assert_eq!(written, dir.join("greeter_server.rs"));
+    assert!(fs::read_to_string(&written).unwrap().contains("Greet"));
+
+    let written = config.lang(Lang::Ts).side(Side::Client).generate().unwrap();
+    assert_eq!(written, dir.join("greeter_client.ts"));
+  }
+
+  #[test]
+  fn output_file_takes_precedence_over_out_dir() {
+    let dir = schema_dir("output-file", &[("greeter.fbs", SCHEMA)]);
+    let written = Config::new()
+      .input(dir.join("greeter.fbs"))
+      .lang(Lang::Rust)
+      .side(Side::Client)
+      .out_dir(&dir)
+      .output_file(dir.join("rpc.rs"))
+      .generate()
+      .unwrap();
+
+    assert_eq!(written, dir.join("rpc.rs"));
+    assert_eq!(fs::read_to_string(&written).unwrap(), Config::new()
+      .input(dir.join("greeter.fbs"))
+      .lang(Lang::Rust)
+      .side(Side::Client)
+      .generate_code()
+      .unwrap());
+    assert!(!dir.join("greeter_client.rs").exists());
+  }
+
+  #[test]
+  fn unused_declarations_of_includes_are_left_out() {
+    let dir = schema_dir("unused", &[
+      ("main.fbs", "include \"lib.fbs\";\nnamespace app;\ntable Request { n: int; }\n\
+        table Response { n: int; }\ntable RpcError { code: uint; message: string; details: string; }\n\
+        union Payload { Request, Response, RpcError }\ntable Envelope { id: string; payload: Payload; }\n\
+        root_type Envelope;\nrpc_service Greeter { Greet(Request): Response; }\n"),
+      ("lib.fbs", "namespace lib;\ntable Request { id: string; }\ntable Unused { n: int; }\n"),
+    ]);
+
+    let code = Config::new().input(dir.join("main.fbs")).lang(Lang::Rust).side(Side::Server).generate_code().unwrap();
+    assert!(code.contains("pub struct RequestContent"));
+    assert!(!code.contains("schema_generated::lib"));
+    assert!(!code.contains("Unused"));
+  }
+
+  #[test]
+  fn every_file_reached_is_listed_when_generation_succeeds() {
+    let dir = schema_dir("files", &[
+      ("main.fbs", "include \"types/payload.fbs\";\ninclude \"envelope.fbs\";\n\
+        root_type Envelope;\nrpc_service Greeter { Greet(Request): Response; }\n"),
+      ("types/payload.fbs", PAYLOAD),
+      ("envelope.fbs", "include \"types/payload.fbs\";\ntable Envelope { id: string; payload: Payload; }\n"),
+    ]);
+    let config = Config::new().input(dir.join("main.fbs")).lang(Lang::Rust).side(Side::Server);
+
+    let (code, files) = config.parse();
+    assert!(code.is_ok());
+    assert_eq!(files, vec![dir.join("main.fbs"), dir.join("types/payload.fbs"), dir.join("envelope.fbs")]);
+  }
+
+  #[test]
+  fn every_file_reached_is_listed_when_parsing_fails() {
+    let dir = schema_dir("parse-error", &[
+      ("main.fbs", "include \"broken.fbs\";\ninclude \"missing.fbs\";\ntable Response { id: string; }\n"),
+      ("broken.fbs", "table Request { id: string\n"),
+    ]);
+    let config = Config::new().input(dir.join("main.fbs")).lang(Lang::Ts).side(Side::Client);
+
+    let (code, files) = config.parse();
+    assert!(matches!(code, Err(Error::Parse(_))));
+    assert_eq!(files, vec![dir.join("main.fbs"), dir.join("broken.fbs"), dir.join("missing.fbs")]);
+  }
+
+  #[test]
+  fn every_file_reached_is_listed_when_type_checking_fails() {
+    let dir = schema_dir("type-error", &[
+      ("main.fbs", "include \"request.fbs\";\nrpc_service Greeter { Greet(Request): Unknown; }\n"),
+      ("request.fbs", "table Request { id: string; }\n"),
+    ]);
+    let config = Config::new().input(dir.join("main.fbs")).lang(Lang::Rust).side(Side::Client);
+
+    let (code, files) = config.parse();
+    assert!(matches!(code, Err(Error::TypeCheck(_))));
+    assert_eq!(files, vec![dir.join("main.fbs"), dir.join("request.fbs")]);
+  }
+}
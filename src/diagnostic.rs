@@ -0,0 +1,191 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use colored::Colorize;
+use pest::error::{Error, ErrorVariant, InputLocation};
+
+use crate::parser::Rule;
+
+
+pub const SYNTAX_ERROR: &str = "E0001";
+pub const IO_ERROR: &str = "E0002";
+pub const INCLUDE_CYCLE: &str = "E0003";
+pub const MALFORMED_DECLARATION: &str = "E0004";
+
+pub const UNDECLARED_TYPE: &str = "E0101";
+pub const DUPLICATE_DECLARATION: &str = "E0102";
+pub const INVALID_STRUCT_FIELD: &str = "E0103";
+pub const DUPLICATE_ENUM_VALUE: &str = "E0104";
+pub const MISSING_ROOT_TYPE: &str = "E0105";
+pub const MISSING_IN_ROOT_UNION: &str = "E0106";
+pub const DUPLICATE_FIELD: &str = "E0107";
+pub const INVALID_RPC_TABLE: &str = "E0108";
+pub const INVALID_ROOT_TYPE: &str = "E0109";
+pub const INVALID_ENUM_TYPE: &str = "E0110";
+pub const INVALID_UNION_MEMBER: &str = "E0111";
+pub const ENUM_VALUE_OUT_OF_RANGE: &str = "E0112";
+pub const INVALID_DEFAULT_VALUE: &str = "E0113";
+pub const UNKNOWN_ATTRIBUTE: &str = "E0114";
+pub const INVALID_FIELD_ID: &str = "E0115";
+pub const INVALID_ATTRIBUTE: &str = "E0116";
+pub const INVALID_RPC_ERROR_TABLE: &str = "E0117";
+pub const INVALID_FIXED_ARRAY: &str = "E0118";
+pub const INVALID_STREAMING: &str = "E0119";
+pub const AMBIGUOUS_NAME: &str = "E0120";
+
+
+/** Location of a diagnostic inside a schema file, with the source line kept for rendering */
+#[derive(Debug, Clone, Default)]
+pub struct Span {
+  pub file: PathBuf,
+  pub line: usize,
+  pub column: usize,
+  pub length: usize,
+  pub line_text: String,
+}
+
+impl Span {
+  pub fn in_file(mut self, file: &Path) -> Self {
+    self.file = file.to_path_buf();
+    self
+  }
+}
+
+impl From<pest::Span<'_>> for Span {
+  fn from(span: pest::Span<'_>) -> Self {
+    let start = span.start_pos();
+    let (line, column) = start.line_col();
+    let line_text = start.line_of().trim_end_matches(['\r', '\n']).to_owned();
+    let length = span.as_str().lines().next().map(str::len).unwrap_or(0);
+
+    Span {
+      file: PathBuf::new(),
+      line,
+      column,
+      length,
+      line_text,
+    }
+  }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+  pub code: &'static str,
+  pub message: String,
+  pub span: Option<Span>,
+}
+
+impl Diagnostic {
+  pub fn new(code: &'static str, message: impl Into<String>, span: Option<Span>) -> Self {
+    Diagnostic {
+      code,
+      message: message.into(),
+      span,
+    }
+  }
+
+  pub fn in_file(mut self, file: &Path) -> Self {
+    self.span = match self.span {
+      Some(span) => Some(span.in_file(file)),
+      None => Some(Span::default().in_file(file)),
+    };
+    self
+  }
+
+  pub fn from_pest_error(error: Error<Rule>, schema_string: &str) -> Self {
+    let message = match &error.variant {
+      ErrorVariant::ParsingError { positives, negatives } => {
+        match (positives.is_empty(), negatives.is_empty()) {
+          (false, _) => format!("expected {}", enumerate_rules(positives)),
+          (true, false) => format!("unexpected {}", enumerate_rules(negatives)),
+          (true, true) => "unknown parsing error".to_owned(),
+        }
+      }
+      ErrorVariant::CustomError { message } => message.to_owned(),
+    };
+
+    let (start, end) = match error.location {
+      InputLocation::Pos(pos) => (pos, pos),
+      InputLocation::Span(span) => span,
+    };
+    let span = pest::Span::new(schema_string, start, end.max(start))
+      .map(Span::from);
+
+    Diagnostic::new(SYNTAX_ERROR, message, span)
+  }
+
+  /** Compiler-style rendering with the offending source line and a caret underline */
+  pub fn render(&self) -> String {
+    let mut rendered = vec![format!("{}{} {}", format!("error[{}]", self.code).red().bold(), ":".bold(), self.message.bold())];
+
+    if let Some(span) = &self.span {
+      if span.line == 0 {
+        rendered.push(format!("  {} {}", "-->".blue().bold(), span.file.display()));
+      } else {
+        let gutter = " ".repeat(span.line.to_string().len());
+        rendered.push(format!("{gutter}{} {}:{}:{}", "-->".blue().bold(), span.file.display(), span.line, span.column));
+        rendered.push(format!("{gutter} {}", "|".blue().bold()));
+        rendered.push(format!("{} {} {}", span.line.to_string().blue().bold(), "|".blue().bold(), span.line_text));
+        rendered.push(format!(
+          "{gutter} {} {}{}",
+          "|".blue().bold(),
+          " ".repeat(span.column.saturating_sub(1)),
+          "^".repeat(span.length.max(1)).red().bold()
+        ));
+      }
+    }
+
+    rendered.join("\n")
+  }
+}
+
+impl fmt::Display for Diagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match &self.span {
+      Some(span) if span.line > 0 => write!(f, "{}:{}:{}: error[{}]: {}", span.file.display(), span.line, span.column, self.code, self.message),
+      Some(span) => write!(f, "{}: error[{}]: {}", span.file.display(), self.code, self.message),
+      None => write!(f, "error[{}]: {}", self.code, self.message),
+    }
+  }
+}
+
+
+fn enumerate_rules(rules: &[Rule]) -> String {
+  let names = rules.iter()
+    .map(|rule| format!("{:?}", rule).replace('_', " "))
+    .collect::<Vec<String>>();
+
+  match names.split_last() {
+    Some((last, [])) => last.to_owned(),
+    Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+    None => "".to_owned(),
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn renders_the_source_line_with_a_caret_under_the_span() {
+    colored::control::set_override(false);
+    let span = Span {
+      file: PathBuf::from("user.fbs"),
+      line: 12,
+      column: 9,
+      length: 4,
+      line_text: "  name: Nme;".to_owned(),
+    };
+    let diagnostic = Diagnostic::new(UNDECLARED_TYPE, "type Nme used in User is not declared", Some(span));
+
+    assert_eq!(diagnostic.to_string(), "user.fbs:12:9: error[E0101]: type Nme used in User is not declared");
+    assert_eq!(diagnostic.render(), [
+      "error[E0101]: type Nme used in User is not declared",
+      "  --> user.fbs:12:9",
+      "   |",
+      "12 |   name: Nme;",
+      "   |         ^^^^",
+    ].join("\n"));
+  }
+}
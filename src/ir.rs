@@ -1,6 +1,15 @@
-use std::collections::{HashMap, HashSet};
+use indexmap::IndexMap;
+use crate::diagnostic::{Diagnostic, Span, MALFORMED_DECLARATION};
 use crate::parser::{Rule};
-use pest::iterators::Pair;
+use pest::iterators::{Pair, Pairs};
+
+
+/** Takes the next child of a declaration, reporting the declaration itself when it's missing */
+fn expect_next<'a>(pairs: &mut Pairs<'a, Rule>, parent: pest::Span<'_>, expected: &str) -> Result<Pair<'a, Rule>, Diagnostic> {
+  pairs.next().ok_or_else(|| {
+    Diagnostic::new(MALFORMED_DECLARATION, format!("malformed declaration, expected {expected}"), Some(parent.into()))
+  })
+}
 
 #[derive(Debug, Clone)]
 pub enum ValueType {
@@ -26,9 +35,23 @@ pub enum ValueType {
   Float32,
   Float64,
   String,
+  /** Name as written in the schema, the qualified name of the declaration once references are resolved */
   CompoundType(String),
 }
 
+/** Name a declaration gets in generated code, `com.acme.User` is `User` there */
+pub fn unqualified(name: &str) -> &str {
+  name.rsplit('.').next().unwrap_or(name)
+}
+
+/** Declarations are keyed by their name prefixed with the namespace they are declared in, `com.acme.User` */
+pub fn qualified_name(namespace: Option<&str>, name: &str) -> String {
+  match namespace {
+    Some(namespace) => format!("{namespace}.{name}"),
+    None => name.to_owned(),
+  }
+}
+
 impl From<&str> for ValueType {
   fn from(string: &str) -> Self {
     match string {
@@ -60,101 +83,388 @@ impl From<&str> for ValueType {
 }
 
 
+#[derive(Debug, Clone)]
+pub enum DefaultValue {
+  Bool(bool),
+  Integer(i64),
+  Float(f64),
+  /** Enum variant name */
+  Identifier(String),
+  /** `field: int = null;` makes an optional scalar */
+  Null,
+}
+
+impl TryFrom<Pair<'_, Rule>> for DefaultValue {
+  type Error = Diagnostic;
+
+  fn try_from(pair: Pair<'_, Rule>) -> Result<Self, Self::Error> {
+    let span = pair.as_span();
+    let value = expect_next(&mut pair.into_inner(), span, "a default value")?;
+
+    let value = match value.as_rule() {
+      Rule::scalar => expect_next(&mut value.into_inner(), span, "a scalar")?,
+      _ => value,
+    };
+
+    match value.as_rule() {
+      Rule::null_constant => Ok(DefaultValue::Null),
+      Rule::boolean_constant => Ok(DefaultValue::Bool(value.as_str() == "true")),
+      Rule::integer_constant => Ok(DefaultValue::Integer(parse_integer_constant(&value)?)),
+      Rule::float_constant => value.as_str().parse::<f64>()
+        .map(DefaultValue::Float)
+        .map_err(|_| Diagnostic::new(MALFORMED_DECLARATION, format!("malformed float constant {}", value.as_str()), Some(span.into()))),
+      Rule::identifier => Ok(DefaultValue::Identifier(value.as_str().to_owned())),
+      rule => Err(Diagnostic::new(MALFORMED_DECLARATION, format!("unexpected {rule:?} as a default value"), Some(span.into())))
+    }
+  }
+}
+
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub enum AttributeValue {
+  Bool(bool),
+  Integer(i64),
+  Float(f64),
+  String(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Attribute {
+  pub name: String,
+  pub value: Option<AttributeValue>,
+  pub span: Span,
+}
+
+impl TryFrom<Pair<'_, Rule>> for Attribute {
+  type Error = Diagnostic;
+
+  fn try_from(pair: Pair<'_, Rule>) -> Result<Self, Self::Error> {
+    let span = pair.as_span();
+    let mut entry = pair.into_inner();
+    let name = expect_next(&mut entry, span, "an attribute name")?.as_str().to_owned();
+
+    let value = match entry.next() {
+      Some(single_value) => {
+        let value = expect_next(&mut single_value.into_inner(), span, "an attribute value")?;
+        let value = match value.as_rule() {
+          Rule::scalar => expect_next(&mut value.into_inner(), span, "a scalar")?,
+          _ => value,
+        };
+
+        Some(match value.as_rule() {
+          Rule::boolean_constant => AttributeValue::Bool(value.as_str() == "true"),
+          Rule::integer_constant => AttributeValue::Integer(parse_integer_constant(&value)?),
+          Rule::float_constant => AttributeValue::Float(value.as_str().parse::<f64>().map_err(|_| {
+            Diagnostic::new(MALFORMED_DECLARATION, format!("malformed float constant {}", value.as_str()), Some(span.into()))
+          })?),
+          _ => AttributeValue::String(value.into_inner().as_str().to_owned()),
+        })
+      }
+      None => None,
+    };
+
+    Ok(Attribute {
+      name,
+      value,
+      span: span.into(),
+    })
+  }
+}
+
+
+/** Attributes in parentheses after a declaration or a field, `(deprecated, id: 3)` */
+#[derive(Debug, Clone, Default)]
+pub struct Metadata {
+  pub attributes: Vec<Attribute>,
+}
+
+impl Metadata {
+  pub fn get(&self, name: &str) -> Option<&Attribute> {
+    self.attributes.iter().find(|attribute| attribute.name == name)
+  }
+
+  pub fn has(&self, name: &str) -> bool {
+    self.get(name).is_some()
+  }
+
+  pub fn is_deprecated(&self) -> bool {
+    self.has("deprecated")
+  }
+
+  pub fn is_required(&self) -> bool {
+    self.has("required")
+  }
+
+  /** Explicit field slot from `(id: N)` */
+  pub fn id(&self) -> Option<i64> {
+    match self.get("id")?.value {
+      Some(AttributeValue::Integer(id)) => Some(id),
+      _ => None,
+    }
+  }
+}
+
+impl TryFrom<Pair<'_, Rule>> for Metadata {
+  type Error = Diagnostic;
+
+  fn try_from(pair: Pair<'_, Rule>) -> Result<Self, Self::Error> {
+    let attributes = pair.into_inner().map(Attribute::try_from).collect::<Result<Vec<Attribute>, Diagnostic>>()?;
+
+    Ok(Metadata {
+      attributes,
+    })
+  }
+}
+
+/** Takes an optional `metadata` child if it's the next one */
+fn parse_optional_metadata(pairs: &mut Pairs<'_, Rule>) -> Result<Metadata, Diagnostic> {
+  match pairs.peek() {
+    Some(pair) if pair.as_rule() == Rule::metadata => Metadata::try_from(pairs.next().unwrap()),
+    _ => Ok(Metadata::default()),
+  }
+}
+
+
 #[derive(Debug, Clone)]
 pub struct Type {
   pub value_type: ValueType,
   pub is_array: bool,
+  /** Length of a fixed size struct array, `field: [ubyte:32];` */
+  pub fixed_length: Option<i64>,
+  pub span: Span,
+  /** Default of a field declared with this type, `field: int = 5;` */
+  pub default_value: Option<DefaultValue>,
+  /** Attributes of a field declared with this type, `field: int (deprecated);` */
+  pub metadata: Metadata,
+}
+
+impl Type {
+  /** Optional scalars are absent on the wire unless set, instead of reading as their default */
+  pub fn is_optional(&self) -> bool {
+    matches!(self.default_value, Some(DefaultValue::Null))
+  }
 }
 
-impl From<Pair<'_, Rule>> for Type {
-  fn from(pair: Pair<'_, Rule>) -> Self {
-    let typing = pair.into_inner().next().unwrap();
+impl TryFrom<Pair<'_, Rule>> for Type {
+  type Error = Diagnostic;
+
+  fn try_from(pair: Pair<'_, Rule>) -> Result<Self, Self::Error> {
+    let span = pair.as_span();
+    let typing = expect_next(&mut pair.into_inner(), span, "a type")?;
 
     match typing.as_rule() {
       Rule::array_type => {
-        Type {
+        Ok(Type {
           value_type: typing.into_inner().as_str().into(),
           is_array: true,
-        }
+          fixed_length: None,
+          span: span.into(),
+          default_value: None,
+          metadata: Metadata::default(),
+        })
       }
       Rule::value_type => {
-        Type {
+        Ok(Type {
           value_type: typing.as_str().into(),
           is_array: false,
-        }
+          fixed_length: None,
+          span: span.into(),
+          default_value: None,
+          metadata: Metadata::default(),
+        })
+      }
+      Rule::fixed_array_type => {
+        let mut fixed_array = typing.into_inner();
+        let value_type = expect_next(&mut fixed_array, span, "an element type")?;
+        let length = expect_next(&mut fixed_array, span, "an array length")?;
+
+        Ok(Type {
+          value_type: value_type.as_str().into(),
+          is_array: false,
+          fixed_length: Some(parse_integer_constant(&length)?),
+          span: span.into(),
+          default_value: None,
+          metadata: Metadata::default(),
+        })
       }
-      _ => unreachable!()
+      rule => Err(Diagnostic::new(MALFORMED_DECLARATION, format!("unexpected {rule:?} in type position"), Some(span.into())))
+    }
+  }
+}
+
+
+/** Shared by tables and structs: `name: type = default (metadata);` */
+fn parse_field_decl(field: Pair<'_, Rule>) -> Result<(String, Type), Diagnostic> {
+  let field_span = field.as_span();
+  let mut field = field.into_inner();
+  let field_name = expect_next(&mut field, field_span, "a field name")?.as_str().to_owned();
+  let mut field_type = Type::try_from(expect_next(&mut field, field_span, "a field type")?)?;
+
+  for pair in field {
+    match pair.as_rule() {
+      Rule::default_value => field_type.default_value = Some(DefaultValue::try_from(pair)?),
+      Rule::metadata => field_type.metadata = Metadata::try_from(pair)?,
+      _ => {}
     }
   }
+
+  Ok((field_name, field_type))
 }
 
 
 #[derive(Debug)]
 pub struct TableDeclaration {
   pub name: String,
-  pub fields: HashMap<String, Type>,
+  pub span: Span,
+  pub metadata: Metadata,
+  pub fields: IndexMap<String, Type>,
   pub fields_order: Vec<String>,
 }
 
-impl From<Pair<'_, Rule>> for TableDeclaration {
-  fn from(pair: Pair<'_, Rule>) -> Self {
+impl TableDeclaration {
+  /** Fields present in flatc generated code, deprecated ones have neither accessors nor builder args */
+  pub fn active_fields(&self) -> impl Iterator<Item = (&String, &Type)> {
+    self.fields.iter().filter(|(_, field)| !field.metadata.is_deprecated())
+  }
+}
+
+impl TryFrom<Pair<'_, Rule>> for TableDeclaration {
+  type Error = Diagnostic;
+
+  fn try_from(pair: Pair<'_, Rule>) -> Result<Self, Self::Error> {
+    let span = pair.as_span();
     let mut table_decl = pair.into_inner();
 
-    let identifier = table_decl.next().unwrap();
-    let name = identifier.as_span().as_str().to_owned();
+    let identifier = expect_next(&mut table_decl, span, "a name")?;
+    let name = identifier.as_str().to_owned();
+    let metadata = parse_optional_metadata(&mut table_decl)?;
     let mut fields_order = vec![];
+    let mut fields = IndexMap::new();
+
+    for field in table_decl {
+      let (field_name, field_type) = parse_field_decl(field)?;
+
+      fields_order.push(field_name.to_owned());
+      fields.insert(field_name, field_type);
+    }
 
-    let fields = table_decl.into_iter().map(|field| {
-      let mut field = field.into_inner();
-      let field_name = field.next().unwrap().as_span().as_str();
-      let field_type = Type::from(field.next().unwrap());
-      (field_name, field_type)
-    }).fold(HashMap::new(), |mut acc: HashMap<String, Type>, (key, value)| {
-      fields_order.push(key.to_owned());
-      acc.insert(key.to_owned(), value);
-      acc
-    });
-
-    TableDeclaration {
+    // flatc lays fields out by `id` once every field has one, generated constructors follow that order
+    if fields.values().all(|field: &Type| field.metadata.id().is_some()) {
+      fields.sort_by(|_, a, _, b| a.metadata.id().cmp(&b.metadata.id()));
+      fields_order.sort_by_key(|field_name| fields.get(field_name).and_then(|field| field.metadata.id()));
+    }
+
+    Ok(TableDeclaration {
       name,
+      span: identifier.as_span().into(),
+      metadata,
       fields,
       fields_order,
-    }
+    })
+  }
+}
+
+
+/** Parses decimal and hexadecimal integer constants, both optionally signed */
+fn parse_integer_constant(pair: &Pair<'_, Rule>) -> Result<i64, Diagnostic> {
+  let literal = pair.as_str();
+  let (negative, unsigned) = match literal.strip_prefix('-') {
+    Some(rest) => (true, rest),
+    None => (false, literal.strip_prefix('+').unwrap_or(literal)),
+  };
+
+  let parsed = match unsigned.strip_prefix("0x").or_else(|| unsigned.strip_prefix("0X")) {
+    Some(hex) => i128::from_str_radix(hex, 16),
+    None => unsigned.parse::<i128>(),
+  };
+
+  parsed.ok()
+    .map(|value| if negative { -value } else { value })
+    .and_then(|value| i64::try_from(value).ok())
+    .ok_or_else(|| Diagnostic::new(MALFORMED_DECLARATION, format!("integer constant {literal} doesn't fit into 64 bits"), Some(pair.as_span().into())))
+}
+
+
+#[derive(Debug)]
+pub struct EnumValue {
+  pub name: String,
+  /** Discriminant exactly as written in the schema, `None` means "previous value + 1" */
+  pub value: Option<i64>,
+  pub span: Span,
+}
+
+impl TryFrom<Pair<'_, Rule>> for EnumValue {
+  type Error = Diagnostic;
+
+  fn try_from(pair: Pair<'_, Rule>) -> Result<Self, Self::Error> {
+    let span = pair.as_span();
+    let mut enumval_decl = pair.into_inner();
+    let identifier = expect_next(&mut enumval_decl, span, "a variant name")?;
+    let value = match enumval_decl.next() {
+      Some(value) => Some(parse_integer_constant(&value)?),
+      None => None,
+    };
+
+    Ok(EnumValue {
+      name: identifier.as_str().to_owned(),
+      value,
+      span: span.into(),
+    })
   }
 }
 
+/** Assigns discriminants the way flatc does: implicit values continue from the previous one */
+fn resolve_discriminants(items: &[EnumValue], first: i64) -> Vec<(&EnumValue, i64)> {
+  let mut next = first;
+
+  items.iter().map(|item| {
+    let value = item.value.unwrap_or(next);
+    next = value.wrapping_add(1);
+    (item, value)
+  }).collect()
+}
+
 
 #[derive(Debug)]
 pub struct EnumDeclaration {
   pub name: String,
+  pub span: Span,
+  pub metadata: Metadata,
   pub type_def: Type,
-  pub items: HashSet<String>,
+  pub items: Vec<EnumValue>,
+}
+
+impl EnumDeclaration {
+  pub fn discriminants(&self) -> Vec<(&EnumValue, i64)> {
+    resolve_discriminants(&self.items, 0)
+  }
 }
 
-impl From<Pair<'_, Rule>> for EnumDeclaration {
-  fn from(pair: Pair<'_, Rule>) -> Self {
+impl TryFrom<Pair<'_, Rule>> for EnumDeclaration {
+  type Error = Diagnostic;
+
+  fn try_from(pair: Pair<'_, Rule>) -> Result<Self, Self::Error> {
+    let span = pair.as_span();
     let mut enum_ast = pair.into_inner();
-    let identifier = enum_ast.next().unwrap();
+    let identifier = expect_next(&mut enum_ast, span, "a name")?;
     let name = identifier.as_str().to_owned();
 
-    let type_def = enum_ast.next().unwrap();
+    let type_def = expect_next(&mut enum_ast, span, "an underlying type")?;
 
-    let type_def = Type::from(type_def);
+    let type_def = Type::try_from(type_def)?;
+    let metadata = parse_optional_metadata(&mut enum_ast)?;
 
-    let items = enum_ast.into_iter().map(|field| {
-      let mut field = field.into_inner();
-      field.next().unwrap().as_str().to_owned()
-    }).fold(HashSet::new(), |mut acc: HashSet<String>, item| {
-      acc.insert(item);
-      acc
-    });
+    let items = enum_ast.map(EnumValue::try_from).collect::<Result<Vec<EnumValue>, Diagnostic>>()?;
 
 
-    EnumDeclaration {
+    Ok(EnumDeclaration {
       name,
+      span: identifier.as_span().into(),
+      metadata,
       items,
       type_def,
-    }
+    })
   }
 }
 
@@ -162,68 +472,138 @@ impl From<Pair<'_, Rule>> for EnumDeclaration {
 #[derive(Debug)]
 pub struct UnionDeclaration {
   pub name: String,
-  pub items: HashSet<String>,
+  pub span: Span,
+  pub metadata: Metadata,
+  pub items: Vec<EnumValue>,
+}
+
+impl UnionDeclaration {
+  pub fn contains(&self, name: &str) -> bool {
+    self.items.iter().any(|item| item.name == name)
+  }
+
+  /** Members with their type tags, 0 is reserved for NONE */
+  pub fn discriminants(&self) -> Vec<(&EnumValue, i64)> {
+    resolve_discriminants(&self.items, 1)
+  }
 }
 
-impl From<Pair<'_, Rule>> for UnionDeclaration {
-  fn from(pair: Pair<'_, Rule>) -> Self {
+impl TryFrom<Pair<'_, Rule>> for UnionDeclaration {
+  type Error = Diagnostic;
+
+  fn try_from(pair: Pair<'_, Rule>) -> Result<Self, Self::Error> {
+    let span = pair.as_span();
     let mut union_ast = pair.into_inner();
-    let identifier = union_ast.next().unwrap();
+    let identifier = expect_next(&mut union_ast, span, "a name")?;
     let name = identifier.as_str().to_owned();
+    let metadata = parse_optional_metadata(&mut union_ast)?;
 
-    let items = union_ast.into_iter().map(|field| {
-      let mut field = field.into_inner();
-      field.next().unwrap().as_str().to_owned()
-    }).fold(HashSet::new(), |mut acc: HashSet<String>, item| {
-      acc.insert(item);
-      acc
-    });
+    let items = union_ast.map(EnumValue::try_from).collect::<Result<Vec<EnumValue>, Diagnostic>>()?;
 
 
-    UnionDeclaration {
+    Ok(UnionDeclaration {
       name,
+      span: identifier.as_span().into(),
+      metadata,
       items,
-    }
+    })
   }
 }
 
 #[derive(Debug)]
 pub struct RpcMethod {
   pub name: String,
+  pub span: Span,
+  /** Table names, resolved like `ValueType::CompoundType` */
   pub input: String,
   pub output: String,
+  pub metadata: Metadata,
+}
+
+/** Direction of `(streaming: "...")` on an RPC method, named as in flatc */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Streaming {
+  None,
+  Server,
+  Client,
+  Bidi,
+}
+
+impl RpcMethod {
+  /** `None` for a value flatc doesn't know either */
+  pub fn streaming(&self) -> Option<Streaming> {
+    let attribute = match self.metadata.get("streaming") {
+      Some(attribute) => attribute,
+      None => return Some(Streaming::None),
+    };
+
+    match &attribute.value {
+      Some(AttributeValue::String(value)) => match value.as_str() {
+        "none" => Some(Streaming::None),
+        "server" => Some(Streaming::Server),
+        "client" => Some(Streaming::Client),
+        "bidi" => Some(Streaming::Bidi),
+        _ => None,
+      },
+      _ => None,
+    }
+  }
+
+  /** Sends any number of input tables followed by an end of stream marker */
+  pub fn streams_requests(&self) -> bool {
+    matches!(self.streaming(), Some(Streaming::Client | Streaming::Bidi))
+  }
+
+  /** Answered with any number of output tables followed by an end of stream marker */
+  pub fn streams_responses(&self) -> bool {
+    matches!(self.streaming(), Some(Streaming::Server | Streaming::Bidi))
+  }
+
+  pub fn is_streaming(&self) -> bool {
+    self.streams_requests() || self.streams_responses()
+  }
 }
 
 #[derive(Debug)]
 pub struct RpcDeclaration {
   pub name: String,
-  pub methods: HashMap<String, RpcMethod>,
+  pub span: Span,
+  pub methods: IndexMap<String, RpcMethod>,
 }
 
-impl From<Pair<'_, Rule>> for RpcDeclaration {
-  fn from(rule: Pair<'_, Rule>) -> Self {
-    let mut methods = HashMap::new();
+impl TryFrom<Pair<'_, Rule>> for RpcDeclaration {
+  type Error = Diagnostic;
+
+  fn try_from(rule: Pair<'_, Rule>) -> Result<Self, Self::Error> {
+    let span = rule.as_span();
+    let mut methods = IndexMap::new();
     let mut rpc_ast = rule.into_inner();
-    let service_name = rpc_ast.next().unwrap().as_str().to_owned();
+    let identifier = expect_next(&mut rpc_ast, span, "a service name")?;
+    let service_name = identifier.as_str().to_owned();
 
 
     for method in rpc_ast {
+      let method_span = method.as_span();
       let mut method = method.into_inner();
-      let method_name = method.next().unwrap().as_str().to_owned();
-      let input = method.next().unwrap().as_str().to_owned();
-      let output = method.next().unwrap().as_str().to_owned();
+      let method_name = expect_next(&mut method, method_span, "a method name")?.as_str().to_owned();
+      let input = expect_next(&mut method, method_span, "an input table")?.as_str().to_owned();
+      let output = expect_next(&mut method, method_span, "an output table")?.as_str().to_owned();
+      let metadata = parse_optional_metadata(&mut method)?;
 
       methods.insert(method_name.to_owned(), RpcMethod {
         name: method_name,
+        span: method_span.into(),
         input,
         output,
+        metadata,
       });
     };
 
-    RpcDeclaration {
+    Ok(RpcDeclaration {
       name: service_name,
+      span: identifier.as_span().into(),
       methods,
-    }
+    })
   }
 }
 
@@ -231,33 +611,108 @@ impl From<Pair<'_, Rule>> for RpcDeclaration {
 #[derive(Debug)]
 pub struct StructDeclaration {
   pub name: String,
-  pub fields: HashMap<String, Type>,
+  pub span: Span,
+  pub metadata: Metadata,
+  pub fields: IndexMap<String, Type>,
   pub fields_order: Vec<String>,
 }
 
-impl From<Pair<'_, Rule>> for StructDeclaration {
-  fn from(pair: Pair<'_, Rule>) -> Self {
+impl TryFrom<Pair<'_, Rule>> for StructDeclaration {
+  type Error = Diagnostic;
+
+  fn try_from(pair: Pair<'_, Rule>) -> Result<Self, Self::Error> {
+    let span = pair.as_span();
     let mut struct_decl = pair.into_inner();
 
-    let identifier = struct_decl.next().unwrap();
-    let name = identifier.as_span().as_str().to_owned();
+    let identifier = expect_next(&mut struct_decl, span, "a name")?;
+    let name = identifier.as_str().to_owned();
+    let metadata = parse_optional_metadata(&mut struct_decl)?;
     let mut fields_order = vec![];
+    let mut fields = IndexMap::new();
+
+    for field in struct_decl {
+      let (field_name, field_type) = parse_field_decl(field)?;
+
+      fields_order.push(field_name.to_owned());
+      fields.insert(field_name, field_type);
+    }
 
-    let fields = struct_decl.into_iter().map(|field| {
-      let mut field = field.into_inner();
-      let field_name = field.next().unwrap().as_span().as_str();
-      let field_type = Type::from(field.next().unwrap());
-      (field_name, field_type)
-    }).fold(HashMap::new(), |mut acc: HashMap<String, Type>, (key, value)| {
-      fields_order.push(key.to_owned());
-      acc.insert(key.to_owned(), value);
-      acc
-    });
-
-    StructDeclaration {
+    Ok(StructDeclaration {
       name,
+      span: identifier.as_span().into(),
+      metadata,
       fields,
       fields_order,
-    }
+    })
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+  use super::*;
+  use crate::parser::{parse_fbs_schema, Statements};
+
+  fn parse(schema: &str) -> Statements {
+    parse_fbs_schema(schema, Path::new("test.fbs")).unwrap()
+  }
+
+  fn values(discriminants: Vec<(&EnumValue, i64)>) -> Vec<(&str, i64)> {
+    discriminants.into_iter().map(|(item, value)| (item.name.as_str(), value)).collect()
+  }
+
+  #[test]
+  fn implicit_enum_values_continue_from_the_previous_one() {
+    let statements = parse("enum E: int { A, B = 5, C, D = -2, E, F = 0x10, G }");
+    let enum_decl = &statements.enum_declarations["E"];
+
+    assert_eq!(values(enum_decl.discriminants()), vec![("A", 0), ("B", 5), ("C", 6), ("D", -2), ("E", -1), ("F", 16), ("G", 17)]);
+    assert_eq!(enum_decl.items.iter().map(|item| item.value).collect::<Vec<_>>(), vec![None, Some(5), None, Some(-2), None, Some(16), None]);
+  }
+
+  #[test]
+  fn union_values_start_after_none() {
+    let statements = parse("table A {} table B {} table C {} union U { A, B = 7, C }");
+
+    assert_eq!(values(statements.unions["U"].discriminants()), vec![("A", 1), ("B", 7), ("C", 8)]);
+  }
+
+  #[test]
+  fn an_implicit_value_after_the_largest_one_wraps_around() {
+    let statements = parse("enum E: long { A = 9223372036854775807, B }");
+
+    // the typechecker reports the wrapped value, discriminants only follow flatc's arithmetic
+    assert_eq!(values(statements.enum_declarations["E"].discriminants()), vec![("A", i64::MAX), ("B", i64::MIN)]);
+  }
+
+  #[test]
+  fn fields_are_ordered_by_id_once_all_have_one() {
+    let statements = parse("table T { c: int (id: 2); a: int (id: 0); b: string (id: 1); }");
+    let table = &statements.table_declaration["T"];
+
+    assert_eq!(table.fields.keys().collect::<Vec<_>>(), vec!["a", "b", "c"]);
+    assert_eq!(table.fields_order, vec!["a", "b", "c"]);
+  }
+
+  #[test]
+  fn fields_keep_declaration_order_without_ids() {
+    let statements = parse("table T { c: int (id: 2); a: int; b: string (id: 1); } table U { z: int; y: int; }");
+
+    assert_eq!(statements.table_declaration["T"].fields_order, vec!["c", "a", "b"]);
+    assert_eq!(statements.table_declaration["U"].fields.keys().collect::<Vec<_>>(), vec!["z", "y"]);
+  }
+
+  #[test]
+  fn field_types_defaults_and_attributes() {
+    let statements = parse("enum E: byte { A, B } table T { n: uint = 0x10 (id: 0); e: E = B (id: 1); v: [string] (id: 2, deprecated); o: long = null (id: 3); }");
+    let fields = &statements.table_declaration["T"].fields;
+
+    assert!(matches!(fields["n"].default_value, Some(DefaultValue::Integer(16))));
+    assert!(matches!(&fields["e"].default_value, Some(DefaultValue::Identifier(variant)) if variant == "B"));
+    assert!(matches!(fields["v"].value_type, ValueType::String) && fields["v"].is_array && fields["v"].metadata.is_deprecated());
+    assert!(fields["o"].is_optional());
+    assert_eq!(fields.values().map(|field| field.metadata.id()).collect::<Vec<_>>(), vec![Some(0), Some(1), Some(2), Some(3)]);
+    assert_eq!(statements.table_declaration["T"].active_fields().map(|(name, _)| name.as_str()).collect::<Vec<_>>(), vec!["n", "e", "o"]);
   }
 }
@@ -0,0 +1,11 @@
+pub mod parser;
+pub mod diagnostic;
+pub mod ir;
+pub mod codegen_rs;
+pub mod typechecker;
+pub mod codegen_ts;
+mod config;
+
+pub use codegen_rs::{Derive, RsOptions};
+pub use codegen_ts::{Int64Repr, TsOptions};
+pub use config::{Config, Error, Lang, Side};
@@ -1,19 +1,9 @@
-extern crate core;
-
-mod parser;
-mod ir;
-mod codegen_rs;
-mod typechecker;
-mod codegen_ts;
-
-use std::fs;
+use std::{fs, process};
 use clap::Parser;
 
 use colored::Colorize;
-use crate::codegen_rs::generate_rust_server_side_code;
-use crate::codegen_ts::generate_ts_client_side_code;
-use crate::parser::parse_fbs_schema;
-use crate::typechecker::type_check;
+use flat_rpc::diagnostic::Diagnostic;
+use flat_rpc::{Config, Derive, Error, Int64Repr};
 
 
 #[derive(clap::ArgEnum, Debug, Clone)]
@@ -22,12 +12,68 @@ enum Side {
   Server,
 }
 
+impl From<Side> for flat_rpc::Side {
+  fn from(side: Side) -> Self {
+    match side {
+      Side::Client => flat_rpc::Side::Client,
+      Side::Server => flat_rpc::Side::Server,
+    }
+  }
+}
+
 #[derive(clap::ArgEnum, Debug, Clone)]
 enum Lang {
   Rust,
   Ts,
 }
 
+impl From<Lang> for flat_rpc::Lang {
+  fn from(lang: Lang) -> Self {
+    match lang {
+      Lang::Rust => flat_rpc::Lang::Rust,
+      Lang::Ts => flat_rpc::Lang::Ts,
+    }
+  }
+}
+
+#[derive(clap::ArgEnum, Debug, Clone)]
+enum TsInt64 {
+  Bigint,
+  Number,
+  String,
+}
+
+impl From<TsInt64> for Int64Repr {
+  fn from(int64: TsInt64) -> Self {
+    match int64 {
+      TsInt64::Bigint => Int64Repr::Bigint,
+      TsInt64::Number => Int64Repr::Number,
+      TsInt64::String => Int64Repr::String,
+    }
+  }
+}
+
+#[derive(clap::ArgEnum, Debug, Clone)]
+enum RsDerive {
+  Debug,
+  Clone,
+  PartialEq,
+  Serialize,
+  Deserialize,
+}
+
+impl From<RsDerive> for Derive {
+  fn from(derive: RsDerive) -> Self {
+    match derive {
+      RsDerive::Debug => Derive::Debug,
+      RsDerive::Clone => Derive::Clone,
+      RsDerive::PartialEq => Derive::PartialEq,
+      RsDerive::Serialize => Derive::Serialize,
+      RsDerive::Deserialize => Derive::Deserialize,
+    }
+  }
+}
+
 #[derive(Parser, Debug)]
 #[clap(author, version, about)]
 struct Args {
@@ -39,43 +85,38 @@ struct Args {
   side: Side,
   #[clap(short, long, arg_enum)]
   lang: Lang,
+  /** TypeScript representation of 64-bit integers in content types, `number` loses precision above 2^53 */
+  #[clap(long, arg_enum, default_value = "bigint")]
+  ts_int64: TsInt64,
+  /** Derives of Rust content types, comma separated, serde ones need the `serde` crate with the `derive` feature */
+  #[clap(long = "rs-derive", arg_enum, use_value_delimiter = true)]
+  rs_derives: Vec<RsDerive>,
 }
 
 
 fn main() {
-  let Args { input_file, output_file, side, lang } = Args::parse();
-
-  let unparsed_file = match fs::read_to_string(&input_file) {
-    Ok(file) => file,
-    Err(e) => {
-      println!(
-        "{} {}",
-        "💔 Can't read prisma file in path:".yellow(),
+  let Args { input_file, output_file, side, lang, ts_int64, rs_derives } = Args::parse();
+  let config = Config::new()
+    .input(&input_file)
+    .side(side.into())
+    .lang(lang.into())
+    .ts_int64(ts_int64.into())
+    .derives(rs_derives.into_iter().map(Derive::from));
+
+  let generated_code = match config.generate_code() {
+    Ok(generated_code) => generated_code,
+    Err(Error::Parse(diagnostics)) => {
+      eprintln!(
+        "{} {}\n",
+        "💔 Can't parse schema file in path:".yellow(),
         input_file.cyan(),
       );
-
-      panic!("{}", e)
-    }
-  };
-  let statements = parse_fbs_schema(&unparsed_file);
-
-
-  if let Some(errors) = type_check(&statements) {
-    panic!("{errors}");
-  }
-
-  let generated_code = match (side, lang) {
-    (Side::Server, Lang::Rust) => {
-      generate_rust_server_side_code(&statements)
-    }
-    (Side::Client, Lang::Rust) => {
-      todo!("Generate code for clients in rust isn't ready");
+      exit_with_diagnostics(&diagnostics);
     }
-    (Side::Client, Lang::Ts) => {
-      generate_ts_client_side_code(&statements)
-    }
-    (Side::Server, Lang::Ts) => {
-      todo!("Actually, I don't have a plan to do so, at least right now");
+    Err(Error::TypeCheck(diagnostics)) => exit_with_diagnostics(&diagnostics),
+    Err(e) => {
+      eprintln!("{}", e.to_string().red().bold());
+      process::exit(1)
     }
   };
 
@@ -86,3 +127,13 @@ fn main() {
     println!("{generated_code}");
   }
 }
+
+
+fn exit_with_diagnostics(diagnostics: &[Diagnostic]) -> ! {
+  for diagnostic in diagnostics {
+    eprintln!("{}\n", diagnostic.render());
+  }
+  eprintln!("{}", format!("aborting due to {} previous error(s)", diagnostics.len()).red().bold());
+
+  process::exit(1)
+}
@@ -1,26 +1,53 @@
-use std::collections::HashMap;
+use indexmap::IndexMap;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
 use pest::iterators::Pair;
 use pest::Parser;
 use pest_derive::Parser;
 
-use crate::ir::{TableDeclaration, RpcDeclaration, UnionDeclaration, ValueType, StructDeclaration, EnumDeclaration};
+use crate::diagnostic::{Diagnostic, Span, INCLUDE_CYCLE, IO_ERROR, MALFORMED_DECLARATION};
+use crate::ir::{qualified_name, unqualified, TableDeclaration, RpcDeclaration, UnionDeclaration, ValueType, StructDeclaration, EnumDeclaration};
 
 #[derive(Parser)]
 #[grammar = "./grammar.pest"]
 pub struct FbsParser {}
 
 
+/** Name of the optional standard error table, used as a failure response once it's a member of the root union */
+pub const RPC_ERROR: &str = "RpcError";
+
+
+/** Declarations of a schema keyed by their qualified name, `com.acme.User`.
+Type references in them are qualified too once `resolve_references` ran */
 #[derive(Default, Debug)]
 pub struct Statements {
   pub root_type_name: Option<String>,
-  pub table_declaration: HashMap<String, TableDeclaration>,
-  pub struct_declaration: HashMap<String, StructDeclaration>,
-  pub unions: HashMap<String, UnionDeclaration>,
-  pub rpc_declarations: HashMap<String, RpcDeclaration>,
-  pub enum_declarations: HashMap<String, EnumDeclaration>,
+  /** Namespace the `root_type` declaration is in, its name is resolved from there */
+  pub root_type_namespace: Option<String>,
+  pub table_declaration: IndexMap<String, TableDeclaration>,
+  pub struct_declaration: IndexMap<String, StructDeclaration>,
+  pub unions: IndexMap<String, UnionDeclaration>,
+  pub rpc_declarations: IndexMap<String, RpcDeclaration>,
+  pub enum_declarations: IndexMap<String, EnumDeclaration>,
+  /** Raw `include` paths, exactly as written in the schema, with the place they were included at */
+  pub includes: Vec<(String, Span)>,
+  /** Every schema file merged into these statements, included files first */
+  pub files: Vec<PathBuf>,
+  /** File each declaration came from, keyed by declaration name */
+  pub origins: IndexMap<String, PathBuf>,
+  /** Every declared name in declaration order, duplicates included */
+  pub declared_names: Vec<(String, Span)>,
+  /** User attributes declared with `attribute "name";` */
+  pub attribute_declarations: IndexMap<String, Span>,
+  /** Every declared namespace in declaration order, `com.acme.storage` */
+  pub namespaces: Vec<String>,
+  /** Namespace each declaration belongs to, declarations before the first `namespace` have none */
+  pub declaration_namespaces: IndexMap<String, String>,
 }
 
 
+#[allow(dead_code)]
 pub enum DeclType<'a> {
   Table(&'a TableDeclaration),
   Struct(&'a StructDeclaration),
@@ -33,19 +60,140 @@ pub enum DeclType<'a> {
 
 impl Statements {
   pub fn get_available_commands_union(&self) -> Option<&UnionDeclaration> {
-    let root_type_name = self.root_type_name.clone().expect("there is no root type name");
+    let root_type = self.table_declaration.get(self.root_type_name.as_ref()?)?;
+    let payload = root_type.fields.get("payload")?;
+
+    match &payload.value_type {
+      ValueType::CompoundType(union_name) => self.unions.get(union_name),
+      _ => None,
+    }
+  }
+
+  /** `table RpcError { code: uint; message: string; details: string; }` if it's a member of the root union */
+  pub fn rpc_error_table(&self) -> Option<&TableDeclaration> {
+    self.get_available_commands_union()?.items.iter()
+      .find(|item| unqualified(&item.name) == RPC_ERROR)
+      .and_then(|item| self.table_declaration.get(&item.name))
+  }
+
+  pub fn has_streaming_requests(&self) -> bool {
+    self.rpc_declarations.values()
+      .flat_map(|rpc| rpc.methods.values())
+      .any(|method| method.streams_requests())
+  }
+
+  pub fn has_streaming_responses(&self) -> bool {
+    self.rpc_declarations.values()
+      .flat_map(|rpc| rpc.methods.values())
+      .any(|method| method.streams_responses())
+  }
+
+  pub fn has_streaming(&self) -> bool {
+    self.has_streaming_requests() || self.has_streaming_responses()
+  }
+
+  /** Attaches the file `declaration_name` came from to a span found inside that declaration */
+  pub fn locate(&self, declaration_name: &str, span: &Span) -> Span {
+    match self.origins.get(declaration_name) {
+      Some(path) => span.to_owned().in_file(path),
+      None => span.to_owned(),
+    }
+  }
+
+  pub fn namespace_of(&self, name: &str) -> Option<&str> {
+    self.declaration_namespaces.get(name).map(String::as_str)
+  }
+
+  /** Groups declaration names by their namespace, keeping the order namespaces are first used in */
+  pub fn group_by_namespace<'a>(&self, names: impl Iterator<Item = &'a String>) -> IndexMap<Option<&str>, Vec<&'a String>> {
+    let mut grouped: IndexMap<Option<&str>, Vec<&'a String>> = IndexMap::new();
+
+    for name in names {
+      grouped.entry(self.namespace_of(name)).or_default().push(name);
+    }
+
+    grouped
+  }
+
+  pub fn origin_of(&self, name: &str) -> String {
+    match self.origins.get(name) {
+      Some(path) => format!("{name} ({})", path.display()),
+      None => name.to_owned(),
+    }
+  }
+
+  /** Moves declarations of a single parsed file into `self`, remembering where they came from */
+  pub fn merge(&mut self, other: Statements, origin: &Path) {
+    let declared_names = other.table_declaration.keys()
+      .chain(other.struct_declaration.keys())
+      .chain(other.unions.keys())
+      .chain(other.rpc_declarations.keys())
+      .chain(other.enum_declarations.keys());
+    for name in declared_names {
+      self.origins.insert(name.to_owned(), origin.to_path_buf());
+    }
 
-    let root_type = self.table_declaration.get(&root_type_name).expect("there is no root type");
-    let payload = root_type.fields.get("payload").expect("there is no payload in root type");
+    self.table_declaration.extend(other.table_declaration);
+    self.struct_declaration.extend(other.struct_declaration);
+    self.unions.extend(other.unions);
+    self.rpc_declarations.extend(other.rpc_declarations);
+    self.enum_declarations.extend(other.enum_declarations);
+    self.declared_names.extend(other.declared_names);
+    self.attribute_declarations.extend(other.attribute_declarations);
+    self.declaration_namespaces.extend(other.declaration_namespaces);
+    for namespace in other.namespaces {
+      if !self.namespaces.contains(&namespace) {
+        self.namespaces.push(namespace);
+      }
+    }
+    self.files.push(origin.to_path_buf());
+  }
 
-    let union_name = match &payload.value_type {
-      ValueType::CompoundType(union_name) => union_name,
-      _ => panic!("union root")
+  /** Replaces every type reference with the qualified name of the declaration it refers to.
+  References nothing is declared for are left as written, the typechecker reports them */
+  pub fn resolve_references(&mut self) {
+    let declared = self.declared_names.iter().map(|(name, _)| name.as_str()).collect::<HashSet<&str>>();
+    let namespaces = &self.declaration_namespaces;
+    let resolve = |name: &mut String, declaration: &str| {
+      if let Some(qualified) = resolve_name(&declared, name, namespaces.get(declaration).map(String::as_str)) {
+        *name = qualified;
+      }
     };
-    self.unions.get(union_name)
+
+    for table in self.table_declaration.values_mut() {
+      for field_type in table.fields.values_mut() {
+        if let ValueType::CompoundType(type_name) = &mut field_type.value_type {
+          resolve(type_name, &table.name);
+        }
+      }
+    }
+    for struct_decl in self.struct_declaration.values_mut() {
+      for field_type in struct_decl.fields.values_mut() {
+        if let ValueType::CompoundType(type_name) = &mut field_type.value_type {
+          resolve(type_name, &struct_decl.name);
+        }
+      }
+    }
+    for union in self.unions.values_mut() {
+      for item in union.items.iter_mut() {
+        resolve(&mut item.name, &union.name);
+      }
+    }
+    for rpc_decl in self.rpc_declarations.values_mut() {
+      for method in rpc_decl.methods.values_mut() {
+        resolve(&mut method.input, &rpc_decl.name);
+        resolve(&mut method.output, &rpc_decl.name);
+      }
+    }
+
+    if let Some(root_type_name) = &mut self.root_type_name {
+      if let Some(qualified) = resolve_name(&declared, root_type_name, self.root_type_namespace.as_deref()) {
+        *root_type_name = qualified;
+      }
+    }
   }
 
-  pub fn resolve_decl_by_name(&self, name: &str) -> DeclType {
+  pub fn resolve_decl_by_name(&self, name: &str) -> DeclType<'_> {
     if let Some(table_decl) = self.table_declaration.get(name) {
       return DeclType::Table(table_decl);
     } else if let Some(struct_decl) = self.struct_declaration.get(name) {
@@ -60,66 +208,489 @@ impl Statements {
 
     DeclType::Null
   }
+
+  /** Declarations generated code refers to: the root type, the rpc services and the tables they take and
+  return, and every type reachable from those. Unused declarations of included schemas aren't generated */
+  pub fn used_declarations(&self) -> HashSet<&str> {
+    let mut used = HashSet::new();
+    let mut pending = self.rpc_declarations.values()
+      .flat_map(|rpc| rpc.methods.values())
+      .flat_map(|method| [method.input.as_str(), method.output.as_str()])
+      .chain(self.rpc_declarations.keys().map(String::as_str))
+      .chain(self.root_type_name.as_deref())
+      .collect::<Vec<&str>>();
+
+    while let Some(name) = pending.pop() {
+      if !used.insert(name) {
+        continue;
+      }
+
+      let fields = match self.resolve_decl_by_name(name) {
+        DeclType::Table(table) => &table.fields,
+        DeclType::Struct(struct_decl) => &struct_decl.fields,
+        DeclType::Union(union) => {
+          pending.extend(union.items.iter().map(|item| item.name.as_str()));
+          continue;
+        }
+        _ => continue,
+      };
+      for field_type in fields.values() {
+        if let ValueType::CompoundType(type_name) = &field_type.value_type {
+          pending.push(type_name);
+        }
+      }
+    }
+
+    used
+  }
+
+  /** Drops the declarations `used_declarations` leaves out, keeping the order of the rest */
+  pub fn retain_used(&mut self) {
+    let used = self.used_declarations().into_iter().map(str::to_owned).collect::<HashSet<String>>();
+
+    self.table_declaration.retain(|name, _| used.contains(name));
+    self.struct_declaration.retain(|name, _| used.contains(name));
+    self.enum_declarations.retain(|name, _| used.contains(name));
+    self.unions.retain(|name, _| used.contains(name));
+  }
+}
+
+
+/** Finds the declaration `name` used in `namespace` refers to the way flatc does:
+`User` used in `com.acme` is looked up as `com.acme.User`, then `com.User` and `User` */
+fn resolve_name(declared: &HashSet<&str>, name: &str, namespace: Option<&str>) -> Option<String> {
+  let mut scope = namespace;
+
+  loop {
+    let candidate = qualified_name(scope, name);
+    if declared.contains(candidate.as_str()) {
+      return Some(candidate);
+    }
+
+    scope = match scope {
+      Some(namespace) => namespace.rsplit_once('.').map(|(outer, _)| outer),
+      None => return None,
+    };
+  }
 }
 
 
-pub fn parse_fbs_schema(schema_string: &str) -> Statements {
+/** Parses a schema without includes, use `parse_fbs_file` for one that has them */
+pub fn parse_fbs_schema(schema_string: &str, file: &Path) -> Result<Statements, Vec<Diagnostic>> {
+  let mut statements = parse_fbs_source(schema_string, file)?;
+  statements.resolve_references();
+  Ok(statements)
+}
+
+/** Declarations of a single file, their references are resolved once every included file is merged */
+fn parse_fbs_source(schema_string: &str, file: &Path) -> Result<Statements, Vec<Diagnostic>> {
   let parsed = FbsParser::parse(Rule::schema, schema_string);
-  let mut file = match parsed {
+  let mut parsed_file = match parsed {
     Ok(parse_result) =>
       parse_result,
     Err(e) => {
-      panic!("Error while parsing file {:?}", e)
+      return Err(vec![Diagnostic::from_pest_error(e, schema_string).in_file(file)]);
     }
   };
 
 
   let mut statements = Statements::default();
+  let mut diagnostics = vec![];
 
+  let parsed_file = match parsed_file.next() {
+    Some(parsed_file) => parsed_file,
+    None => return Ok(statements),
+  };
+  for statement in parsed_file.into_inner() {
+    if let Err(diagnostic) = parse_statement(statement, &mut statements, file) {
+      diagnostics.push(diagnostic.in_file(file));
+    }
+  }
 
-  let file = file.next().unwrap();
-  for statement in file.into_inner() {
-    match statement.as_rule() {
-      Rule::table_decl => {
-        let table_decl = TableDeclaration::from(statement);
-        statements.table_declaration.insert(table_decl.name.to_owned(), table_decl);
-      }
-      Rule::struct_decl => {
-        let struct_decl = StructDeclaration::from(statement);
-        statements.struct_declaration.insert(struct_decl.name.to_owned(), struct_decl);
-      }
-      Rule::root_decl => {
-        let statement: Pair<'_, Rule> = statement;
-        let mut root_type = statement.into_inner();
-        let name = root_type.next().unwrap().as_str().to_owned();
-        statements.root_type_name = Some(name.to_owned());
-      }
-      Rule::union_decl => {
-        let union = UnionDeclaration::from(statement);
-        statements.unions.insert(union.name.to_owned(), union);
-      }
-      Rule::enum_decl => {
-        let enum_decl = EnumDeclaration::from(statement);
-        statements.enum_declarations.insert(enum_decl.name.to_owned(), enum_decl);
-      }
-      Rule::rpc_decl => {
-        let rpc_decl = RpcDeclaration::from(statement);
-        statements.rpc_declarations.insert("".to_owned(), rpc_decl);
+  if diagnostics.is_empty() {
+    Ok(statements)
+  } else {
+    Err(diagnostics)
+  }
+}
+
+fn parse_statement(statement: Pair<'_, Rule>, statements: &mut Statements, file: &Path) -> Result<(), Diagnostic> {
+  let declared_before = statements.declared_names.len();
+  // a `namespace` applies to every declaration after it until the next one
+  let namespace = statements.namespaces.last().cloned();
+  let qualified = |name: &str| qualified_name(namespace.as_deref(), name);
+
+  match statement.as_rule() {
+    Rule::table_decl => {
+      let mut table_decl = TableDeclaration::try_from(statement)?;
+      table_decl.name = qualified(&table_decl.name);
+      statements.declared_names.push((table_decl.name.to_owned(), table_decl.span.to_owned().in_file(file)));
+      statements.table_declaration.insert(table_decl.name.to_owned(), table_decl);
+    }
+    Rule::struct_decl => {
+      let mut struct_decl = StructDeclaration::try_from(statement)?;
+      struct_decl.name = qualified(&struct_decl.name);
+      statements.declared_names.push((struct_decl.name.to_owned(), struct_decl.span.to_owned().in_file(file)));
+      statements.struct_declaration.insert(struct_decl.name.to_owned(), struct_decl);
+    }
+    Rule::root_decl => {
+      let span = statement.as_span();
+      let name = statement.into_inner().next()
+        .ok_or_else(|| Diagnostic::new(MALFORMED_DECLARATION, "root_type must name a table", Some(span.into())))?
+        .as_str();
+      statements.root_type_name = Some(name.to_owned());
+      statements.root_type_namespace = namespace.to_owned();
+    }
+    Rule::union_decl => {
+      let mut union = UnionDeclaration::try_from(statement)?;
+      union.name = qualified(&union.name);
+      statements.declared_names.push((union.name.to_owned(), union.span.to_owned().in_file(file)));
+      statements.unions.insert(union.name.to_owned(), union);
+    }
+    Rule::enum_decl => {
+      let mut enum_decl = EnumDeclaration::try_from(statement)?;
+      enum_decl.name = qualified(&enum_decl.name);
+      statements.declared_names.push((enum_decl.name.to_owned(), enum_decl.span.to_owned().in_file(file)));
+      statements.enum_declarations.insert(enum_decl.name.to_owned(), enum_decl);
+    }
+    Rule::include_decl => {
+      let span = Span::from(statement.as_span()).in_file(file);
+      let path = statement.into_inner().next()
+        .map(|path| path.into_inner().as_str().to_owned())
+        .ok_or_else(|| Diagnostic::new(MALFORMED_DECLARATION, "include must name a file", Some(span.to_owned())))?;
+      statements.includes.push((path, span));
+    }
+    Rule::attribute_decl => {
+      let span = Span::from(statement.as_span()).in_file(file);
+      let name = statement.into_inner().next()
+        .map(|name| match name.as_rule() {
+          Rule::string_literal => name.into_inner().as_str().to_owned(),
+          _ => name.as_str().to_owned(),
+        })
+        .ok_or_else(|| Diagnostic::new(MALFORMED_DECLARATION, "attribute must have a name", Some(span.to_owned())))?;
+      statements.attribute_declarations.insert(name, span);
+    }
+    Rule::rpc_decl => {
+      let mut rpc_decl = RpcDeclaration::try_from(statement)?;
+      rpc_decl.name = qualified(&rpc_decl.name);
+      statements.declared_names.push((rpc_decl.name.to_owned(), rpc_decl.span.to_owned().in_file(file)));
+      statements.rpc_declarations.insert(rpc_decl.name.to_owned(), rpc_decl);
+    }
+    Rule::namespace_decl => {
+      let span = Span::from(statement.as_span()).in_file(file);
+      let namespace = statement.into_inner().next()
+        .map(|namespace| namespace.as_str().to_owned())
+        .ok_or_else(|| Diagnostic::new(MALFORMED_DECLARATION, "namespace must have a name", Some(span)))?;
+      statements.namespaces.push(namespace);
+    }
+    _ => {}
+  }
+
+  if let Some(namespace) = namespace {
+    for (name, _) in &statements.declared_names[declared_before..] {
+      statements.declaration_namespaces.insert(name.to_owned(), namespace.to_owned());
+    }
+  }
+
+  Ok(())
+}
+
+
+/** Parses the schema in `path` together with everything it includes.
+Includes are resolved relative to the including file, each file is merged only once
+and the root type is taken from the top level file only. */
+pub fn parse_fbs_file(path: &Path) -> Result<Statements, Vec<Diagnostic>> {
+  parse_fbs_file_tracked(path).0
+}
+
+/** Same as `parse_fbs_file`, also returning every file reached while loading, the ones that
+can't be read or parsed included, so a build script can watch them even when loading fails */
+pub fn parse_fbs_file_tracked(path: &Path) -> (Result<Statements, Vec<Diagnostic>>, Vec<PathBuf>) {
+  let mut statements = Statements::default();
+  let mut include_stack = vec![];
+  let mut reached = vec![];
+  let mut diagnostics = vec![];
+
+  load_fbs_file(path, None, &mut statements, &mut include_stack, &mut reached, &mut diagnostics);
+
+  if diagnostics.is_empty() {
+    statements.resolve_references();
+    (Ok(statements), reached)
+  } else {
+    (Err(diagnostics), reached)
+  }
+}
+
+fn load_fbs_file(
+  path: &Path,
+  included_at: Option<&Span>,
+  statements: &mut Statements,
+  include_stack: &mut Vec<PathBuf>,
+  reached: &mut Vec<PathBuf>,
+  diagnostics: &mut Vec<Diagnostic>,
+) {
+  let report_at = |code, message: String| {
+    match included_at {
+      Some(span) => Diagnostic::new(code, message, Some(span.to_owned())),
+      None => Diagnostic::new(code, message, None).in_file(path),
+    }
+  };
+
+  let path = match fs::canonicalize(path) {
+    Ok(path) => path,
+    Err(e) => {
+      if !reached.iter().any(|file| file == path) {
+        reached.push(path.to_path_buf());
       }
-      _ => {}
+      diagnostics.push(report_at(IO_ERROR, format!("can't read schema file {}: {}", path.display(), e)));
+      return;
+    }
+  };
+  if !reached.contains(&path) {
+    reached.push(path.to_owned());
+  }
+
+  if include_stack.contains(&path) {
+    let cycle = include_stack.iter()
+      .skip_while(|item| **item != path)
+      .chain(std::iter::once(&path))
+      .map(|item| item.display().to_string())
+      .collect::<Vec<String>>();
+    diagnostics.push(report_at(INCLUDE_CYCLE, format!("include cycle detected: {}", cycle.join(" -> "))));
+    return;
+  }
+
+  if statements.files.contains(&path) {
+    return;
+  }
+
+  let schema_string = match fs::read_to_string(&path) {
+    Ok(schema_string) => schema_string,
+    Err(e) => {
+      diagnostics.push(report_at(IO_ERROR, format!("can't read schema file {}: {}", path.display(), e)));
+      return;
     }
+  };
+  let mut file_statements = match parse_fbs_source(&schema_string, &path) {
+    Ok(file_statements) => file_statements,
+    Err(errors) => {
+      diagnostics.extend(errors);
+      return;
+    }
+  };
+
+  let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+  include_stack.push(path.to_owned());
+  for (include, span) in &file_statements.includes {
+    load_fbs_file(&base_dir.join(include), Some(span), statements, include_stack, reached, diagnostics);
   }
+  include_stack.pop();
 
-  statements
+  if include_stack.is_empty() {
+    statements.root_type_name = file_statements.root_type_name.take();
+    statements.root_type_namespace = file_statements.root_type_namespace.take();
+  }
+  statements.merge(file_statements, &path);
 }
 
 
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::diagnostic::{INCLUDE_CYCLE, IO_ERROR, SYNTAX_ERROR};
 
+  /** Writes `files` into a fresh directory under the system temp dir */
+  fn schema_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("flat-rpc-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
 
+    for (file, contents) in files {
+      let path = dir.join(file);
+      fs::create_dir_all(path.parent().unwrap()).unwrap();
+      fs::write(path, contents).unwrap();
+    }
+
+    fs::canonicalize(dir).unwrap()
+  }
+
+  fn codes(diagnostics: &[Diagnostic]) -> Vec<&'static str> {
+    diagnostics.iter().map(|diagnostic| diagnostic.code).collect()
+  }
+
+  #[test]
+  fn includes_resolve_relative_to_the_including_file() {
+    let dir = schema_dir("relative", &[
+      ("main.fbs", "include \"types/user.fbs\";\ntable Root { user: User; }\nroot_type Root;\n"),
+      ("types/user.fbs", "include \"name.fbs\";\ntable User { name: Name; }\n"),
+      ("types/name.fbs", "table Name { value: string; }\n"),
+    ]);
+
+    let statements = parse_fbs_file(&dir.join("main.fbs")).unwrap();
+
+    assert_eq!(statements.files, vec![dir.join("types/name.fbs"), dir.join("types/user.fbs"), dir.join("main.fbs")]);
+    assert_eq!(statements.origins.get("Name"), Some(&dir.join("types/name.fbs")));
+    assert_eq!(statements.origins.get("User"), Some(&dir.join("types/user.fbs")));
+    assert_eq!(statements.origins.get("Root"), Some(&dir.join("main.fbs")));
+  }
+
+  #[test]
+  fn a_file_included_twice_is_merged_once() {
+    let dir = schema_dir("dedup", &[
+      ("main.fbs", "include \"a.fbs\";\ninclude \"b.fbs\";\ninclude \"./common.fbs\";\ntable Root { a: A; }\n"),
+      ("a.fbs", "include \"common.fbs\";\ntable A { common: Common; }\n"),
+      ("b.fbs", "include \"common.fbs\";\ntable B { common: Common; }\n"),
+      ("common.fbs", "table Common { value: int; }\n"),
+    ]);
+
+    let statements = parse_fbs_file(&dir.join("main.fbs")).unwrap();
+
+    assert_eq!(statements.files.iter().filter(|file| **file == dir.join("common.fbs")).count(), 1);
+    assert_eq!(statements.declared_names.iter().filter(|(name, _)| name == "Common").count(), 1);
+    assert_eq!(statements.files.len(), 4);
+  }
+
+  #[test]
+  fn include_cycles_are_reported_at_the_include() {
+    let dir = schema_dir("cycle", &[
+      ("a.fbs", "include \"b.fbs\";\ntable A { value: int; }\n"),
+      ("b.fbs", "include \"a.fbs\";\ntable B { value: int; }\n"),
+    ]);
+
+    let diagnostics = parse_fbs_file(&dir.join("a.fbs")).unwrap_err();
+
+    assert_eq!(codes(&diagnostics), vec![INCLUDE_CYCLE]);
+    let span = diagnostics[0].span.as_ref().unwrap();
+    assert_eq!(span.file, dir.join("b.fbs"));
+    assert_eq!(span.line, 1);
+    assert!(diagnostics[0].message.contains(&format!("{} -> {} -> {}", dir.join("a.fbs").display(), dir.join("b.fbs").display(), dir.join("a.fbs").display())));
+  }
+
+  #[test]
+  fn a_missing_include_names_the_including_file() {
+    let dir = schema_dir("missing", &[
+      ("other.fbs", "// comment\ninclude \"absent.fbs\";\ntable Other { value: int; }\n"),
+    ]);
+
+    let diagnostics = parse_fbs_file(&dir.join("other.fbs")).unwrap_err();
+
+    assert_eq!(codes(&diagnostics), vec![IO_ERROR]);
+    let span = diagnostics[0].span.as_ref().unwrap();
+    assert_eq!((span.file.to_owned(), span.line), (dir.join("other.fbs"), 2));
+    assert!(diagnostics[0].message.contains("absent.fbs"));
+  }
+
+  #[test]
+  fn the_root_type_comes_from_the_top_level_file_only() {
+    let dir = schema_dir("root", &[
+      ("main.fbs", "include \"other.fbs\";\ntable Root { value: int; }\nroot_type Root;\n"),
+      ("other.fbs", "table Other { value: int; }\nroot_type Other;\n"),
+      ("no_root.fbs", "include \"other.fbs\";\ntable Root { value: int; }\n"),
+    ]);
+
+    assert_eq!(parse_fbs_file(&dir.join("main.fbs")).unwrap().root_type_name.as_deref(), Some("Root"));
+    assert_eq!(parse_fbs_file(&dir.join("no_root.fbs")).unwrap().root_type_name, None);
+  }
+
+  #[test]
+  fn references_resolve_from_the_innermost_namespace() {
+    let schema = "
+namespace com;
+table Base { value: int; }
+table User { value: int; }
+namespace com.acme;
+table User { base: Base; other: com.User; groups: [Group]; missing: Missing; }
+table Group { value: int; }
+root_type User;
+";
+    let statements = parse_fbs_schema(schema, Path::new("ns.fbs")).unwrap();
+    let user = statements.table_declaration.get("com.acme.User").unwrap();
+    let field_type = |name: &str| match &user.fields.get(name).unwrap().value_type {
+      ValueType::CompoundType(type_name) => type_name.to_owned(),
+      other => format!("{other:?}"),
+    };
+
+    assert_eq!(field_type("base"), "com.Base");
+    assert_eq!(field_type("other"), "com.User");
+    assert_eq!(field_type("groups"), "com.acme.Group");
+    assert_eq!(field_type("missing"), "Missing");
+    assert_eq!(statements.root_type_name.as_deref(), Some("com.acme.User"));
+    assert_eq!(statements.namespace_of("com.acme.User"), Some("com.acme"));
+  }
+
+  #[test]
+  fn included_declarations_resolve_in_their_own_namespace() {
+    let dir = schema_dir("namespaces", &[
+      ("main.fbs", "include \"user.fbs\";\nnamespace app;\ntable Root { user: model.User; }\nroot_type Root;\n"),
+      ("user.fbs", "namespace model;\ntable Name { value: string; }\ntable User { name: Name; }\n"),
+    ]);
+
+    let statements = parse_fbs_file(&dir.join("main.fbs")).unwrap();
+    let field_type = |table: &str, field: &str| match &statements.table_declaration.get(table).unwrap().fields.get(field).unwrap().value_type {
+      ValueType::CompoundType(type_name) => type_name.to_owned(),
+      other => format!("{other:?}"),
+    };
+
+    assert_eq!(field_type("app.Root", "user"), "model.User");
+    assert_eq!(field_type("model.User", "name"), "model.Name");
+    assert_eq!(statements.root_type_name.as_deref(), Some("app.Root"));
+    assert_eq!(statements.origins.get("model.User"), Some(&dir.join("user.fbs")));
+  }
+
+  #[test]
+  fn used_declarations_are_reached_from_the_root_type_and_services() {
+    let schema = "
+namespace lib;
+enum Level: byte { Low, High }
+struct Point { x: float; }
+table Status { level: Level; }
+table Unused { status: Status; }
+namespace app;
+table Status { at: lib.Point; }
+table Req { status: Status; }
+table Resp { n: int; }
+union Payload { Req, Resp }
+table Root { id: string; payload: Payload; }
+root_type Root;
+rpc_service Api { Get(Req): Resp; }
+";
+    let mut statements = parse_fbs_schema(schema, Path::new("used.fbs")).unwrap();
+    let mut used = statements.used_declarations().into_iter().collect::<Vec<&str>>();
+    used.sort();
+
+    assert_eq!(used, vec!["app.Api", "app.Payload", "app.Req", "app.Resp", "app.Root", "app.Status", "lib.Point"]);
 
+    statements.retain_used();
+    assert_eq!(statements.table_declaration.keys().collect::<Vec<&String>>(), vec!["app.Status", "app.Req", "app.Resp", "app.Root"]);
+    assert!(statements.enum_declarations.is_empty());
+  }
 
+  #[test]
+  fn syntax_errors_point_at_the_offending_token() {
+    let diagnostics = parse_fbs_schema("table User {\n  name: string\n}\n", Path::new("user.fbs")).unwrap_err();
 
+    assert_eq!(codes(&diagnostics), vec![SYNTAX_ERROR]);
+    let span = diagnostics[0].span.as_ref().unwrap();
+    assert_eq!((span.file.as_path(), span.line, span.column), (Path::new("user.fbs"), 3, 1));
+    assert_eq!(span.line_text, "}");
+    assert!(diagnostics[0].message.starts_with("expected"));
+  }
 
+  #[test]
+  fn malformed_declarations_are_reported_together() {
+    let schema = "enum Big: long { A = 9223372036854775808 }\ntable T { value: int = 99999999999999999999; }\ntable Fine { value: int; }\n";
+    let diagnostics = parse_fbs_schema(schema, Path::new("big.fbs")).unwrap_err();
 
+    assert_eq!(codes(&diagnostics), vec![MALFORMED_DECLARATION, MALFORMED_DECLARATION]);
+    let lines = diagnostics.iter().map(|diagnostic| diagnostic.span.as_ref().unwrap().line).collect::<Vec<usize>>();
+    assert_eq!(lines, vec![1, 2]);
+    assert!(diagnostics[0].message.contains("9223372036854775808"));
+  }
 
+  #[test]
+  fn an_empty_schema_has_no_declarations() {
+    let statements = parse_fbs_schema("// nothing here\n", Path::new("empty.fbs")).unwrap();
 
+    assert!(statements.declared_names.is_empty());
+    assert_eq!(statements.root_type_name, None);
+  }
+}
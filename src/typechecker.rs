@@ -1,50 +1,869 @@
-use crate::parser::Statements;
+use std::collections::{HashMap, HashSet};
+use indexmap::IndexMap;
+
+use crate::diagnostic::{
+  Diagnostic, Span, AMBIGUOUS_NAME, DUPLICATE_DECLARATION, DUPLICATE_ENUM_VALUE, DUPLICATE_FIELD, ENUM_VALUE_OUT_OF_RANGE, INVALID_ATTRIBUTE, INVALID_DEFAULT_VALUE, INVALID_ENUM_TYPE,
+  INVALID_FIELD_ID, INVALID_FIXED_ARRAY, INVALID_ROOT_TYPE, INVALID_RPC_ERROR_TABLE, INVALID_RPC_TABLE, INVALID_STREAMING, INVALID_STRUCT_FIELD, INVALID_UNION_MEMBER, MISSING_IN_ROOT_UNION, MISSING_ROOT_TYPE, UNDECLARED_TYPE,
+  UNKNOWN_ATTRIBUTE,
+};
+use crate::ir::{unqualified, AttributeValue, DefaultValue, EnumValue, Metadata, Streaming, TableDeclaration, Type, ValueType};
+use crate::parser::{DeclType, Statements, RPC_ERROR};
 
 
 /** Check if all RPC tables are in root table payload union */
-fn check_root_union(statements: &Statements) -> Option<String> {
-  let rpc_types = statements.rpc_declarations.values().flat_map(|rpc_decl| {
-    rpc_decl.methods.values().flat_map(|method| {
-      vec![method.input.to_owned(), method.output.to_owned()]
-    }).collect::<Vec<String>>()
-  }).collect::<Vec<String>>();
+fn check_root_union(statements: &Statements, errors: &mut Vec<Diagnostic>) {
+  let root_union = match statements.get_available_commands_union() {
+    Some(root_union) => root_union,
+    None => return,
+  };
+
+  for rpc_decl in statements.rpc_declarations.values() {
+    for method in rpc_decl.methods.values() {
+      for table in [&method.input, &method.output] {
+        if !root_union.contains(table) {
+          errors.push(Diagnostic::new(
+            MISSING_IN_ROOT_UNION,
+            format!("{} is used in RPC method {}::{}, but not presented in root table payload union {}", statements.origin_of(table), rpc_decl.name, method.name, root_union.name),
+            Some(statements.locate(&rpc_decl.name, &method.span)),
+          ));
+        }
+      }
+    }
+  }
+}
+
+
+/** Check root_type points to a table with a `payload` union field */
+fn check_root_type(statements: &Statements, errors: &mut Vec<Diagnostic>) {
+  let root_type_name = match &statements.root_type_name {
+    Some(root_type_name) => root_type_name,
+    None => {
+      let mut diagnostic = Diagnostic::new(MISSING_ROOT_TYPE, "There is no root type in the schema", None);
+      if let Some(file) = statements.files.last() {
+        diagnostic = diagnostic.in_file(file);
+      }
+      errors.push(diagnostic);
+      return;
+    }
+  };
+
+  let root_type = match statements.resolve_decl_by_name(root_type_name) {
+    DeclType::Table(root_type) => root_type,
+    _ => {
+      let mut diagnostic = Diagnostic::new(INVALID_ROOT_TYPE, format!("root_type {root_type_name} must be a table"), None);
+      if let Some(file) = statements.files.last() {
+        diagnostic = diagnostic.in_file(file);
+      }
+      errors.push(diagnostic);
+      return;
+    }
+  };
+
+  if statements.get_available_commands_union().is_none() {
+    errors.push(Diagnostic::new(
+      INVALID_ROOT_TYPE,
+      format!("root table {root_type_name} must have a `payload` field of a union type"),
+      Some(statements.locate(root_type_name, &root_type.span)),
+    ));
+  }
+}
+
+
+fn check_duplicate_declarations(statements: &Statements, errors: &mut Vec<Diagnostic>) {
+  let mut seen: HashMap<&str, &Span> = HashMap::new();
+
+  for (name, span) in &statements.declared_names {
+    match seen.get(name.as_str()) {
+      Some(first) => errors.push(Diagnostic::new(
+        DUPLICATE_DECLARATION,
+        format!("{name} is declared more than once, first declaration is at {}:{}", first.file.display(), first.line),
+        Some(span.to_owned()),
+      )),
+      None => {
+        seen.insert(name, span);
+      }
+    }
+  }
+}
+
+
+/** Generated code puts every namespace into one module, so `a.User` and `b.User` would clash there
+once both are used. Declarations nothing uses aren't generated, their names are free to repeat */
+fn check_ambiguous_names(statements: &Statements, errors: &mut Vec<Diagnostic>) {
+  let used = statements.used_declarations();
+  let mut seen: HashMap<&str, &str> = HashMap::new();
+
+  for (name, span) in statements.declared_names.iter().filter(|(name, _)| used.contains(name.as_str())) {
+    match seen.get(unqualified(name)) {
+      Some(first) if first != name => errors.push(Diagnostic::new(
+        AMBIGUOUS_NAME,
+        format!("{name} has the same name as {first}, both are used by generated code which doesn't keep namespaces apart"),
+        Some(span.to_owned()),
+      )),
+      Some(_) => {}
+      None => {
+        seen.insert(unqualified(name), name);
+      }
+    }
+  }
+}
+
+
+fn check_duplicate_fields(statements: &Statements, decl_name: &str, fields_order: &[String], fields: &IndexMap<String, Type>, errors: &mut Vec<Diagnostic>) {
+  let mut seen = HashSet::new();
+
+  for field_name in fields_order {
+    if !seen.insert(field_name) {
+      errors.push(Diagnostic::new(
+        DUPLICATE_FIELD,
+        format!("field {field_name} is declared more than once in {decl_name}"),
+        fields.get(field_name).map(|field| statements.locate(decl_name, &field.span)),
+      ));
+    }
+  }
+}
+
 
+/** Check names and discriminants are unique and every discriminant fits into `range` */
+fn check_enum_values(statements: &Statements, decl_name: &str, discriminants: &[(&EnumValue, i64)], range: (i64, i64), errors: &mut Vec<Diagnostic>) {
+  let mut seen_names = HashSet::new();
+  let mut seen_values = HashMap::new();
+  let mut previous = None;
+
+  for (item, value) in discriminants {
+    // an implicit value after i64::MAX wraps around, flatc refuses it too
+    let overflows = item.value.is_none() && previous == Some(i64::MAX);
+    previous = Some(*value);
+    if !seen_names.insert(&item.name) {
+      errors.push(Diagnostic::new(
+        DUPLICATE_ENUM_VALUE,
+        format!("{} is declared more than once in {decl_name}", item.name),
+        Some(statements.locate(decl_name, &item.span)),
+      ));
+    } else if let Some(first) = seen_values.insert(*value, &item.name) {
+      errors.push(Diagnostic::new(
+        DUPLICATE_ENUM_VALUE,
+        format!("{} has the same value {value} as {first} in {decl_name}", item.name),
+        Some(statements.locate(decl_name, &item.span)),
+      ));
+    }
 
-  let root_union = statements.get_available_commands_union().unwrap();
+    if overflows {
+      errors.push(Diagnostic::new(
+        ENUM_VALUE_OUT_OF_RANGE,
+        format!("implicit value of {} in {decl_name} overflows 64 bits, give it an explicit value", item.name),
+        Some(statements.locate(decl_name, &item.span)),
+      ));
+    } else if *value < range.0 || *value > range.1 {
+      errors.push(Diagnostic::new(
+        ENUM_VALUE_OUT_OF_RANGE,
+        format!("value {value} of {} doesn't fit into {decl_name}, it must be in {}..={}", item.name, range.0, range.1),
+        Some(statements.locate(decl_name, &item.span)),
+      ));
+    }
+  }
+}
 
-  let missed_tables = rpc_types.iter().filter_map(|item| {
-    if root_union.items.contains(item) {
+
+/** Range of values an integer type can hold, `None` for non integer types.
+Discriminants are stored as i64, so unsigned 64 bit enums are capped at i64::MAX */
+fn integer_range(value_type: &ValueType) -> Option<(i64, i64)> {
+  match value_type {
+    ValueType::Byte | ValueType::Int8 => Some((i8::MIN.into(), i8::MAX.into())),
+    ValueType::Ubyte | ValueType::Uint8 => Some((u8::MIN.into(), u8::MAX.into())),
+    ValueType::Short | ValueType::Int16 => Some((i16::MIN.into(), i16::MAX.into())),
+    ValueType::Ushort | ValueType::Uint16 => Some((u16::MIN.into(), u16::MAX.into())),
+    ValueType::Int | ValueType::Int32 => Some((i32::MIN.into(), i32::MAX.into())),
+    ValueType::Uint | ValueType::Uint32 => Some((u32::MIN.into(), u32::MAX.into())),
+    ValueType::Long | ValueType::Int64 => Some((i64::MIN, i64::MAX)),
+    ValueType::Ulong | ValueType::Uint64 => Some((0, i64::MAX)),
+    _ => None,
+  }
+}
+
+
+/** Check every referenced type is declared, reports it and returns `None` otherwise */
+fn resolve_field_type<'a>(statements: &'a Statements, decl_name: &str, field_type: &Type, errors: &mut Vec<Diagnostic>) -> Option<DeclType<'a>> {
+  let type_name = match &field_type.value_type {
+    ValueType::CompoundType(type_name) => type_name,
+    _ => return None,
+  };
+
+  match statements.resolve_decl_by_name(type_name) {
+    DeclType::Null | DeclType::Rpc(_) => {
+      errors.push(Diagnostic::new(
+        UNDECLARED_TYPE,
+        format!("type {type_name} used in {decl_name} is not declared"),
+        Some(statements.locate(decl_name, &field_type.span)),
+      ));
       None
-    } else {
-      Some(item.to_owned())
     }
-  })
-    .collect::<Vec<String>>();
+    decl => Some(decl),
+  }
+}
 
 
-  if missed_tables.is_empty() {
-    None
-  } else {
-    Some(format!("Following tables included into RPC statemtns, but not presented in root table payload union: {}", missed_tables.join(", ")))
+/** Defaults are allowed for scalars and enums only and have to fit into the field type, `null` makes them optional */
+fn check_default_value(statements: &Statements, decl_name: &str, field_name: &str, field_type: &Type, errors: &mut Vec<Diagnostic>) {
+  let default_value = match &field_type.default_value {
+    Some(default_value) => default_value,
+    None => return,
+  };
+
+  let is_valid = match (&field_type.value_type, field_type.is_array, default_value) {
+    (_, true, _) => false,
+    (ValueType::String, false, _) => false,
+    (ValueType::CompoundType(type_name), false, DefaultValue::Null) => statements.enum_declarations.contains_key(type_name),
+    (_, false, DefaultValue::Null) => true,
+    (ValueType::Bool, false, DefaultValue::Bool(_)) => true,
+    (ValueType::Float | ValueType::Float32 | ValueType::Double | ValueType::Float64, false, DefaultValue::Float(_) | DefaultValue::Integer(_)) => true,
+    (ValueType::CompoundType(type_name), false, _) => match (statements.enum_declarations.get(type_name), default_value) {
+      (Some(enum_decl), DefaultValue::Identifier(variant)) => enum_decl.items.iter().any(|item| &item.name == variant),
+      (Some(enum_decl), DefaultValue::Integer(value)) => enum_decl.discriminants().iter().any(|(_, discriminant)| discriminant == value),
+      _ => false,
+    },
+    (value_type, false, DefaultValue::Integer(value)) => match integer_range(value_type) {
+      Some((min, max)) => min <= *value && *value <= max,
+      None => false,
+    },
+    _ => false,
+  };
+
+  if !is_valid {
+    errors.push(Diagnostic::new(
+      INVALID_DEFAULT_VALUE,
+      format!("default value of field {field_name} in {decl_name} doesn't match its type, defaults are allowed only for scalars and enums"),
+      Some(statements.locate(decl_name, &field_type.span)),
+    ));
   }
 }
 
 
-pub fn type_check(statements: &Statements) -> Option<String> {
-  let mut errors = vec![];
-  if statements.root_type_name.is_none() {
-    errors.push("There is no root type in the schema".to_owned())
+/** Attributes flatc understands without an `attribute` declaration */
+const BUILTIN_ATTRIBUTES: &[&str] = &[
+  "id", "deprecated", "required", "key", "hash", "force_align", "bit_flags", "original_order", "nested_flatbuffer", "flexbuffer", "shared", "private",
+  "streaming", "idempotent", "vector64", "offset64", "csharp_partial", "native_inline", "native_default", "native_custom_alloc", "native_type",
+  "native_type_pack_name", "cpp_type", "cpp_ptr_type", "cpp_ptr_type_get", "cpp_str_type", "cpp_str_flex_ctor",
+];
+
+fn check_known_attributes(statements: &Statements, decl_name: &str, metadata: &Metadata, errors: &mut Vec<Diagnostic>) {
+  for attribute in &metadata.attributes {
+    if !BUILTIN_ATTRIBUTES.contains(&attribute.name.as_str()) && !statements.attribute_declarations.contains_key(&attribute.name) {
+      errors.push(Diagnostic::new(
+        UNKNOWN_ATTRIBUTE,
+        format!("attribute {} used in {decl_name} is unknown, declare it with `attribute \"{}\";`", attribute.name, attribute.name),
+        Some(statements.locate(decl_name, &attribute.span)),
+      ));
+    }
+  }
+}
+
+fn check_attributes(statements: &Statements, errors: &mut Vec<Diagnostic>) {
+  for table in statements.table_declaration.values() {
+    check_known_attributes(statements, &table.name, &table.metadata, errors);
+    for field_type in table.fields.values() {
+      check_known_attributes(statements, &table.name, &field_type.metadata, errors);
+    }
+  }
+  for struct_decl in statements.struct_declaration.values() {
+    check_known_attributes(statements, &struct_decl.name, &struct_decl.metadata, errors);
+    for field_type in struct_decl.fields.values() {
+      check_known_attributes(statements, &struct_decl.name, &field_type.metadata, errors);
+    }
+  }
+  for enum_decl in statements.enum_declarations.values() {
+    check_known_attributes(statements, &enum_decl.name, &enum_decl.metadata, errors);
+  }
+  for union in statements.unions.values() {
+    check_known_attributes(statements, &union.name, &union.metadata, errors);
+  }
+  for rpc_decl in statements.rpc_declarations.values() {
+    for method in rpc_decl.methods.values() {
+      check_known_attributes(statements, &rpc_decl.name, &method.metadata, errors);
+    }
+  }
+}
+
+
+/** Either every field has an `id` or none of them, ids have to cover 0..n without gaps.
+A union field or vector of unions takes two slots, its `id` is the one of the value and `id - 1` is the one of the type */
+fn check_field_ids(statements: &Statements, table: &TableDeclaration, errors: &mut Vec<Diagnostic>) {
+  if !table.fields.values().any(|field| field.metadata.has("id")) {
+    return;
+  }
+
+  let mut slots: HashMap<i64, &str> = HashMap::new();
+  for (field_name, field_type) in table.fields.iter() {
+    let id = match field_type.metadata.get("id") {
+      Some(attribute) => match attribute.value {
+        Some(AttributeValue::Integer(id)) if id >= 0 => id,
+        _ => {
+          errors.push(Diagnostic::new(
+            INVALID_FIELD_ID,
+            format!("id of field {field_name} in {} must be a non-negative integer", table.name),
+            Some(statements.locate(&table.name, &attribute.span)),
+          ));
+          continue;
+        }
+      },
+      None => {
+        errors.push(Diagnostic::new(
+          INVALID_FIELD_ID,
+          format!("field {field_name} in {} has no id, either all fields of a table have ids or none of them", table.name),
+          Some(statements.locate(&table.name, &field_type.span)),
+        ));
+        continue;
+      }
+    };
+
+    let is_union = match &field_type.value_type {
+      ValueType::CompoundType(type_name) => statements.unions.contains_key(type_name),
+      _ => false,
+    };
+    let field_slots = if is_union { vec![id - 1, id] } else { vec![id] };
+
+    for slot in field_slots {
+      let message = if slot < 0 {
+        format!("union field {field_name} in {} takes two slots, its id must be at least 1", table.name)
+      } else if let Some(first) = slots.insert(slot, field_name) {
+        format!("field {field_name} in {} takes slot {slot} already taken by {first}", table.name)
+      } else {
+        continue;
+      };
+
+      errors.push(Diagnostic::new(INVALID_FIELD_ID, message, Some(statements.locate(&table.name, &field_type.span))));
+    }
+  }
+
+  if let Some(missing) = (0..slots.len() as i64).find(|slot| !slots.contains_key(slot)) {
+    errors.push(Diagnostic::new(
+      INVALID_FIELD_ID,
+      format!("field ids of {} must be contiguous, slot {missing} is not taken by any field", table.name),
+      Some(statements.locate(&table.name, &table.span)),
+    ));
+  }
+}
+
+
+/** `required` makes sense only for offsets, scalars are always readable */
+fn check_field_attributes(statements: &Statements, decl_name: &str, field_name: &str, field_type: &Type, errors: &mut Vec<Diagnostic>) {
+  let metadata = &field_type.metadata;
+  let mut report = |message: String, span: &Span| {
+    errors.push(Diagnostic::new(INVALID_ATTRIBUTE, message, Some(statements.locate(decl_name, span))));
   };
 
-  if statements.get_available_commands_union().is_none() {
-    errors.push("There is no root type in the schema".to_owned())
-  } else if let Some(errs) = check_root_union(statements) {
-    errors.push(errs);
+  for attribute in &metadata.attributes {
+    if matches!(attribute.name.as_str(), "deprecated" | "required" | "key") && attribute.value.is_some() {
+      report(format!("attribute {} of field {field_name} in {decl_name} doesn't take a value", attribute.name), &attribute.span);
+    }
+  }
+
+  if metadata.is_deprecated() && metadata.is_required() {
+    report(format!("field {field_name} in {decl_name} can't be both deprecated and required"), &field_type.span);
+  }
+
+  if metadata.is_required() {
+    let is_offset = field_type.is_array || match &field_type.value_type {
+      ValueType::String => true,
+      ValueType::CompoundType(type_name) => !statements.enum_declarations.contains_key(type_name),
+      _ => false,
+    };
+
+    if !is_offset {
+      report(format!("field {field_name} in {decl_name} can't be required, only strings, vectors, tables, structs and unions may be required"), &field_type.span);
+    }
+  }
+}
+
+
+fn check_tables(statements: &Statements, errors: &mut Vec<Diagnostic>) {
+  for table in statements.table_declaration.values() {
+    check_duplicate_fields(statements, &table.name, &table.fields_order, &table.fields, errors);
+
+    for (field_name, field_type) in table.fields.iter() {
+      resolve_field_type(statements, &table.name, field_type, errors);
+      check_default_value(statements, &table.name, field_name, field_type, errors);
+      check_field_attributes(statements, &table.name, field_name, field_type, errors);
+
+      if field_type.fixed_length.is_some() {
+        errors.push(Diagnostic::new(
+          INVALID_FIXED_ARRAY,
+          format!("field {field_name} in {} can't be a fixed length array, they are allowed only in structs, use a vector in tables", table.name),
+          Some(statements.locate(&table.name, &field_type.span)),
+        ));
+      }
+    }
+
+    check_field_ids(statements, table, errors);
+  }
+}
+
+
+/** Structs are fixed size, so they may hold only scalars, enums and other structs */
+fn check_structs(statements: &Statements, errors: &mut Vec<Diagnostic>) {
+  for struct_decl in statements.struct_declaration.values() {
+    check_duplicate_fields(statements, &struct_decl.name, &struct_decl.fields_order, &struct_decl.fields, errors);
+
+    for field_name in &struct_decl.fields_order {
+      let field_type = match struct_decl.fields.get(field_name) {
+        Some(field_type) => field_type,
+        None => continue,
+      };
+
+      let invalid_kind = match (&field_type.value_type, field_type.is_array) {
+        (_, true) => Some("a vector"),
+        (ValueType::String, false) => Some("a string"),
+        (ValueType::CompoundType(_), false) => match resolve_field_type(statements, &struct_decl.name, field_type, errors) {
+          Some(DeclType::Table(_)) => Some("a table"),
+          Some(DeclType::Union(_)) => Some("a union"),
+          _ => None,
+        },
+        _ => None,
+      };
+
+      if field_type.default_value.is_some() {
+        errors.push(Diagnostic::new(
+          INVALID_DEFAULT_VALUE,
+          format!("field {field_name} of struct {} can't have a default value, struct fields are always present", struct_decl.name),
+          Some(statements.locate(&struct_decl.name, &field_type.span)),
+        ));
+      }
+
+      if let Some(attribute) = ["id", "deprecated", "required"].iter().find_map(|name| field_type.metadata.get(name)) {
+        errors.push(Diagnostic::new(
+          INVALID_ATTRIBUTE,
+          format!("field {field_name} of struct {} can't have attribute {}, struct layout is fixed", struct_decl.name, attribute.name),
+          Some(statements.locate(&struct_decl.name, &attribute.span)),
+        ));
+      }
+
+      // flatc stores the length in 16 bits
+      if let Some(length) = field_type.fixed_length {
+        if !(1..=u16::MAX.into()).contains(&length) {
+          errors.push(Diagnostic::new(
+            INVALID_FIXED_ARRAY,
+            format!("length of array {field_name} in struct {} must be between 1 and {}", struct_decl.name, u16::MAX),
+            Some(statements.locate(&struct_decl.name, &field_type.span)),
+          ));
+        }
+      }
+
+      if let Some(invalid_kind) = invalid_kind {
+        errors.push(Diagnostic::new(
+          INVALID_STRUCT_FIELD,
+          format!("field {field_name} of struct {} can't be {invalid_kind}, structs may contain only scalars, enums and structs", struct_decl.name),
+          Some(statements.locate(&struct_decl.name, &field_type.span)),
+        ));
+      }
+    }
+  }
+}
+
+
+fn check_enums(statements: &Statements, errors: &mut Vec<Diagnostic>) {
+  for enum_decl in statements.enum_declarations.values() {
+    let is_array = enum_decl.type_def.is_array || enum_decl.type_def.fixed_length.is_some();
+    let range = match (is_array, integer_range(&enum_decl.type_def.value_type)) {
+      (false, Some(range)) => range,
+      _ => {
+        errors.push(Diagnostic::new(
+          INVALID_ENUM_TYPE,
+          format!("underlying type of enum {} must be an integer type", enum_decl.name),
+          Some(statements.locate(&enum_decl.name, &enum_decl.type_def.span)),
+        ));
+        continue;
+      }
+    };
+
+    check_enum_values(statements, &enum_decl.name, &enum_decl.discriminants(), range, errors);
+  }
+}
+
+
+fn check_unions(statements: &Statements, errors: &mut Vec<Diagnostic>) {
+  for union in statements.unions.values() {
+    check_enum_values(statements, &union.name, &union.discriminants(), (1, u8::MAX.into()), errors);
+
+    for item in &union.items {
+      let message = match statements.resolve_decl_by_name(&item.name) {
+        DeclType::Table(_) => continue,
+        DeclType::Null | DeclType::Rpc(_) => (UNDECLARED_TYPE, format!("type {} used in {} is not declared", item.name, union.name)),
+        _ => (INVALID_UNION_MEMBER, format!("{} can't be a member of union {}, unions may contain only tables", item.name, union.name)),
+      };
+
+      errors.push(Diagnostic::new(message.0, message.1, Some(statements.locate(&union.name, &item.span))));
+    }
+  }
+}
+
+
+fn check_rpc_tables(statements: &Statements, errors: &mut Vec<Diagnostic>) {
+  for rpc_decl in statements.rpc_declarations.values() {
+    for method in rpc_decl.methods.values() {
+      for table in [&method.input, &method.output] {
+        let message = match statements.resolve_decl_by_name(table) {
+          DeclType::Table(_) => continue,
+          DeclType::Null => (UNDECLARED_TYPE, format!("type {table} used in RPC method {}::{} is not declared", rpc_decl.name, method.name)),
+          _ => (INVALID_RPC_TABLE, format!("{} used in RPC method {}::{} must be a table", statements.origin_of(table), rpc_decl.name, method.name)),
+        };
+
+        errors.push(Diagnostic::new(message.0, message.1, Some(statements.locate(&rpc_decl.name, &method.span))));
+      }
+    }
+  }
+}
+
+
+/** Streams end with an empty payload, which is also how a failure looks without an RpcError table.
+Frames continuing a request stream are told apart by their payload type, so its input table can't be shared */
+fn check_rpc_streaming(statements: &Statements, errors: &mut Vec<Diagnostic>) {
+  for rpc_decl in statements.rpc_declarations.values() {
+    for method in rpc_decl.methods.values() {
+      let span = method.metadata.get("streaming").map_or(&method.span, |attribute| &attribute.span);
+      let streaming = match method.streaming() {
+        Some(Streaming::None) => continue,
+        Some(streaming) => streaming,
+        None => {
+          errors.push(Diagnostic::new(
+            INVALID_STREAMING,
+            format!("streaming of RPC method {}::{} must be \"none\", \"server\", \"client\" or \"bidi\"", rpc_decl.name, method.name),
+            Some(statements.locate(&rpc_decl.name, span)),
+          ));
+          continue;
+        }
+      };
+
+      if statements.rpc_error_table().is_none() {
+        let direction = match streaming {
+          Streaming::Server => "server streaming",
+          Streaming::Client => "client streaming",
+          _ => "bidirectional streaming",
+        };
+        errors.push(Diagnostic::new(
+          INVALID_STREAMING,
+          format!(
+            "{direction} RPC method {}::{} needs an {RPC_ERROR} table in the root table payload union, so a failed stream can be told from a finished one",
+            rpc_decl.name, method.name,
+          ),
+          Some(statements.locate(&rpc_decl.name, span)),
+        ));
+      }
+
+      if !method.streams_requests() {
+        continue;
+      }
+      let shared_with = statements.rpc_declarations.values()
+        .flat_map(|other_rpc| other_rpc.methods.values().map(move |other| (other_rpc, other)))
+        .find(|(other_rpc, other)| other.input == method.input && !(other_rpc.name == rpc_decl.name && other.name == method.name));
+      if let Some((other_rpc, other)) = shared_with {
+        errors.push(Diagnostic::new(
+          INVALID_STREAMING,
+          format!(
+            "RPC method {}::{} streams its requests, so its input {} can't be the input of {}::{} too",
+            rpc_decl.name, method.name, method.input, other_rpc.name, other.name,
+          ),
+          Some(statements.locate(&rpc_decl.name, span)),
+        ));
+      }
+    }
+  }
+}
+
+/** The standard error table is `{ code: <integer>; message: string; details: string; }`, details are optional */
+fn check_rpc_error_table(statements: &Statements, errors: &mut Vec<Diagnostic>) {
+  let error_table = match statements.rpc_error_table() {
+    Some(error_table) => error_table,
+    None => return,
   };
 
+  for required in ["code", "message"] {
+    if !error_table.fields.contains_key(required) {
+      errors.push(Diagnostic::new(
+        INVALID_RPC_ERROR_TABLE,
+        format!("{RPC_ERROR} table must have a `{required}` field"),
+        Some(statements.locate(&error_table.name, &error_table.span)),
+      ));
+    }
+  }
+
+  for (field_name, field_type) in error_table.fields.iter() {
+    let is_valid = match field_name.as_str() {
+      "code" => !field_type.is_array && !field_type.is_optional() && integer_range(&field_type.value_type).is_some(),
+      "message" | "details" | "id" => !field_type.is_array && matches!(field_type.value_type, ValueType::String),
+      _ => false,
+    };
+
+    if !is_valid {
+      errors.push(Diagnostic::new(
+        INVALID_RPC_ERROR_TABLE,
+        format!("field {field_name} doesn't fit {RPC_ERROR} table, it may have only `code` of a non optional integer type and `message`, `details` and `id` strings"),
+        Some(statements.locate(&error_table.name, &field_type.span)),
+      ));
+    }
+  }
+}
+
+pub fn type_check(statements: &Statements) -> Option<Vec<Diagnostic>> {
+  let mut errors = vec![];
+
+  check_duplicate_declarations(statements, &mut errors);
+  check_ambiguous_names(statements, &mut errors);
+  check_root_type(statements, &mut errors);
+  check_tables(statements, &mut errors);
+  check_structs(statements, &mut errors);
+  check_enums(statements, &mut errors);
+  check_unions(statements, &mut errors);
+  check_rpc_tables(statements, &mut errors);
+  check_attributes(statements, &mut errors);
+  check_rpc_error_table(statements, &mut errors);
+  check_rpc_streaming(statements, &mut errors);
+  check_root_union(statements, &mut errors);
+
   if errors.is_empty() {
     None
   } else {
-    Some(errors.join("\n"))
+    Some(errors)
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+  use super::*;
+  use crate::parser::parse_fbs_schema;
+
+  const SERVICE: &str = "
+table Req { n: int; }
+table Resp { n: int; }
+table RpcError { code: uint; message: string; details: string; }
+union Payload { Req, Resp, RpcError }
+table Root { id: string; payload: Payload; }
+root_type Root;
+rpc_service Api { Echo(Req): Resp; }
+";
+
+  fn check_schema(schema: &str) -> Vec<Diagnostic> {
+    let statements = parse_fbs_schema(schema, Path::new("test.fbs")).unwrap();
+    type_check(&statements).unwrap_or_default()
+  }
+
+  /** Codes reported for `declarations` added to a valid service */
+  fn codes(declarations: &str) -> Vec<&'static str> {
+    check_schema(&format!("{SERVICE}{declarations}")).iter().map(|diagnostic| diagnostic.code).collect()
+  }
+
+  #[test]
+  fn a_valid_service_passes() {
+    assert_eq!(codes(""), Vec::<&str>::new());
+  }
+
+  #[test]
+  fn undeclared_types() {
+    assert_eq!(codes("table T { user: User; }"), vec![UNDECLARED_TYPE]);
+    assert_eq!(codes("table T { users: [User]; }"), vec![UNDECLARED_TYPE]);
+    assert_eq!(codes("struct S { user: User; }"), vec![UNDECLARED_TYPE]);
+    assert_eq!(codes("union U { User }"), vec![UNDECLARED_TYPE]);
+    assert_eq!(codes("rpc_service Other { Get(User): Resp; }"), vec![UNDECLARED_TYPE, MISSING_IN_ROOT_UNION]);
+  }
+
+  #[test]
+  fn undeclared_types_point_at_the_field() {
+    let diagnostics = check_schema(&format!("{SERVICE}table T {{\n  value: int;\n  user: User;\n}}"));
+    let span = diagnostics[0].span.as_ref().unwrap();
+
+    assert_eq!((span.line, span.column), (SERVICE.lines().count() + 3, 9));
+    assert_eq!(span.line_text, "  user: User;");
+  }
+
+  #[test]
+  fn duplicate_declarations() {
+    assert_eq!(codes("table Req { m: int; }"), vec![DUPLICATE_DECLARATION]);
+    assert_eq!(codes("enum Resp: byte { A }"), vec![DUPLICATE_DECLARATION]);
+  }
+
+  #[test]
+  fn references_resolve_per_namespace() {
+    assert_eq!(codes("namespace a; table T { req: Req; }"), Vec::<&str>::new());
+    assert_eq!(codes("namespace a; table User { id: int; } namespace b; table T { user: a.User; }"), Vec::<&str>::new());
+    assert_eq!(codes("namespace a; table User { id: int; } namespace b; table T { user: User; }"), vec![UNDECLARED_TYPE]);
+    assert_eq!(codes("namespace a; table T { user: b.User; }"), vec![UNDECLARED_TYPE]);
+  }
+
+  #[test]
+  fn used_names_are_unique_across_namespaces() {
+    assert_eq!(codes("namespace a; rpc_service Api { Echo(Req): Resp; }"), vec![AMBIGUOUS_NAME]);
+    assert_eq!(codes("namespace a; table User { id: int; } table User { id: int; }"), vec![DUPLICATE_DECLARATION]);
+  }
+
+  #[test]
+  fn unused_names_may_repeat_across_namespaces() {
+    assert_eq!(codes("namespace a; table Req { n: int; }"), Vec::<&str>::new());
+    assert_eq!(codes("namespace a; table User { id: int; } namespace b; enum User: byte { A }"), Vec::<&str>::new());
+  }
+
+  #[test]
+  fn structs_hold_only_fixed_size_fields() {
+    assert_eq!(codes("struct S { name: string; }"), vec![INVALID_STRUCT_FIELD]);
+    assert_eq!(codes("struct S { values: [int]; }"), vec![INVALID_STRUCT_FIELD]);
+    assert_eq!(codes("struct S { req: Req; }"), vec![INVALID_STRUCT_FIELD]);
+    assert_eq!(codes("struct S { payload: Payload; }"), vec![INVALID_STRUCT_FIELD]);
+    assert_eq!(codes("enum E: byte { A } struct P { x: float; } struct S { e: E; p: P; values: [ubyte:4]; }"), Vec::<&str>::new());
+  }
+
+  #[test]
+  fn duplicate_enum_values() {
+    assert_eq!(codes("enum E: byte { A = 1, B = 1 }"), vec![DUPLICATE_ENUM_VALUE]);
+    assert_eq!(codes("enum E: byte { A = 1, B = 0, C }"), vec![DUPLICATE_ENUM_VALUE]);
+    assert_eq!(codes("enum E: byte { A, A }"), vec![DUPLICATE_ENUM_VALUE]);
+    assert_eq!(codes("union U { Req = 2, Resp = 2 }"), vec![DUPLICATE_ENUM_VALUE]);
+  }
+
+  #[test]
+  fn missing_root_type() {
+    let diagnostics = check_schema("table T { n: int; }");
+
+    assert_eq!(diagnostics.iter().map(|diagnostic| diagnostic.code).collect::<Vec<_>>(), vec![MISSING_ROOT_TYPE]);
+  }
+
+  #[test]
+  fn rpc_tables_must_be_in_the_root_union() {
+    assert_eq!(codes("table Other { n: int; } rpc_service Extra { Get(Other): Resp; }"), vec![MISSING_IN_ROOT_UNION]);
+  }
+
+  #[test]
+  fn duplicate_fields() {
+    assert_eq!(codes("table T { n: int; n: long; }"), vec![DUPLICATE_FIELD]);
+    assert_eq!(codes("struct S { n: int; n: long; }"), vec![DUPLICATE_FIELD]);
+  }
+
+  #[test]
+  fn rpc_tables_must_be_tables() {
+    assert_eq!(codes("struct S { n: int; } rpc_service Extra { Get(S): Resp; }"), vec![INVALID_RPC_TABLE, MISSING_IN_ROOT_UNION]);
+  }
+
+  #[test]
+  fn the_root_type_must_be_a_table_with_a_payload_union() {
+    let codes = |schema| check_schema(schema).iter().map(|diagnostic| diagnostic.code).collect::<Vec<_>>();
+
+    assert_eq!(codes("struct Root { n: int; } root_type Root;"), vec![INVALID_ROOT_TYPE]);
+    assert_eq!(codes("table Root { id: string; payload: int; } root_type Root;"), vec![INVALID_ROOT_TYPE]);
+  }
+
+  #[test]
+  fn enums_have_an_integer_type() {
+    assert_eq!(codes("enum E: string { A }"), vec![INVALID_ENUM_TYPE]);
+    assert_eq!(codes("enum E: float { A }"), vec![INVALID_ENUM_TYPE]);
+    assert_eq!(codes("enum E: [int] { A }"), vec![INVALID_ENUM_TYPE]);
+  }
+
+  #[test]
+  fn unions_hold_only_tables() {
+    assert_eq!(codes("struct S { n: int; } union U { Req, S }"), vec![INVALID_UNION_MEMBER]);
+    assert_eq!(codes("enum E: byte { A } union U { E }"), vec![INVALID_UNION_MEMBER]);
+  }
+
+  #[test]
+  fn enum_values_fit_their_type() {
+    assert_eq!(codes("enum E: ubyte { A = 255 }"), Vec::<&str>::new());
+    assert_eq!(codes("enum E: ubyte { A = 256 }"), vec![ENUM_VALUE_OUT_OF_RANGE]);
+    assert_eq!(codes("enum E: ubyte { A = 255, B }"), vec![ENUM_VALUE_OUT_OF_RANGE]);
+    assert_eq!(codes("enum E: byte { A = -129 }"), vec![ENUM_VALUE_OUT_OF_RANGE]);
+    assert_eq!(codes("enum E: uint { A = -1 }"), vec![ENUM_VALUE_OUT_OF_RANGE]);
+    assert_eq!(codes("union U { Req = 0 }"), vec![ENUM_VALUE_OUT_OF_RANGE]);
+    assert_eq!(codes("union U { Req = 255, Resp }"), vec![ENUM_VALUE_OUT_OF_RANGE]);
+    assert_eq!(codes("enum E: long { A = -9223372036854775808, B = 9223372036854775807 }"), Vec::<&str>::new());
+    assert_eq!(codes("enum E: long { A = 9223372036854775807, B }"), vec![ENUM_VALUE_OUT_OF_RANGE]);
+    assert_eq!(codes("enum E: ulong { A = 9223372036854775807, B }"), vec![ENUM_VALUE_OUT_OF_RANGE]);
+  }
+
+  #[test]
+  fn defaults_fit_their_field() {
+    assert_eq!(codes("enum E: byte { A, B } table T { a: int = 5; b: bool = true; c: float = 1; d: E = B; e: E = 1; f: long = null; }"), Vec::<&str>::new());
+    assert_eq!(codes("table T { name: string = 5; }"), vec![INVALID_DEFAULT_VALUE]);
+    assert_eq!(codes("table T { value: ubyte = 256; }"), vec![INVALID_DEFAULT_VALUE]);
+    assert_eq!(codes("table T { value: bool = 1; }"), vec![INVALID_DEFAULT_VALUE]);
+    assert_eq!(codes("table T { values: [int] = 1; }"), vec![INVALID_DEFAULT_VALUE]);
+    assert_eq!(codes("enum E: byte { A } table T { e: E = C; }"), vec![INVALID_DEFAULT_VALUE]);
+    assert_eq!(codes("table T { req: Req = null; }"), vec![INVALID_DEFAULT_VALUE]);
+    assert_eq!(codes("struct S { n: int = 1; }"), vec![INVALID_DEFAULT_VALUE]);
+  }
+
+  #[test]
+  fn attributes_are_builtin_or_declared() {
+    assert_eq!(codes("table T { n: int (priority: 1); }"), vec![UNKNOWN_ATTRIBUTE]);
+    assert_eq!(codes("table T (audited) { n: int; }"), vec![UNKNOWN_ATTRIBUTE]);
+    assert_eq!(codes("attribute \"priority\"; table T { n: int (priority: 1, deprecated); }"), Vec::<&str>::new());
+  }
+
+  #[test]
+  fn field_ids_cover_every_slot_once() {
+    assert_eq!(codes("table T { a: int (id: 1); b: int (id: 0); }"), Vec::<&str>::new());
+    assert_eq!(codes("table T { a: int (id: 0); b: int; }"), vec![INVALID_FIELD_ID]);
+    assert_eq!(codes("table T { a: int (id: 0); b: int (id: 0); }"), vec![INVALID_FIELD_ID]);
+    assert_eq!(codes("table T { a: int (id: 0); b: int (id: 2); }"), vec![INVALID_FIELD_ID]);
+    assert_eq!(codes("table T { a: int (id: -1); }"), vec![INVALID_FIELD_ID]);
+    assert_eq!(codes("table T { a: int (id: 0); p: Payload (id: 2); }"), Vec::<&str>::new());
+    assert_eq!(codes("table T { a: int (id: 0); p: Payload (id: 1); }"), vec![INVALID_FIELD_ID]);
+    assert_eq!(codes("table T { p: Payload (id: 0); }"), vec![INVALID_FIELD_ID]);
+  }
+
+  #[test]
+  fn field_attributes_fit_the_field() {
+    assert_eq!(codes("table T { n: int (required); }"), vec![INVALID_ATTRIBUTE]);
+    assert_eq!(codes("table T { name: string (required, deprecated); }"), vec![INVALID_ATTRIBUTE]);
+    assert_eq!(codes("table T { name: string (required: true); }"), vec![INVALID_ATTRIBUTE]);
+    assert_eq!(codes("struct S { n: int (deprecated); }"), vec![INVALID_ATTRIBUTE]);
+    assert_eq!(codes("table T { name: string (required); req: Req (required); }"), Vec::<&str>::new());
+  }
+
+  #[test]
+  fn the_rpc_error_table_has_a_fixed_shape() {
+    let codes = |error_table: &str| {
+      let schema = SERVICE.replace("table RpcError { code: uint; message: string; details: string; }", error_table);
+      check_schema(&schema).iter().map(|diagnostic| diagnostic.code).collect::<Vec<_>>()
+    };
+
+    assert_eq!(codes("table RpcError { code: int; message: string; }"), Vec::<&str>::new());
+    assert_eq!(codes("table RpcError { message: string; }"), vec![INVALID_RPC_ERROR_TABLE]);
+    assert_eq!(codes("table RpcError { code: string; message: string; }"), vec![INVALID_RPC_ERROR_TABLE]);
+    assert_eq!(codes("table RpcError { code: int = null; message: string; }"), vec![INVALID_RPC_ERROR_TABLE]);
+    assert_eq!(codes("table RpcError { code: int; message: string; retry: bool; }"), vec![INVALID_RPC_ERROR_TABLE]);
+  }
+
+  #[test]
+  fn fixed_arrays_belong_in_structs() {
+    assert_eq!(codes("table T { values: [ubyte:4]; }"), vec![INVALID_FIXED_ARRAY]);
+    assert_eq!(codes("struct S { values: [ubyte:0]; }"), vec![INVALID_FIXED_ARRAY]);
+    assert_eq!(codes("struct S { values: [ubyte:65536]; }"), vec![INVALID_FIXED_ARRAY]);
+  }
+
+  #[test]
+  fn streaming_methods() {
+    let codes = |methods: &str, with_error_table: bool| {
+      let mut schema = SERVICE.replace("rpc_service Api { Echo(Req): Resp; }", methods);
+      if !with_error_table {
+        schema = schema.replace("union Payload { Req, Resp, RpcError }", "union Payload { Req, Resp }");
+      }
+      check_schema(&schema).iter().map(|diagnostic| diagnostic.code).collect::<Vec<_>>()
+    };
+
+    for streaming in ["none", "server", "client", "bidi"] {
+      assert_eq!(codes(&format!("rpc_service Api {{ Echo(Req): Resp (streaming: \"{streaming}\"); }}"), true), Vec::<&str>::new());
+    }
+    assert_eq!(codes("rpc_service Api { Echo(Req): Resp (streaming: \"both\"); }", true), vec![INVALID_STREAMING]);
+    assert_eq!(codes("rpc_service Api { Echo(Req): Resp (streaming: 1); }", true), vec![INVALID_STREAMING]);
+    assert_eq!(codes("rpc_service Api { Echo(Req): Resp (streaming: \"server\"); }", false), vec![INVALID_STREAMING]);
+    assert_eq!(codes("rpc_service Api { Echo(Req): Resp; }", false), Vec::<&str>::new());
+    assert_eq!(codes("rpc_service Api { Echo(Req): Resp (streaming: \"client\"); Get(Req): Resp; }", true), vec![INVALID_STREAMING]);
+    assert_eq!(codes("rpc_service Api { Echo(Req): Resp (streaming: \"server\"); Get(Req): Resp; }", true), Vec::<&str>::new());
+  }
+
+  #[test]
+  fn every_problem_is_reported_at_once() {
+    assert_eq!(
+      codes("table T { user: User; n: int; n: int; } struct S { name: string; } enum E: byte { A = 1, B = 1 }"),
+      vec![DUPLICATE_FIELD, UNDECLARED_TYPE, INVALID_STRUCT_FIELD, DUPLICATE_ENUM_VALUE],
+    );
   }
 }
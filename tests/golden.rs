@@ -0,0 +1,97 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use flat_rpc::{Config, Derive, Int64Repr, Lang, Side};
+
+
+/** Generated code is compared against the files checked in under tests/golden,
+`UPDATE_GOLDEN=1 cargo test --test golden` rewrites them for the diff to be reviewed */
+fn check_golden(golden: &Path, code: &str) {
+  if std::env::var_os("UPDATE_GOLDEN").is_some() {
+    fs::write(golden, code).unwrap();
+    return;
+  }
+  let expected = match fs::read_to_string(golden) {
+    Ok(expected) => expected,
+    Err(e) => panic!("can't read {}: {e}, UPDATE_GOLDEN=1 writes it", golden.display()),
+  };
+  if expected != code {
+    let line = expected.lines().zip(code.lines()).take_while(|(expected, line)| expected == line).count() + 1;
+    panic!("{} differs from the generated code from line {line} on, rerun with UPDATE_GOLDEN=1 and review the diff", golden.display());
+  }
+}
+
+fn path(relative: &str) -> PathBuf {
+  Path::new(env!("CARGO_MANIFEST_DIR")).join(relative)
+}
+
+fn fixture(lang: Lang, side: Side) -> Config {
+  Config::new().input(path("tests/fixtures/golden.fbs")).lang(lang).side(side)
+}
+
+fn generate(config: &Config) -> String {
+  config.generate_code().unwrap_or_else(|e| panic!("{e}"))
+}
+
+
+#[test]
+fn rust_server_matches_its_golden() {
+  let derives = [Derive::Debug, Derive::Clone, Derive::PartialEq, Derive::Serialize, Derive::Deserialize];
+  let code = generate(&fixture(Lang::Rust, Side::Server).derives(derives));
+
+  // fixed arrays longer than 32 have no serde impls of their own
+  assert!(code.contains("pub bytes: [u8; 64]") && code.contains("serialize_with = \"fixed_array::serialize\""));
+  assert!(code.contains("pub hp: Option<i32>") && code.contains("pub big: Option<i64>"));
+  check_golden(&path("tests/golden/server.rs"), &code);
+}
+
+#[test]
+fn rust_client_matches_its_golden() {
+  let code = generate(&fixture(Lang::Rust, Side::Client));
+
+  assert!(code.contains("impl TryFrom<Draw<'_>> for DrawContent") && code.contains("impl Default for DrawContent"));
+  assert!(code.contains("ShapeContent::from_vectors("));
+  check_golden(&path("tests/golden/client.rs"), &code);
+}
+
+#[test]
+fn ts_server_matches_its_golden() {
+  let code = generate(&fixture(Lang::Ts, Side::Server));
+
+  assert!(code.contains("required field name is absent in Draw"));
+  check_golden(&path("tests/golden/server.ts"), &code);
+}
+
+#[test]
+fn ts_client_matches_its_golden() {
+  let code = generate(&fixture(Lang::Ts, Side::Client));
+
+  assert!(code.contains("class FrameQueue<T>") && code.contains("size?: bigint"));
+  check_golden(&path("tests/golden/client.ts"), &code);
+}
+
+#[test]
+fn ts_client_with_int64_as_strings_matches_its_golden() {
+  let code = generate(&fixture(Lang::Ts, Side::Client).ts_int64(Int64Repr::String));
+
+  assert!(code.contains("size?: string"));
+  check_golden(&path("tests/golden/client.int64-string.ts"), &code);
+}
+
+#[test]
+fn generating_twice_gives_the_same_code() {
+  for lang in [Lang::Rust, Lang::Ts] {
+    for side in [Side::Server, Side::Client] {
+      let config = fixture(lang, side);
+      assert_eq!(generate(&config), generate(&config), "{lang:?} {side:?} code depends on more than the schema");
+    }
+  }
+}
+
+/** The runtime's transport tests compile and run what flat-rpc makes of runtime/tests/common/rpc.fbs */
+#[test]
+fn runtime_test_server_and_client_are_current() {
+  for (side, generated) in [(Side::Server, "rpc_server.rs"), (Side::Client, "rpc_client.rs")] {
+    let config = Config::new().input(path("runtime/tests/common/rpc.fbs")).lang(Lang::Rust).side(side);
+    check_golden(&path("runtime/tests/common").join(generated), &generate(&config));
+  }
+}
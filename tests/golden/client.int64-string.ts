@@ -0,0 +1,699 @@
+import * as fb from "flatbuffers";
+const {Builder} = fb;
+import { Circle, CircleT, Square, SquareT, Draw, DrawT, Drawn, DrawnT, Replay, ReplayT, Stroke, StrokeT, Cursor, CursorT, RpcError as RpcErrorTable, RpcErrorT, Envelope, EnvelopeT, Point, PointT, Hash, HashT, Span, SpanT, Shape, Payload, Mode  } from "./schema_generated";
+
+
+export const ModeValues = {
+Fast: 0,
+Slow: 1,
+} as const satisfies Record<keyof typeof Mode, number>;
+export const ShapeValues = {
+NONE: 0,
+Circle: 1,
+Square: 2,
+} as const satisfies Record<keyof typeof Shape, number>;
+export const PayloadValues = {
+NONE: 0,
+Draw: 1,
+Drawn: 2,
+Replay: 3,
+Stroke: 4,
+Cursor: 5,
+RpcError: 6,
+} as const satisfies Record<keyof typeof Payload, number>;
+export type CircleContent = {
+	r?: number,
+	id?: string
+}
+
+
+const circleContentIntoProtocolClass = (content: CircleContent): CircleT => {
+return new CircleT(content.r ?? 1)
+}
+export const circleContentFromProtocolClass = (value: CircleT): CircleContent => ({
+r: value.r,
+});
+
+export type SquareContent = {
+	side: number,
+	id?: string
+}
+
+
+const squareContentIntoProtocolClass = (content: SquareContent): SquareT => {
+return new SquareT(content.side)
+}
+export const squareContentFromProtocolClass = (value: SquareT): SquareContent => ({
+side: value.side,
+});
+
+export type DrawContent = {
+	name: string,
+	mode?: Mode,
+	modes: Array<Mode>,
+	hash: HashContent,
+	at: SpanContent,
+	shape: ShapeContent | null,
+	shapes: Array<ShapeContent>,
+	size?: string,
+	sizes: Array<string>,
+	hp?: number | null,
+	big?: string | null,
+	ok?: boolean,
+	points: Array<PointContent>,
+	id?: string
+}
+
+
+const drawContentIntoProtocolClass = (content: DrawContent): DrawT => {
+return new DrawT(content.name, content.mode ?? Mode.Slow, content.modes, hashContentIntoProtocolClass(content.hash), spanContentIntoProtocolClass(content.at), content.shape?.type ?? Shape.NONE, content.shape ? shapeContentIntoProtocolClass(content.shape) : null, content.shapes.map((item) => item.type), content.shapes.map(shapeContentIntoProtocolClass), BigInt(content.size ?? "5"), content.sizes.map(BigInt), content.hp ?? null, content.big == null ? null : BigInt(content.big), content.ok ?? true, content.points.map(pointContentIntoProtocolClass))
+}
+export const drawContentFromProtocolClass = (value: DrawT): DrawContent => ({
+name: value.name ?? "",
+mode: value.mode,
+modes: value.modes,
+hash: hashContentFromProtocolClass(value.hash ?? new HashT()),
+at: spanContentFromProtocolClass(value.at ?? new SpanT()),
+shape: shapeContentFromProtocolClass(value.shapeType, value.shape),
+shapes: value.shapes.flatMap((item, index) => shapeContentFromProtocolClass(value.shapesType[index], item) ?? []),
+size: value.size.toString(),
+sizes: value.sizes.map(String),
+hp: value.hp,
+big: value.big == null ? null : value.big.toString(),
+ok: value.ok,
+points: value.points.map(pointContentFromProtocolClass),
+});
+const checkDrawRequiredFields = (table: Draw): void => {
+if (table.bb!.__offset(table.bb_pos, 4) === 0) throw new Error("required field name is absent in Draw");
+}
+export type DrawnContent = {
+	count: number,
+	last: ShapeContent | null,
+	id?: string
+}
+
+
+const drawnContentIntoProtocolClass = (content: DrawnContent): DrawnT => {
+return new DrawnT(content.count, content.last?.type ?? Shape.NONE, content.last ? shapeContentIntoProtocolClass(content.last) : null)
+}
+export const drawnContentFromProtocolClass = (value: DrawnT): DrawnContent => ({
+count: value.count,
+last: shapeContentFromProtocolClass(value.lastType, value.last),
+});
+
+export type ReplayContent = {
+	from: string,
+	id?: string
+}
+
+
+const replayContentIntoProtocolClass = (content: ReplayContent): ReplayT => {
+return new ReplayT(BigInt(content.from))
+}
+export const replayContentFromProtocolClass = (value: ReplayT): ReplayContent => ({
+from: value.from.toString(),
+});
+
+export type StrokeContent = {
+	at: PointContent,
+	id?: string
+}
+
+
+const strokeContentIntoProtocolClass = (content: StrokeContent): StrokeT => {
+return new StrokeT(pointContentIntoProtocolClass(content.at))
+}
+export const strokeContentFromProtocolClass = (value: StrokeT): StrokeContent => ({
+at: pointContentFromProtocolClass(value.at ?? new PointT()),
+});
+
+export type CursorContent = {
+	at: PointContent,
+	id?: string
+}
+
+
+const cursorContentIntoProtocolClass = (content: CursorContent): CursorT => {
+return new CursorT(pointContentIntoProtocolClass(content.at))
+}
+export const cursorContentFromProtocolClass = (value: CursorT): CursorContent => ({
+at: pointContentFromProtocolClass(value.at ?? new PointT()),
+});
+
+export type RpcErrorContent = {
+	code: number,
+	message: string,
+	details: string,
+	id?: string
+}
+
+
+const rpcErrorContentIntoProtocolClass = (content: RpcErrorContent): RpcErrorT => {
+return new RpcErrorT(content.code, content.message, content.details)
+}
+export const rpcErrorContentFromProtocolClass = (value: RpcErrorT): RpcErrorContent => ({
+code: value.code,
+message: value.message ?? "",
+details: value.details ?? "",
+});
+
+export type EnvelopeContent = {
+	id?: string,
+	payload: PayloadContent | null,
+}
+
+
+const envelopeContentIntoProtocolClass = (content: EnvelopeContent): EnvelopeT => {
+return new EnvelopeT(content.id, content.payload?.type ?? Payload.NONE, content.payload ? payloadContentIntoProtocolClass(content.payload) : null)
+}
+export const envelopeContentFromProtocolClass = (value: EnvelopeT): EnvelopeContent => ({
+id: value.id ?? "",
+payload: payloadContentFromProtocolClass(value.payloadType, value.payload),
+});
+
+export type PointContent = {
+x: number;
+y: number;
+};
+export const pointContentIntoProtocolClass = (content: PointContent): PointT => {
+return new PointT(content.x,
+content.y,)
+};
+export const pointContentFromProtocolClass = (value: PointT): PointContent => ({
+x: value.x,
+y: value.y,
+});
+export type HashContent = {
+bytes: Uint8Array;
+small: Uint8Array;
+};
+export const hashContentIntoProtocolClass = (content: HashContent): HashT => {
+return new HashT(Array.from(content.bytes),
+Array.from(content.small),)
+};
+export const hashContentFromProtocolClass = (value: HashT): HashContent => ({
+bytes: Uint8Array.from(value.bytes),
+small: Uint8Array.from(value.small),
+});
+export type SpanContent = {
+start: string;
+len: number;
+};
+export const spanContentIntoProtocolClass = (content: SpanContent): SpanT => {
+return new SpanT(BigInt(content.start),
+content.len,)
+};
+export const spanContentFromProtocolClass = (value: SpanT): SpanContent => ({
+start: value.start.toString(),
+len: value.len,
+});
+export type ShapeContent = 
+	| { type: Shape.Circle, value: CircleContent }
+	| { type: Shape.Square, value: SquareContent }
+
+
+
+const shapeContentIntoProtocolClass = (content: ShapeContent): CircleT | SquareT => {
+switch (content.type) {
+case Shape.Circle: return circleContentIntoProtocolClass(content.value);
+case Shape.Square: return squareContentIntoProtocolClass(content.value);
+}
+}
+export const shapeContentFromProtocolClass = (type: Shape, value: CircleT | SquareT | null): ShapeContent | null => {
+if (value === null) return null;
+switch (type) {
+case Shape.Circle: return { type: Shape.Circle, value: circleContentFromProtocolClass(value as CircleT) };
+case Shape.Square: return { type: Shape.Square, value: squareContentFromProtocolClass(value as SquareT) };
+default: return null;
+}
+}
+export type PayloadContent = 
+	| { type: Payload.Draw, value: DrawContent }
+	| { type: Payload.Drawn, value: DrawnContent }
+	| { type: Payload.Replay, value: ReplayContent }
+	| { type: Payload.Stroke, value: StrokeContent }
+	| { type: Payload.Cursor, value: CursorContent }
+	| { type: Payload.RpcError, value: RpcErrorContent }
+
+
+
+const payloadContentIntoProtocolClass = (content: PayloadContent): DrawT | DrawnT | ReplayT | StrokeT | CursorT | RpcErrorT => {
+switch (content.type) {
+case Payload.Draw: return drawContentIntoProtocolClass(content.value);
+case Payload.Drawn: return drawnContentIntoProtocolClass(content.value);
+case Payload.Replay: return replayContentIntoProtocolClass(content.value);
+case Payload.Stroke: return strokeContentIntoProtocolClass(content.value);
+case Payload.Cursor: return cursorContentIntoProtocolClass(content.value);
+case Payload.RpcError: return rpcErrorContentIntoProtocolClass(content.value);
+}
+}
+export const payloadContentFromProtocolClass = (type: Payload, value: DrawT | DrawnT | ReplayT | StrokeT | CursorT | RpcErrorT | null): PayloadContent | null => {
+if (value === null) return null;
+switch (type) {
+case Payload.Draw: return { type: Payload.Draw, value: drawContentFromProtocolClass(value as DrawT) };
+case Payload.Drawn: return { type: Payload.Drawn, value: drawnContentFromProtocolClass(value as DrawnT) };
+case Payload.Replay: return { type: Payload.Replay, value: replayContentFromProtocolClass(value as ReplayT) };
+case Payload.Stroke: return { type: Payload.Stroke, value: strokeContentFromProtocolClass(value as StrokeT) };
+case Payload.Cursor: return { type: Payload.Cursor, value: cursorContentFromProtocolClass(value as CursorT) };
+case Payload.RpcError: return { type: Payload.RpcError, value: rpcErrorContentFromProtocolClass(value as RpcErrorT) };
+default: return null;
+}
+}
+export class RpcError extends Error {
+	static readonly BAD_REQUEST = 400;
+	static readonly CANCELLED = 499;
+	static readonly INTERNAL = 500;
+	static readonly NOT_IMPLEMENTED = 501;
+	static readonly BAD_RESPONSE = 502;
+	static readonly UNAVAILABLE = 503;
+
+	constructor(public readonly code: number, message: string, public readonly details: string = "") {
+		super(message);
+		this.name = "RpcError";
+	}
+}
+
+export type Transport = {
+	sendMessage: (msg: Uint8Array, id: string, method: string) => Promise<Envelope>
+	openStream?: (msg: Uint8Array, id: string, method: string) => AsyncIterable<Envelope>
+	openCall?: (msg: Uint8Array, id: string, method: string) => Promise<StreamingCall>
+}
+
+/** A call opened with its first request, the rest go through `send` and end with an end of stream marker or an error frame */
+export type StreamingCall = {
+	send: (msg: Uint8Array) => Promise<void>
+	responses: AsyncIterable<Envelope>
+}
+
+class FrameQueue<T> implements AsyncIterable<T> {
+	static readonly LIMIT = 64;
+
+	private readonly frames: T[] = [];
+	private error: Error | undefined;
+	private stopped = false;
+	private wake = () => {};
+
+	/** `false` once the consumer stopped or fell behind, the frame is dropped then */
+	push(frame: T): boolean {
+		if (this.stopped || this.error !== undefined) {
+			return false;
+		}
+		if (this.frames.length >= FrameQueue.LIMIT) {
+			this.fail(new RpcError(RpcError.UNAVAILABLE, `more than ${FrameQueue.LIMIT} frames are waiting, the consumer fell behind`));
+			return false;
+		}
+		this.frames.push(frame);
+		this.wake();
+		return true;
+	}
+
+	/** The consumer gets `error` next, frames still waiting are dropped */
+	fail(error: Error): void {
+		this.error ??= error;
+		this.frames.length = 0;
+		this.wake();
+	}
+
+	async *[Symbol.asyncIterator](): AsyncIterator<T> {
+		try {
+			while (true) {
+				const frame = this.frames.shift();
+				if (frame !== undefined) {
+					yield frame;
+					continue;
+				}
+				if (this.error !== undefined) {
+					throw this.error;
+				}
+				await new Promise<void>((resolve) => this.wake = resolve);
+			}
+		} finally {
+			this.stopped = true;
+			this.frames.length = 0;
+		}
+	}
+}
+
+export class WebSocketTransport implements Transport {
+	/** Bytes the socket may buffer before sending requests of a stream waits, browsers would buffer without limit */
+	static readonly HIGH_WATER_MARK = 1 << 20;
+
+	// `receive` tells whether the request keeps waiting for more responses
+	private readonly pending = new Map<string, { receive: (response: Envelope) => boolean, reject: (error: Error) => void }>();
+	private readonly opened: Promise<void>;
+
+	constructor(private readonly socket: WebSocket) {
+		socket.binaryType = "arraybuffer";
+		this.opened = new Promise((resolve, reject) => {
+			if (socket.readyState === WebSocket.OPEN) {
+				resolve();
+				return;
+			}
+			socket.addEventListener("open", () => resolve(), { once: true });
+			socket.addEventListener("close", () => reject(new RpcError(RpcError.UNAVAILABLE, "WebSocket closed before opening")), { once: true });
+		});
+		// rejections surface from sendMessage, openStream and openCall
+		this.opened.catch(() => {});
+
+		socket.addEventListener("message", (event: MessageEvent) => this.onMessage(event));
+		socket.addEventListener("close", () => this.rejectAll("WebSocket closed"));
+		socket.addEventListener("error", () => this.rejectAll("WebSocket failed"));
+	}
+
+	static connect(url: string | URL, protocols?: string | string[]): WebSocketTransport {
+		return new WebSocketTransport(new WebSocket(url, protocols));
+	}
+
+	async sendMessage(msg: Uint8Array, id: string): Promise<Envelope> {
+		await this.opened;
+		this.checkCanSend(id);
+
+		return new Promise((resolve, reject) => {
+			this.pending.set(id, {
+				receive: (response) => {
+					resolve(response);
+					return false;
+				},
+				reject,
+			});
+			this.socket.send(msg);
+		});
+	}
+
+	async *openStream(msg: Uint8Array, id: string): AsyncIterable<Envelope> {
+		const responses = await this.open(msg, id);
+		try {
+			yield* responses;
+		} finally {
+			this.pending.delete(id);
+		}
+	}
+
+	async openCall(msg: Uint8Array, id: string): Promise<StreamingCall> {
+		const responses = await this.open(msg, id);
+		const pending = this.pending;
+		return {
+			send: (msg) => this.sendWhenDrained(msg),
+			responses: (async function* () {
+				try {
+					yield* responses;
+				} finally {
+					pending.delete(id);
+				}
+			})(),
+		};
+	}
+
+	close(): void {
+		this.socket.close();
+	}
+
+	private async open(msg: Uint8Array, id: string): Promise<FrameQueue<Envelope>> {
+		await this.opened;
+		this.checkCanSend(id);
+
+		const responses = new FrameQueue<Envelope>();
+		this.pending.set(id, {
+			receive: (response) => responses.push(response),
+			reject: (error) => responses.fail(error),
+		});
+		this.socket.send(msg);
+		return responses;
+	}
+
+	private async sendWhenDrained(msg: Uint8Array): Promise<void> {
+		while (this.socket.readyState === WebSocket.OPEN && this.socket.bufferedAmount > WebSocketTransport.HIGH_WATER_MARK) {
+			await new Promise((resolve) => setTimeout(resolve, 10));
+		}
+		if (this.socket.readyState !== WebSocket.OPEN) {
+			throw new RpcError(RpcError.UNAVAILABLE, "WebSocket is not open");
+		}
+		this.socket.send(msg);
+	}
+
+	private checkCanSend(id: string): void {
+		if (this.socket.readyState !== WebSocket.OPEN) {
+			throw new RpcError(RpcError.UNAVAILABLE, "WebSocket is not open");
+		}
+		if (this.pending.has(id)) {
+			throw new RpcError(RpcError.BAD_REQUEST, `request ${id} is already in flight`);
+		}
+	}
+
+	private onMessage(event: MessageEvent): void {
+		if (!(event.data instanceof ArrayBuffer)) {
+			return;
+		}
+		const response = Envelope.getRootAsEnvelope(new fb.ByteBuffer(new Uint8Array(event.data)));
+		const id = response.id();
+		// responses nobody waits for anymore, or without an id, are dropped
+		const request = id == null ? undefined : this.pending.get(id);
+		if (id == null || request === undefined) {
+			return;
+		}
+		if (!request.receive(response)) {
+			this.pending.delete(id);
+		}
+	}
+
+	private rejectAll(message: string): void {
+		const error = new RpcError(RpcError.UNAVAILABLE, message);
+		for (const { reject } of this.pending.values()) {
+			reject(error);
+		}
+		this.pending.clear();
+	}
+}
+
+export class FetchTransport implements Transport {
+	static readonly CONTENT_TYPE = "application/x-flatbuffers";
+
+	constructor(private readonly baseUrl: string, private readonly init: RequestInit = {}) {}
+
+	async sendMessage(msg: Uint8Array, id: string, method: string): Promise<Envelope> {
+		const headers = new Headers(this.init.headers);
+		headers.set("content-type", FetchTransport.CONTENT_TYPE);
+
+		let response: Response;
+		try {
+			response = await fetch(`${this.baseUrl.replace(/\/+$/, "")}/${method}`, { ...this.init, method: "POST", headers, body: msg });
+		} catch (e) {
+			throw new RpcError(RpcError.UNAVAILABLE, `request ${id} failed: ${e}`);
+		}
+		if (!response.ok) {
+			throw new RpcError(response.status, `request ${id} failed with HTTP ${response.status}`, await response.text());
+		}
+
+		const buffer = new Uint8Array(await response.arrayBuffer());
+		return Envelope.getRootAsEnvelope(new fb.ByteBuffer(buffer));
+	}
+}
+
+const errorFrame = (id: string, error: unknown): Uint8Array => {
+const rpcError = error instanceof RpcError ? error : new RpcError(RpcError.INTERNAL, String(error));
+const builder = new Builder();
+const payloadOffset = rpcErrorContentIntoProtocolClass({ code: rpcError.code, message: rpcError.message, details: rpcError.details }).pack(builder);
+const idOffset = builder.createString(id);
+const responseRoot = Envelope.createEnvelope(builder, idOffset, Payload.RpcError, payloadOffset);
+builder.finish(responseRoot);
+return builder.asUint8Array();
+}
+const endOfStreamFrame = (id: string): Uint8Array => {
+const builder = new Builder();
+const idOffset = builder.createString(id);
+const responseRoot = Envelope.createEnvelope(builder, idOffset, Payload.NONE, 0);
+builder.finish(responseRoot);
+return builder.asUint8Array();
+}
+const startCall = async <T extends { id?: string }>(
+	transport: Transport,
+	method: string,
+	requests: AsyncIterable<T> | Iterable<T>,
+	encode: (content: T, id: string) => Uint8Array,
+): Promise<{ id: string, responses: AsyncIterable<Envelope> }> => {
+	if (transport.openCall === undefined) {
+		throw new RpcError(RpcError.NOT_IMPLEMENTED, "transport can't stream requests");
+	}
+	const contents = (async function* () {
+		yield* requests;
+	})();
+	const first = await contents.next();
+	if (first.done) {
+		throw new RpcError(RpcError.BAD_REQUEST, `${method} is opened with its first request, got none`);
+	}
+	const id = first.value.id || globalThis.crypto.randomUUID();
+	const call = await transport.openCall(encode(first.value, id), id, method);
+
+	// stops once the responses aren't read anymore, the server has answered then
+	let answered = false;
+	const sending = (async () => {
+		try {
+			for (let next = await contents.next(); !next.done && !answered; next = await contents.next()) {
+				await call.send(encode(next.value, id));
+			}
+		} catch (error) {
+			await call.send(errorFrame(id, error instanceof RpcError ? error : new RpcError(RpcError.CANCELLED, String(error)))).catch(() => {});
+			throw error;
+		}
+		await contents.return(undefined);
+		await call.send(endOfStreamFrame(id));
+	})();
+	const failed = new Promise<never>((_, reject) => sending.catch(reject));
+	failed.catch(() => {});
+
+	const responses = async function* () {
+		const iterator = call.responses[Symbol.asyncIterator]();
+		try {
+			while (true) {
+				const next = await Promise.race([iterator.next(), failed]);
+				if (next.done) {
+					return;
+				}
+				yield next.value;
+			}
+		} finally {
+			answered = true;
+			await iterator.return?.();
+		}
+	};
+	return { id, responses: responses() };
+}
+
+export const canvasDraw = async (transport: Transport, content: DrawContent): Promise<DrawnContent> => {
+const builder = new Builder();
+let protocolPackage = drawContentIntoProtocolClass(content);
+let payloadOffset = protocolPackage.pack(builder);
+let id = content.id || globalThis.crypto.randomUUID();
+const idOffset = builder.createString(id);
+const root = Envelope.createEnvelope(builder, idOffset, Payload.Draw, payloadOffset);
+builder.finish(root)
+const response: Envelope = await transport.sendMessage(builder.asUint8Array(), id, "Canvas/Draw");
+switch (response.payloadType()) {
+case Payload.Drawn:
+break;
+case Payload.RpcError: {
+const error = new RpcErrorTable();
+response.payload(error);
+throw new RpcError(Number(error.code()), error.message() ?? "", error.details() ?? "");
+}
+default:
+throw new RpcError(RpcError.BAD_RESPONSE, `response payload must be Drawn, got ${Payload[response.payloadType()]}`);
+}
+const responseData = new Drawn();
+response.payload(responseData);
+return {
+        ...drawnContentFromProtocolClass(responseData.unpack()),
+        id
+      }
+}
+export async function* canvasReplay(transport: Transport, content: ReplayContent): AsyncIterable<DrawnContent> {
+if (transport.openStream === undefined) {
+throw new RpcError(RpcError.NOT_IMPLEMENTED, "transport can't open streams");
+}
+const builder = new Builder();
+let protocolPackage = replayContentIntoProtocolClass(content);
+let payloadOffset = protocolPackage.pack(builder);
+let id = content.id || globalThis.crypto.randomUUID();
+const idOffset = builder.createString(id);
+const root = Envelope.createEnvelope(builder, idOffset, Payload.Replay, payloadOffset);
+builder.finish(root)
+for await (const response of transport.openStream(builder.asUint8Array(), id, "Canvas/Replay")) {
+if (response.payloadType() === Payload.NONE) {
+return;
+}
+switch (response.payloadType()) {
+case Payload.Drawn:
+break;
+case Payload.RpcError: {
+const error = new RpcErrorTable();
+response.payload(error);
+throw new RpcError(Number(error.code()), error.message() ?? "", error.details() ?? "");
+}
+default:
+throw new RpcError(RpcError.BAD_RESPONSE, `response payload must be Drawn, got ${Payload[response.payloadType()]}`);
+}
+const responseData = new Drawn();
+response.payload(responseData);
+yield {
+        ...drawnContentFromProtocolClass(responseData.unpack()),
+        id
+      };
+}
+throw new RpcError(RpcError.UNAVAILABLE, `connection closed before stream ${id} ended`);
+}
+export const canvasSketch = async (transport: Transport, requests: AsyncIterable<StrokeContent> | Iterable<StrokeContent>): Promise<DrawnContent> => {
+const { id, responses } = await startCall(transport, "Canvas/Sketch", requests, (content: StrokeContent, id: string) => {
+const builder = new Builder();
+const payloadOffset = strokeContentIntoProtocolClass(content).pack(builder);
+const idOffset = builder.createString(id);
+const root = Envelope.createEnvelope(builder, idOffset, Payload.Stroke, payloadOffset);
+builder.finish(root);
+return builder.asUint8Array();
+});
+for await (const response of responses) {
+switch (response.payloadType()) {
+case Payload.Drawn:
+break;
+case Payload.RpcError: {
+const error = new RpcErrorTable();
+response.payload(error);
+throw new RpcError(Number(error.code()), error.message() ?? "", error.details() ?? "");
+}
+default:
+throw new RpcError(RpcError.BAD_RESPONSE, `response payload must be Drawn, got ${Payload[response.payloadType()]}`);
+}
+const responseData = new Drawn();
+response.payload(responseData);
+return {
+        ...drawnContentFromProtocolClass(responseData.unpack()),
+        id
+      };
+}
+throw new RpcError(RpcError.UNAVAILABLE, `connection closed before request ${id} was answered`);
+}
+export async function* canvasLive(transport: Transport, requests: AsyncIterable<CursorContent> | Iterable<CursorContent>): AsyncIterable<CursorContent> {
+const { id, responses } = await startCall(transport, "Canvas/Live", requests, (content: CursorContent, id: string) => {
+const builder = new Builder();
+const payloadOffset = cursorContentIntoProtocolClass(content).pack(builder);
+const idOffset = builder.createString(id);
+const root = Envelope.createEnvelope(builder, idOffset, Payload.Cursor, payloadOffset);
+builder.finish(root);
+return builder.asUint8Array();
+});
+for await (const response of responses) {
+if (response.payloadType() === Payload.NONE) {
+return;
+}
+switch (response.payloadType()) {
+case Payload.Cursor:
+break;
+case Payload.RpcError: {
+const error = new RpcErrorTable();
+response.payload(error);
+throw new RpcError(Number(error.code()), error.message() ?? "", error.details() ?? "");
+}
+default:
+throw new RpcError(RpcError.BAD_RESPONSE, `response payload must be Cursor, got ${Payload[response.payloadType()]}`);
+}
+const responseData = new Cursor();
+response.payload(responseData);
+yield {
+        ...cursorContentFromProtocolClass(responseData.unpack()),
+        id
+      };
+}
+throw new RpcError(RpcError.UNAVAILABLE, `connection closed before stream ${id} ended`);
+}
+export const createCanvasApi = (transport: Transport) => {
+return {
+draw: canvasDraw.bind(null, transport),
+replay: canvasReplay.bind(null, transport),
+sketch: canvasSketch.bind(null, transport),
+live: canvasLive.bind(null, transport),
+}
+}
+export const createApiObject = (transport: Transport) => {
+return {
+canvas: createCanvasApi(transport),
+}
+}
\ No newline at end of file
@@ -0,0 +1,873 @@
+
+pub use crate::schema_generated::{ Circle, CircleArgs, CircleT, Square, SquareArgs, SquareT, Draw, DrawArgs, DrawT, Drawn, DrawnArgs, DrawnT, Replay, ReplayArgs, ReplayT, Stroke, StrokeArgs, StrokeT, Cursor, CursorArgs, CursorT, RpcError as RpcErrorTable, RpcErrorArgs, RpcErrorT, Envelope, EnvelopeArgs, EnvelopeT, Point, PointT, Hash, HashT, Span, SpanT, Mode, Shape, Payload, root_as_envelope };
+use flatbuffers::{FlatBufferBuilder, WIPOffset, UnionWIPOffset};
+pub trait IntoOffset<T: 'static> {
+  fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<T>;
+}
+
+/** Decoding a protocol table into its content type failed */
+#[derive(Debug, Clone)]
+pub struct DecodeError {
+  pub message: String,
+}
+
+impl std::fmt::Display for DecodeError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "can't decode protocol table: {}", self.message)
+  }
+}
+
+impl std::error::Error for DecodeError {}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct RpcError {
+  pub code: u32,
+  pub message: String,
+  pub details: String,
+}
+
+impl RpcError {
+  pub const BAD_REQUEST: u32 = 400;
+  pub const CANCELLED: u32 = 499;
+  pub const INTERNAL: u32 = 500;
+  pub const NOT_IMPLEMENTED: u32 = 501;
+  pub const BAD_RESPONSE: u32 = 502;
+  pub const UNAVAILABLE: u32 = 503;
+
+  pub fn new(code: u32, message: impl Into<String>) -> Self {
+    RpcError { code, message: message.into(), details: String::new() }
+  }
+
+  pub fn internal(message: impl Into<String>) -> Self {
+    RpcError::new(RpcError::INTERNAL, message)
+  }
+
+  pub fn with_details(mut self, details: impl Into<String>) -> Self {
+    self.details = details.into();
+    self
+  }
+}
+
+impl std::fmt::Display for RpcError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "rpc error {}: {}", self.code, self.message)
+  }
+}
+
+impl std::error::Error for RpcError {}
+
+impl From<DecodeError> for RpcError {
+  fn from(error: DecodeError) -> Self {
+    RpcError::new(RpcError::BAD_REQUEST, error.to_string())
+  }
+}
+
+
+impl From<RpcErrorTable<'_>> for RpcError {
+  fn from(error: RpcErrorTable<'_>) -> Self {
+    RpcError {
+      code: error.code(),
+      message: error.message().unwrap_or_default().to_owned(),
+      details: error.details().unwrap_or_default().to_owned(),
+    }
+  }
+}
+
+mod fixed_array {
+
+  pub fn serialize<S: serde::Serializer, T: serde::Serialize, const N: usize>(value: &[T; N], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(value)
+  }
+
+  pub fn deserialize<'de, D: serde::Deserializer<'de>, T: serde::Deserialize<'de>, const N: usize>(deserializer: D) -> Result<[T; N], D::Error> {
+    let values = <Vec<T> as serde::Deserialize>::deserialize(deserializer)?;
+    let length = values.len();
+    values.try_into().map_err(|_| serde::de::Error::invalid_length(length, &"an array of the declared length"))
+  }
+}
+const _: () = {
+assert!(Mode::Fast.0 == 0, "Mode::Fast must be 0 to stay wire compatible with the schema");
+assert!(Mode::Slow.0 == 1, "Mode::Slow must be 1 to stay wire compatible with the schema");
+};
+
+impl serde::Serialize for Mode {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self.variant_name() {
+      Some(variant) => serializer.serialize_str(variant),
+      None => serde::Serialize::serialize(&self.0, serializer),
+    }
+  }
+}
+
+
+impl<'de> serde::Deserialize<'de> for Mode {
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+      Variant(String),
+      Value(i8),
+    }
+
+    match Repr::deserialize(deserializer)? {
+      Repr::Value(value) => Ok(Mode(value)),
+      Repr::Variant(variant) => Mode::ENUM_VALUES.iter()
+        .find(|value| value.variant_name() == Some(variant.as_str()))
+        .copied()
+        .ok_or_else(|| serde::de::Error::custom(format!("unknown Mode variant {variant}"))),
+    }
+  }
+}
+
+const _: () = {
+assert!(Shape::Circle.0 == 1, "Shape::Circle must be 1 to stay wire compatible with the schema");
+assert!(Shape::Square.0 == 2, "Shape::Square must be 2 to stay wire compatible with the schema");
+};
+const _: () = {
+assert!(Payload::Draw.0 == 1, "Payload::Draw must be 1 to stay wire compatible with the schema");
+assert!(Payload::Drawn.0 == 2, "Payload::Drawn must be 2 to stay wire compatible with the schema");
+assert!(Payload::Replay.0 == 3, "Payload::Replay must be 3 to stay wire compatible with the schema");
+assert!(Payload::Stroke.0 == 4, "Payload::Stroke must be 4 to stay wire compatible with the schema");
+assert!(Payload::Cursor.0 == 5, "Payload::Cursor must be 5 to stay wire compatible with the schema");
+assert!(Payload::RpcError.0 == 6, "Payload::RpcError must be 6 to stay wire compatible with the schema");
+};
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum ShapeContent {
+Circle(CircleContent),
+Square(SquareContent),
+}
+
+impl ShapeContent {
+  pub fn union_type(&self) -> Shape {
+    match self {
+      ShapeContent::Circle(_) => Shape::Circle,
+      ShapeContent::Square(_) => Shape::Square,
+    }
+  }
+
+  /** `table` must be the value stored next to `union_type` in a buffer checked by the verifier, which verifies union values against their type.
+  NONE and members unknown to this schema version decode as None */
+  #[allow(dead_code)]
+  fn from_table(union_type: Shape, table: flatbuffers::Table<'_>) -> Result<Option<Self>, DecodeError> {
+    match union_type {
+      Shape::Circle => CircleContent::try_from(unsafe { Circle::init_from_table(table) }).map(|value| Some(ShapeContent::Circle(value))),
+      Shape::Square => SquareContent::try_from(unsafe { Square::init_from_table(table) }).map(|value| Some(ShapeContent::Square(value))),
+      _ => Ok(None),
+    }
+  }
+
+  /** Decodes a vector of unions out of its parallel type and value vectors, NONE and unknown members are skipped */
+  #[allow(dead_code)]
+  fn from_vectors<'a>(types: impl IntoIterator<Item = Shape>, tables: impl IntoIterator<Item = flatbuffers::Table<'a>>) -> Result<Vec<Self>, DecodeError> {
+    let mut values = vec![];
+    for (union_type, table) in types.into_iter().zip(tables) {
+      if let Some(value) = Self::from_table(union_type, table)? {
+        values.push(value);
+      }
+    }
+    Ok(values)
+  }
+}
+
+impl IntoOffset<UnionWIPOffset> for ShapeContent {
+  fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<UnionWIPOffset> {
+    match self {
+      ShapeContent::Circle(value) => value.into_offset(builder).as_union_value(),
+      ShapeContent::Square(value) => value.into_offset(builder).as_union_value(),
+    }
+  }
+}
+
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum PayloadContent {
+Draw(DrawContent),
+Drawn(DrawnContent),
+Replay(ReplayContent),
+Stroke(StrokeContent),
+Cursor(CursorContent),
+RpcError(RpcErrorContent),
+}
+
+impl PayloadContent {
+  pub fn union_type(&self) -> Payload {
+    match self {
+      PayloadContent::Draw(_) => Payload::Draw,
+      PayloadContent::Drawn(_) => Payload::Drawn,
+      PayloadContent::Replay(_) => Payload::Replay,
+      PayloadContent::Stroke(_) => Payload::Stroke,
+      PayloadContent::Cursor(_) => Payload::Cursor,
+      PayloadContent::RpcError(_) => Payload::RpcError,
+    }
+  }
+
+  /** `table` must be the value stored next to `union_type` in a buffer checked by the verifier, which verifies union values against their type.
+  NONE and members unknown to this schema version decode as None */
+  #[allow(dead_code)]
+  fn from_table(union_type: Payload, table: flatbuffers::Table<'_>) -> Result<Option<Self>, DecodeError> {
+    match union_type {
+      Payload::Draw => DrawContent::try_from(unsafe { Draw::init_from_table(table) }).map(|value| Some(PayloadContent::Draw(value))),
+      Payload::Drawn => DrawnContent::try_from(unsafe { Drawn::init_from_table(table) }).map(|value| Some(PayloadContent::Drawn(value))),
+      Payload::Replay => ReplayContent::try_from(unsafe { Replay::init_from_table(table) }).map(|value| Some(PayloadContent::Replay(value))),
+      Payload::Stroke => StrokeContent::try_from(unsafe { Stroke::init_from_table(table) }).map(|value| Some(PayloadContent::Stroke(value))),
+      Payload::Cursor => CursorContent::try_from(unsafe { Cursor::init_from_table(table) }).map(|value| Some(PayloadContent::Cursor(value))),
+      Payload::RpcError => RpcErrorContent::try_from(unsafe { RpcErrorTable::init_from_table(table) }).map(|value| Some(PayloadContent::RpcError(value))),
+      _ => Ok(None),
+    }
+  }
+
+  /** Decodes a vector of unions out of its parallel type and value vectors, NONE and unknown members are skipped */
+  #[allow(dead_code)]
+  fn from_vectors<'a>(types: impl IntoIterator<Item = Payload>, tables: impl IntoIterator<Item = flatbuffers::Table<'a>>) -> Result<Vec<Self>, DecodeError> {
+    let mut values = vec![];
+    for (union_type, table) in types.into_iter().zip(tables) {
+      if let Some(value) = Self::from_table(union_type, table)? {
+        values.push(value);
+      }
+    }
+    Ok(values)
+  }
+}
+
+impl IntoOffset<UnionWIPOffset> for PayloadContent {
+  fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<UnionWIPOffset> {
+    match self {
+      PayloadContent::Draw(value) => value.into_offset(builder).as_union_value(),
+      PayloadContent::Drawn(value) => value.into_offset(builder).as_union_value(),
+      PayloadContent::Replay(value) => value.into_offset(builder).as_union_value(),
+      PayloadContent::Stroke(value) => value.into_offset(builder).as_union_value(),
+      PayloadContent::Cursor(value) => value.into_offset(builder).as_union_value(),
+      PayloadContent::RpcError(value) => value.into_offset(builder).as_union_value(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PointContent {
+	 pub x: f32,
+	 pub y: f32,
+}
+impl Default for PointContent {
+fn default() -> Self {
+PointContent {
+x: Default::default(),
+y: Default::default(),
+}
+}
+}
+impl From<PointContent> for Point {
+fn from(struct_def: PointContent) -> Self {
+PointT {
+x: struct_def.x,
+y: struct_def.y,
+}.pack()
+}
+}
+impl From<&Point> for PointContent {
+fn from(struct_def: &Point) -> Self {
+PointContent {
+x: struct_def.x(),
+y: struct_def.y(),
+}
+}
+}
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct HashContent {
+#[serde(serialize_with = "fixed_array::serialize")]
+#[serde(deserialize_with = "fixed_array::deserialize")]
+	 pub bytes: [u8; 64],
+	 pub small: [u8; 4],
+}
+impl Default for HashContent {
+fn default() -> Self {
+HashContent {
+bytes: std::array::from_fn(|_| Default::default()),
+small: std::array::from_fn(|_| Default::default()),
+}
+}
+}
+impl From<HashContent> for Hash {
+fn from(struct_def: HashContent) -> Self {
+HashT {
+bytes: struct_def.bytes,
+small: struct_def.small,
+}.pack()
+}
+}
+impl From<&Hash> for HashContent {
+fn from(struct_def: &Hash) -> Self {
+HashContent {
+bytes: std::array::from_fn(|index| struct_def.bytes().get(index)),
+small: std::array::from_fn(|index| struct_def.small().get(index)),
+}
+}
+}
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SpanContent {
+	 pub start: i64,
+	 pub len: u32,
+}
+impl Default for SpanContent {
+fn default() -> Self {
+SpanContent {
+start: Default::default(),
+len: Default::default(),
+}
+}
+}
+impl From<SpanContent> for Span {
+fn from(struct_def: SpanContent) -> Self {
+SpanT {
+start: struct_def.start,
+len: struct_def.len,
+}.pack()
+}
+}
+impl From<&Span> for SpanContent {
+fn from(struct_def: &Span) -> Self {
+SpanContent {
+start: struct_def.start(),
+len: struct_def.len(),
+}
+}
+}
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CircleContent {
+	pub r: f64,
+}
+
+
+impl Default for CircleContent {
+fn default() -> Self {
+CircleContent {
+r: 1.0,
+}
+}
+}
+impl IntoOffset<Circle<'static>> for CircleContent {
+fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<Circle<'static>> {
+let args = &CircleArgs { 
+   r: self.r,   };
+Circle::create(builder, args)
+}
+}
+
+
+
+impl TryFrom<Circle<'_>> for CircleContent {
+type Error = DecodeError;
+fn try_from(table: Circle<'_>) -> Result<Self, Self::Error> {
+Ok(CircleContent {
+r: table.r(),
+})
+}
+}
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SquareContent {
+	pub side: f64,
+}
+
+
+impl Default for SquareContent {
+fn default() -> Self {
+SquareContent {
+side: Default::default(),
+}
+}
+}
+impl IntoOffset<Square<'static>> for SquareContent {
+fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<Square<'static>> {
+let args = &SquareArgs { 
+   side: self.side,   };
+Square::create(builder, args)
+}
+}
+
+
+
+impl TryFrom<Square<'_>> for SquareContent {
+type Error = DecodeError;
+fn try_from(table: Square<'_>) -> Result<Self, Self::Error> {
+Ok(SquareContent {
+side: table.side(),
+})
+}
+}
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DrawContent {
+	pub name: String,
+	pub mode: Mode,
+	pub modes: Vec<Mode>,
+	pub hash: HashContent,
+	pub at: SpanContent,
+	pub shape: Option<ShapeContent>,
+	pub shapes: Vec<ShapeContent>,
+	pub size: i64,
+	pub sizes: Vec<u64>,
+	pub hp: Option<i32>,
+	pub big: Option<i64>,
+	pub ok: bool,
+	pub points: Vec<PointContent>,
+	pub id: String
+}
+
+
+impl Default for DrawContent {
+fn default() -> Self {
+DrawContent {
+name: Default::default(),
+mode: Mode::Slow,
+modes: Default::default(),
+hash: Default::default(),
+at: Default::default(),
+shape: Default::default(),
+shapes: Default::default(),
+size: 5,
+sizes: Default::default(),
+hp: None,
+big: None,
+ok: true,
+points: Default::default(),
+id: Default::default(),
+}
+}
+}
+impl IntoOffset<Draw<'static>> for DrawContent {
+fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<Draw<'static>> {
+let name_offset = builder.create_string(&self.name);
+let mut offset_vec = vec![];
+for value in self . modes { offset_vec . push (value) ; }
+let modes_offset = builder.create_vector(&offset_vec);
+let hash = self.hash.into();
+let at = self.at.into();
+let shape_type = self.shape.as_ref().map(ShapeContent::union_type).unwrap_or_default();
+let shape_offset = self.shape.map(|value| value.into_offset(builder));
+let shapes_type_offset = builder.create_vector(&self.shapes.iter().map(ShapeContent::union_type).collect::<Vec<Shape>>());
+let mut offset_vec = vec![];
+for value in self . shapes { offset_vec . push (value . into_offset (builder)) ; }
+let shapes_offset = builder.create_vector(&offset_vec);
+let sizes_offset = builder.create_vector(&self.sizes);
+let mut offset_vec = vec![];
+for value in self . points { offset_vec . push (Point :: from (value)) ; }
+let points_offset = builder.create_vector(&offset_vec);
+let args = &DrawArgs { 
+ shape_type, shape: shape_offset, name: Some(name_offset),
+modes: Some(modes_offset),
+shapes_type: Some(shapes_type_offset),
+shapes: Some(shapes_offset),
+sizes: Some(sizes_offset),
+points: Some(points_offset), size: self.size,
+hp: self.hp,
+big: self.big,
+ok: self.ok, hash: Some(&hash),
+at: Some(&at), mode: self.mode, };
+Draw::create(builder, args)
+}
+}
+
+
+impl From < DrawContent > for Vec < u8 > { fn from (content : DrawContent) -> Self { let mut builder = FlatBufferBuilder :: new () ; let id = content . id . to_owned () ; let root_type = EnvelopeContent { id , payload : Some (PayloadContent :: Draw (content)) , } ; let offset = root_type . into_offset (& mut builder) ; builder . finish (offset , None) ; Vec :: from (builder . finished_data ()) } }
+impl TryFrom<Draw<'_>> for DrawContent {
+type Error = DecodeError;
+fn try_from(table: Draw<'_>) -> Result<Self, Self::Error> {
+Ok(DrawContent {
+name: table.name().to_owned(),
+mode: table.mode(),
+modes: table.modes().map(|vector| vector.iter().collect()).unwrap_or_default(),
+hash: table.hash().map(HashContent::from).unwrap_or_default(),
+at: table.at().map(SpanContent::from).unwrap_or_default(),
+shape: table.shape().map(|value| ShapeContent::from_table(table.shape_type(), value)).transpose()?.flatten(),
+shapes: ShapeContent::from_vectors(table.shapes_type().into_iter().flatten(), table.shapes().into_iter().flatten())?,
+size: table.size(),
+sizes: table.sizes().map(|vector| vector.iter().collect()).unwrap_or_default(),
+hp: table.hp(),
+big: table.big(),
+ok: table.ok(),
+points: table.points().map(|vector| vector.iter().map(PointContent::from).collect()).unwrap_or_default(),
+id: String::new(),
+})
+}
+}
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DrawnContent {
+	pub count: i32,
+	pub last: Option<ShapeContent>,
+	pub id: String
+}
+
+
+impl Default for DrawnContent {
+fn default() -> Self {
+DrawnContent {
+count: Default::default(),
+last: Default::default(),
+id: Default::default(),
+}
+}
+}
+impl IntoOffset<Drawn<'static>> for DrawnContent {
+fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<Drawn<'static>> {
+let last_type = self.last.as_ref().map(ShapeContent::union_type).unwrap_or_default();
+let last_offset = self.last.map(|value| value.into_offset(builder));
+let args = &DrawnArgs { 
+ last_type, last: last_offset,  count: self.count,   };
+Drawn::create(builder, args)
+}
+}
+
+
+impl From < DrawnContent > for Vec < u8 > { fn from (content : DrawnContent) -> Self { let mut builder = FlatBufferBuilder :: new () ; let id = content . id . to_owned () ; let root_type = EnvelopeContent { id , payload : Some (PayloadContent :: Drawn (content)) , } ; let offset = root_type . into_offset (& mut builder) ; builder . finish (offset , None) ; Vec :: from (builder . finished_data ()) } }
+impl TryFrom<Drawn<'_>> for DrawnContent {
+type Error = DecodeError;
+fn try_from(table: Drawn<'_>) -> Result<Self, Self::Error> {
+Ok(DrawnContent {
+count: table.count(),
+last: table.last().map(|value| ShapeContent::from_table(table.last_type(), value)).transpose()?.flatten(),
+id: String::new(),
+})
+}
+}
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ReplayContent {
+	pub from: u64,
+	pub id: String
+}
+
+
+impl Default for ReplayContent {
+fn default() -> Self {
+ReplayContent {
+from: Default::default(),
+id: Default::default(),
+}
+}
+}
+impl IntoOffset<Replay<'static>> for ReplayContent {
+fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<Replay<'static>> {
+let args = &ReplayArgs { 
+   from: self.from,   };
+Replay::create(builder, args)
+}
+}
+
+
+impl From < ReplayContent > for Vec < u8 > { fn from (content : ReplayContent) -> Self { let mut builder = FlatBufferBuilder :: new () ; let id = content . id . to_owned () ; let root_type = EnvelopeContent { id , payload : Some (PayloadContent :: Replay (content)) , } ; let offset = root_type . into_offset (& mut builder) ; builder . finish (offset , None) ; Vec :: from (builder . finished_data ()) } }
+impl TryFrom<Replay<'_>> for ReplayContent {
+type Error = DecodeError;
+fn try_from(table: Replay<'_>) -> Result<Self, Self::Error> {
+Ok(ReplayContent {
+from: table.from(),
+id: String::new(),
+})
+}
+}
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct StrokeContent {
+	pub at: PointContent,
+	pub id: String
+}
+
+
+impl Default for StrokeContent {
+fn default() -> Self {
+StrokeContent {
+at: Default::default(),
+id: Default::default(),
+}
+}
+}
+impl IntoOffset<Stroke<'static>> for StrokeContent {
+fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<Stroke<'static>> {
+let at = self.at.into();
+let args = &StrokeArgs { 
+    at: Some(&at),  };
+Stroke::create(builder, args)
+}
+}
+
+
+impl From < StrokeContent > for Vec < u8 > { fn from (content : StrokeContent) -> Self { let mut builder = FlatBufferBuilder :: new () ; let id = content . id . to_owned () ; let root_type = EnvelopeContent { id , payload : Some (PayloadContent :: Stroke (content)) , } ; let offset = root_type . into_offset (& mut builder) ; builder . finish (offset , None) ; Vec :: from (builder . finished_data ()) } }
+impl TryFrom<Stroke<'_>> for StrokeContent {
+type Error = DecodeError;
+fn try_from(table: Stroke<'_>) -> Result<Self, Self::Error> {
+Ok(StrokeContent {
+at: table.at().map(PointContent::from).unwrap_or_default(),
+id: String::new(),
+})
+}
+}
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CursorContent {
+	pub at: PointContent,
+	pub id: String
+}
+
+
+impl Default for CursorContent {
+fn default() -> Self {
+CursorContent {
+at: Default::default(),
+id: Default::default(),
+}
+}
+}
+impl IntoOffset<Cursor<'static>> for CursorContent {
+fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<Cursor<'static>> {
+let at = self.at.into();
+let args = &CursorArgs { 
+    at: Some(&at),  };
+Cursor::create(builder, args)
+}
+}
+
+
+impl From < CursorContent > for Vec < u8 > { fn from (content : CursorContent) -> Self { let mut builder = FlatBufferBuilder :: new () ; let id = content . id . to_owned () ; let root_type = EnvelopeContent { id , payload : Some (PayloadContent :: Cursor (content)) , } ; let offset = root_type . into_offset (& mut builder) ; builder . finish (offset , None) ; Vec :: from (builder . finished_data ()) } }
+impl TryFrom<Cursor<'_>> for CursorContent {
+type Error = DecodeError;
+fn try_from(table: Cursor<'_>) -> Result<Self, Self::Error> {
+Ok(CursorContent {
+at: table.at().map(PointContent::from).unwrap_or_default(),
+id: String::new(),
+})
+}
+}
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RpcErrorContent {
+	pub code: u32,
+	pub message: String,
+	pub details: String,
+	pub id: String
+}
+
+
+impl Default for RpcErrorContent {
+fn default() -> Self {
+RpcErrorContent {
+code: Default::default(),
+message: Default::default(),
+details: Default::default(),
+id: Default::default(),
+}
+}
+}
+impl IntoOffset<RpcErrorTable<'static>> for RpcErrorContent {
+fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<RpcErrorTable<'static>> {
+let message_offset = builder.create_string(&self.message);
+let details_offset = builder.create_string(&self.details);
+let args = &RpcErrorArgs { 
+  message: Some(message_offset),
+details: Some(details_offset), code: self.code,   };
+RpcErrorTable::create(builder, args)
+}
+}
+
+
+impl From < RpcErrorContent > for Vec < u8 > { fn from (content : RpcErrorContent) -> Self { let mut builder = FlatBufferBuilder :: new () ; let id = content . id . to_owned () ; let root_type = EnvelopeContent { id , payload : Some (PayloadContent :: RpcError (content)) , } ; let offset = root_type . into_offset (& mut builder) ; builder . finish (offset , None) ; Vec :: from (builder . finished_data ()) } }
+impl TryFrom<RpcErrorTable<'_>> for RpcErrorContent {
+type Error = DecodeError;
+fn try_from(table: RpcErrorTable<'_>) -> Result<Self, Self::Error> {
+Ok(RpcErrorContent {
+code: table.code(),
+message: table.message().unwrap_or_default().to_owned(),
+details: table.details().unwrap_or_default().to_owned(),
+id: String::new(),
+})
+}
+}
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EnvelopeContent {
+	pub id: String,
+	pub payload: Option<PayloadContent>,
+}
+
+
+impl Default for EnvelopeContent {
+fn default() -> Self {
+EnvelopeContent {
+id: Default::default(),
+payload: Default::default(),
+}
+}
+}
+impl IntoOffset<Envelope<'static>> for EnvelopeContent {
+fn into_offset(self, builder: &mut FlatBufferBuilder<'static>) -> WIPOffset<Envelope<'static>> {
+let id_offset = builder.create_string(&self.id);
+let payload_type = self.payload.as_ref().map(PayloadContent::union_type).unwrap_or_default();
+let payload_offset = self.payload.map(|value| value.into_offset(builder));
+let args = &EnvelopeArgs { 
+ payload_type, payload: payload_offset, id: Some(id_offset),    };
+Envelope::create(builder, args)
+}
+}
+
+
+
+
+use std::future::Future;
+
+/** Per request data passed to every handler */
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+  /** id of the incoming root table, echoed back in the response */
+  pub id: String,
+  pub service: &'static str,
+  pub method: &'static str,
+}
+
+
+/** Failed requests are answered with an Payload::RpcError payload */
+fn error_frame(id: &str, error: &RpcError) -> Vec<u8> {
+  Vec::from(RpcErrorContent {
+    id: id.to_owned(),
+    code: error.code,
+    message: error.message.to_owned(),
+    details: error.details.to_owned(),
+  })
+}
+
+
+/** A root table with an empty payload ends a stream, failed streams end with an RpcError payload instead */
+fn end_of_stream_frame(id: &str) -> Vec<u8> {
+  let mut builder = FlatBufferBuilder::new();
+  let id = builder.create_string(id);
+  let root_type = Envelope::create(&mut builder, &EnvelopeArgs {
+    id: Some(id),
+    payload_type: Payload::NONE,
+    payload: None,
+  });
+  builder.finish(root_type, None);
+
+  Vec::from(builder.finished_data())
+}
+
+pub trait CanvasHandler {
+fn draw(&self, ctx: &RequestContext, req: DrawContent) -> impl Future<Output = Result<DrawnContent, RpcError>> + Send;
+fn replay(&self, ctx: &RequestContext, req: ReplayContent) -> impl flat_rpc_runtime::Stream<Item = Result<DrawnContent, RpcError>> + Send;
+fn sketch(&self, ctx: &RequestContext, reqs: RequestStream<StrokeContent>) -> impl Future<Output = Result<DrawnContent, RpcError>> + Send;
+fn live(&self, ctx: &RequestContext, reqs: RequestStream<CursorContent>) -> impl flat_rpc_runtime::Stream<Item = Result<CursorContent, RpcError>> + Send;
+}
+pub trait RequestHandler: CanvasHandler {}
+impl<T: CanvasHandler> RequestHandler for T {}
+pub async fn process_request(handler: &impl RequestHandler, buffer: Vec<u8>) -> Vec<u8> {
+let root_type = match root_as_envelope(&buffer) {
+Ok(root_type) => root_type,
+Err(e) => return error_frame("", &RpcError::new(RpcError::BAD_REQUEST, format!("can't parse binary as a root type: {e}"))),
+};
+let id = root_type.id().unwrap_or_default();
+let response = match root_type.payload_type() {
+Payload::Draw => match root_type.payload_as_draw().map(DrawContent::try_from) {
+Some(Ok(request)) => {
+let ctx = RequestContext { id: id.to_owned(), service: "Canvas", method: "Draw" };
+CanvasHandler::draw(handler, &ctx, request).await.map(|mut response| {
+if response.id.is_empty() {
+response.id = id.to_owned();
+}
+Vec::from(response)
+})
+}
+Some(Err(error)) => Err(RpcError::from(error)),
+None => Err(RpcError::new(RpcError::BAD_REQUEST, "payload of Draw is absent")),
+},
+Payload::Replay => Err(RpcError::new(RpcError::BAD_REQUEST, "Canvas::Replay is a streaming method, serve it with process_request_frames")),
+Payload::Stroke => Err(RpcError::new(RpcError::BAD_REQUEST, "Canvas::Sketch is a streaming method, serve it with process_request_frames")),
+Payload::Cursor => Err(RpcError::new(RpcError::BAD_REQUEST, "Canvas::Live is a streaming method, serve it with process_request_frames")),
+Payload::NONE | Payload::RpcError => Err(RpcError::new(RpcError::BAD_REQUEST, format!("no request stream is open with id {id:?}"))),
+unknown_variant => Err(RpcError::new(RpcError::NOT_IMPLEMENTED, format!("UNKNOWN PAYLOAD TYPE {:?}", unknown_variant))),
+};
+
+match response {
+Ok(frame) => frame,
+Err(error) => error_frame(id, &error),
+}
+}
+fn decode_canvas_sketch_request (frame : & [u8]) -> Result < Option < StrokeContent > , RpcError > { let root_type = root_as_envelope (frame) . map_err (| e | RpcError :: new (RpcError :: BAD_REQUEST , format ! ("can't parse binary as a root type: {e}"))) ? ; match root_type . payload_type () { Payload :: Stroke => { let payload = root_type . payload_as_stroke () . ok_or_else (|| RpcError :: new (RpcError :: BAD_REQUEST , "payload of Stroke is absent")) ? ; let mut request = StrokeContent :: try_from (payload) ? ; request . id = root_type . id () . unwrap_or_default () . to_owned () ; Ok (Some (request)) } Payload :: NONE => Ok (None) , Payload :: RpcError => Err (root_type . payload_as_rpc_error () . map (RpcError :: from) . unwrap_or_default ()) , unexpected => Err (RpcError :: new (RpcError :: BAD_REQUEST , format ! ("request stream item must be {}, got {:?}" , "Stroke" , unexpected))) , } }
+fn decode_canvas_live_request (frame : & [u8]) -> Result < Option < CursorContent > , RpcError > { let root_type = root_as_envelope (frame) . map_err (| e | RpcError :: new (RpcError :: BAD_REQUEST , format ! ("can't parse binary as a root type: {e}"))) ? ; match root_type . payload_type () { Payload :: Cursor => { let payload = root_type . payload_as_cursor () . ok_or_else (|| RpcError :: new (RpcError :: BAD_REQUEST , "payload of Cursor is absent")) ? ; let mut request = CursorContent :: try_from (payload) ? ; request . id = root_type . id () . unwrap_or_default () . to_owned () ; Ok (Some (request)) } Payload :: NONE => Ok (None) , Payload :: RpcError => Err (root_type . payload_as_rpc_error () . map (RpcError :: from) . unwrap_or_default ()) , unexpected => Err (RpcError :: new (RpcError :: BAD_REQUEST , format ! ("request stream item must be {}, got {:?}" , "Cursor" , unexpected))) , } }
+# [doc = r" Frames continuing a request stream are told apart by their payload type "] pub fn route_request (frame : & [u8]) -> flat_rpc_runtime :: Route { let root_type = match root_as_envelope (frame) { Ok (root_type) => root_type , Err (_) => return flat_rpc_runtime :: Route :: Call , } ; let id = root_type . id () . unwrap_or_default () . to_owned () ; match root_type . payload_type () { Payload :: Stroke | Payload :: Cursor => flat_rpc_runtime :: Route :: Stream (id) , Payload :: NONE | Payload :: RpcError => flat_rpc_runtime :: Route :: End (id) , _ => flat_rpc_runtime :: Route :: Call , } } # [doc = r" Requests of a streaming call, it ends after the end of stream marker or the first error, a client cancelling
+    the stream included. A handler leaving `STREAM_BUFFER` requests unread gets an UNAVAILABLE error instead of the rest "] pub struct RequestStream < T > { first : Option < Result < T , RpcError >> , frames : Option < flat_rpc_runtime :: FrameStream > , decode : fn (& [u8]) -> Result < Option < T > , RpcError > , } impl < T > RequestStream < T > { fn new (first : Result < Option < T > , RpcError > , frames : flat_rpc_runtime :: FrameStream , decode : fn (& [u8]) -> Result < Option < T > , RpcError >) -> Self { RequestStream { first : first . transpose () , frames : Some (frames) , decode } } pub async fn next (& mut self) -> Option < Result < T , RpcError >> where T : Unpin { std :: future :: poll_fn (| cx | flat_rpc_runtime :: Stream :: poll_next (std :: pin :: Pin :: new (& mut * self) , cx)) . await } } impl < T : Unpin > flat_rpc_runtime :: Stream for RequestStream < T > { type Item = Result < T , RpcError > ; fn poll_next (mut self : std :: pin :: Pin < & mut Self > , cx : & mut std :: task :: Context < '_ >) -> std :: task :: Poll < Option < Self :: Item >> { let item = match self . first . take () { Some (first) => Some (first) , None => { let frames = match self . frames . as_mut () { Some (frames) => frames , None => return std :: task :: Poll :: Ready (None) , } ; match std :: task :: ready ! (flat_rpc_runtime :: Stream :: poll_next (std :: pin :: Pin :: new (& mut * frames) , cx)) { Some (frame) => (self . decode) (& frame) . transpose () , None if frames . fell_behind () => Some (Err (RpcError :: new (RpcError :: UNAVAILABLE , format ! ("more than {} requests were waiting, the handler fell behind" , flat_rpc_runtime :: STREAM_BUFFER)))) , None => Some (Err (RpcError :: new (RpcError :: UNAVAILABLE , "connection closed before the request stream ended"))) , } } } ; if ! matches ! (item , Some (Ok (_))) { self . frames = None ; } std :: task :: Poll :: Ready (item) } }
+pub async fn process_request_frames(handler: &impl RequestHandler, buffer: Vec<u8>, requests: flat_rpc_runtime::FrameStream, responses: &flat_rpc_runtime::FrameSink) {
+let root_type = match root_as_envelope(&buffer) {
+Ok(root_type) => root_type,
+Err(_) => {
+responses.send(process_request(handler, buffer).await).await;
+return;
+}
+};
+let id = root_type.id().unwrap_or_default();
+match root_type.payload_type() {
+Payload::Replay => match root_type.payload_as_replay().map(ReplayContent::try_from) {
+Some(Ok(request)) => {
+let ctx = RequestContext { id: id.to_owned(), service: "Canvas", method: "Replay" };
+let mut items = std::pin::pin!(CanvasHandler::replay(handler, &ctx, request));
+while let Some(item) = std::future::poll_fn(|cx| flat_rpc_runtime::Stream::poll_next(items.as_mut(), cx)).await {
+let frame = match item.map(|mut response| {
+if response.id.is_empty() {
+response.id = id.to_owned();
+}
+Vec::from(response)
+}) {
+Ok(frame) => frame,
+Err(error) => {
+responses.send(error_frame(id, &error)).await;
+return;
+}
+};
+if !responses.send(frame).await {
+return;
+}
+}
+responses.send(end_of_stream_frame(id)).await;
+}
+Some(Err(error)) => {
+responses.send(error_frame(id, &RpcError::from(error))).await;
+}
+None => {
+responses.send(error_frame(id, &RpcError::new(RpcError::BAD_REQUEST, "payload of Replay is absent"))).await;
+}
+},
+Payload::Stroke => {
+let ctx = RequestContext { id: id.to_owned(), service: "Canvas", method: "Sketch" };
+let request = RequestStream::new(decode_canvas_sketch_request(&buffer), requests, decode_canvas_sketch_request);
+let frame = match CanvasHandler::sketch(handler, &ctx, request).await.map(|mut response| {
+if response.id.is_empty() {
+response.id = id.to_owned();
+}
+Vec::from(response)
+}) {
+Ok(frame) => frame,
+Err(error) => error_frame(id, &error),
+};
+responses.send(frame).await;
+}
+Payload::Cursor => {
+let ctx = RequestContext { id: id.to_owned(), service: "Canvas", method: "Live" };
+let request = RequestStream::new(decode_canvas_live_request(&buffer), requests, decode_canvas_live_request);
+let mut items = std::pin::pin!(CanvasHandler::live(handler, &ctx, request));
+while let Some(item) = std::future::poll_fn(|cx| flat_rpc_runtime::Stream::poll_next(items.as_mut(), cx)).await {
+let frame = match item.map(|mut response| {
+if response.id.is_empty() {
+response.id = id.to_owned();
+}
+Vec::from(response)
+}) {
+Ok(frame) => frame,
+Err(error) => {
+responses.send(error_frame(id, &error)).await;
+return;
+}
+};
+if !responses.send(frame).await {
+return;
+}
+}
+responses.send(end_of_stream_frame(id)).await;
+}
+_ => {
+responses.send(process_request(handler, buffer).await).await;
+}
+}
+}
+pub struct RpcServer < H > (pub H) ; impl < H : RequestHandler + Send + Sync + 'static > flat_rpc_runtime :: Service for RpcServer < H > { async fn call (& self , frame : Vec < u8 > , requests : flat_rpc_runtime :: FrameStream , responses : flat_rpc_runtime :: FrameSink) { process_request_frames (& self . 0 , frame , requests , & responses) . await } fn route (& self , frame : & [u8]) -> flat_rpc_runtime :: Route { route_request (frame) } fn streams_responses (& self , frame : & [u8]) -> bool { match root_as_envelope (frame) { Ok (root_type) => matches ! (root_type . payload_type () , Payload :: Replay | Payload :: Cursor) , Err (_) => false , } } fn match_method (& self , frame : & [u8] , method : & str) -> flat_rpc_runtime :: MethodMatch { let payload_type = match root_as_envelope (frame) { Ok (root_type) => Some (root_type . payload_type ()) , Err (_) => None , } ; match (payload_type , method) { (Some (Payload :: Draw) , "Canvas/Draw") | (Some (Payload :: Replay) , "Canvas/Replay") | (Some (Payload :: Stroke) , "Canvas/Sketch") | (Some (Payload :: Cursor) , "Canvas/Live") => flat_rpc_runtime :: MethodMatch :: Called , (_ , "Canvas/Draw" | "Canvas/Replay" | "Canvas/Sketch" | "Canvas/Live") => flat_rpc_runtime :: MethodMatch :: Other , _ => flat_rpc_runtime :: MethodMatch :: Unknown , } } }
\ No newline at end of file
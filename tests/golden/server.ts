@@ -0,0 +1,546 @@
+import * as fb from "flatbuffers";
+const {Builder} = fb;
+import { Circle, CircleT, Square, SquareT, Draw, DrawT, Drawn, DrawnT, Replay, ReplayT, Stroke, StrokeT, Cursor, CursorT, RpcError as RpcErrorTable, RpcErrorT, Envelope, EnvelopeT, Point, PointT, Hash, HashT, Span, SpanT, Shape, Payload, Mode  } from "./schema_generated";
+
+
+export const ModeValues = {
+Fast: 0,
+Slow: 1,
+} as const satisfies Record<keyof typeof Mode, number>;
+export const ShapeValues = {
+NONE: 0,
+Circle: 1,
+Square: 2,
+} as const satisfies Record<keyof typeof Shape, number>;
+export const PayloadValues = {
+NONE: 0,
+Draw: 1,
+Drawn: 2,
+Replay: 3,
+Stroke: 4,
+Cursor: 5,
+RpcError: 6,
+} as const satisfies Record<keyof typeof Payload, number>;
+export type CircleContent = {
+	r?: number,
+	id?: string
+}
+
+
+const circleContentIntoProtocolClass = (content: CircleContent): CircleT => {
+return new CircleT(content.r ?? 1)
+}
+export const circleContentFromProtocolClass = (value: CircleT): CircleContent => ({
+r: value.r,
+});
+
+export type SquareContent = {
+	side: number,
+	id?: string
+}
+
+
+const squareContentIntoProtocolClass = (content: SquareContent): SquareT => {
+return new SquareT(content.side)
+}
+export const squareContentFromProtocolClass = (value: SquareT): SquareContent => ({
+side: value.side,
+});
+
+export type DrawContent = {
+	name: string,
+	mode?: Mode,
+	modes: Array<Mode>,
+	hash: HashContent,
+	at: SpanContent,
+	shape: ShapeContent | null,
+	shapes: Array<ShapeContent>,
+	size?: bigint,
+	sizes: Array<bigint>,
+	hp?: number | null,
+	big?: bigint | null,
+	ok?: boolean,
+	points: Array<PointContent>,
+	id?: string
+}
+
+
+const drawContentIntoProtocolClass = (content: DrawContent): DrawT => {
+return new DrawT(content.name, content.mode ?? Mode.Slow, content.modes, hashContentIntoProtocolClass(content.hash), spanContentIntoProtocolClass(content.at), content.shape?.type ?? Shape.NONE, content.shape ? shapeContentIntoProtocolClass(content.shape) : null, content.shapes.map((item) => item.type), content.shapes.map(shapeContentIntoProtocolClass), content.size ?? 5n, content.sizes, content.hp ?? null, content.big ?? null, content.ok ?? true, content.points.map(pointContentIntoProtocolClass))
+}
+export const drawContentFromProtocolClass = (value: DrawT): DrawContent => ({
+name: value.name ?? "",
+mode: value.mode,
+modes: value.modes,
+hash: hashContentFromProtocolClass(value.hash ?? new HashT()),
+at: spanContentFromProtocolClass(value.at ?? new SpanT()),
+shape: shapeContentFromProtocolClass(value.shapeType, value.shape),
+shapes: value.shapes.flatMap((item, index) => shapeContentFromProtocolClass(value.shapesType[index], item) ?? []),
+size: value.size,
+sizes: value.sizes,
+hp: value.hp,
+big: value.big,
+ok: value.ok,
+points: value.points.map(pointContentFromProtocolClass),
+});
+const checkDrawRequiredFields = (table: Draw): void => {
+if (table.bb!.__offset(table.bb_pos, 4) === 0) throw new Error("required field name is absent in Draw");
+}
+export type DrawnContent = {
+	count: number,
+	last: ShapeContent | null,
+	id?: string
+}
+
+
+const drawnContentIntoProtocolClass = (content: DrawnContent): DrawnT => {
+return new DrawnT(content.count, content.last?.type ?? Shape.NONE, content.last ? shapeContentIntoProtocolClass(content.last) : null)
+}
+export const drawnContentFromProtocolClass = (value: DrawnT): DrawnContent => ({
+count: value.count,
+last: shapeContentFromProtocolClass(value.lastType, value.last),
+});
+
+export type ReplayContent = {
+	from: bigint,
+	id?: string
+}
+
+
+const replayContentIntoProtocolClass = (content: ReplayContent): ReplayT => {
+return new ReplayT(content.from)
+}
+export const replayContentFromProtocolClass = (value: ReplayT): ReplayContent => ({
+from: value.from,
+});
+
+export type StrokeContent = {
+	at: PointContent,
+	id?: string
+}
+
+
+const strokeContentIntoProtocolClass = (content: StrokeContent): StrokeT => {
+return new StrokeT(pointContentIntoProtocolClass(content.at))
+}
+export const strokeContentFromProtocolClass = (value: StrokeT): StrokeContent => ({
+at: pointContentFromProtocolClass(value.at ?? new PointT()),
+});
+
+export type CursorContent = {
+	at: PointContent,
+	id?: string
+}
+
+
+const cursorContentIntoProtocolClass = (content: CursorContent): CursorT => {
+return new CursorT(pointContentIntoProtocolClass(content.at))
+}
+export const cursorContentFromProtocolClass = (value: CursorT): CursorContent => ({
+at: pointContentFromProtocolClass(value.at ?? new PointT()),
+});
+
+export type RpcErrorContent = {
+	code: number,
+	message: string,
+	details: string,
+	id?: string
+}
+
+
+const rpcErrorContentIntoProtocolClass = (content: RpcErrorContent): RpcErrorT => {
+return new RpcErrorT(content.code, content.message, content.details)
+}
+export const rpcErrorContentFromProtocolClass = (value: RpcErrorT): RpcErrorContent => ({
+code: value.code,
+message: value.message ?? "",
+details: value.details ?? "",
+});
+
+export type EnvelopeContent = {
+	id?: string,
+	payload: PayloadContent | null,
+}
+
+
+const envelopeContentIntoProtocolClass = (content: EnvelopeContent): EnvelopeT => {
+return new EnvelopeT(content.id, content.payload?.type ?? Payload.NONE, content.payload ? payloadContentIntoProtocolClass(content.payload) : null)
+}
+export const envelopeContentFromProtocolClass = (value: EnvelopeT): EnvelopeContent => ({
+id: value.id ?? "",
+payload: payloadContentFromProtocolClass(value.payloadType, value.payload),
+});
+
+export type PointContent = {
+x: number;
+y: number;
+};
+export const pointContentIntoProtocolClass = (content: PointContent): PointT => {
+return new PointT(content.x,
+content.y,)
+};
+export const pointContentFromProtocolClass = (value: PointT): PointContent => ({
+x: value.x,
+y: value.y,
+});
+export type HashContent = {
+bytes: Uint8Array;
+small: Uint8Array;
+};
+export const hashContentIntoProtocolClass = (content: HashContent): HashT => {
+return new HashT(Array.from(content.bytes),
+Array.from(content.small),)
+};
+export const hashContentFromProtocolClass = (value: HashT): HashContent => ({
+bytes: Uint8Array.from(value.bytes),
+small: Uint8Array.from(value.small),
+});
+export type SpanContent = {
+start: bigint;
+len: number;
+};
+export const spanContentIntoProtocolClass = (content: SpanContent): SpanT => {
+return new SpanT(content.start,
+content.len,)
+};
+export const spanContentFromProtocolClass = (value: SpanT): SpanContent => ({
+start: value.start,
+len: value.len,
+});
+export type ShapeContent = 
+	| { type: Shape.Circle, value: CircleContent }
+	| { type: Shape.Square, value: SquareContent }
+
+
+
+const shapeContentIntoProtocolClass = (content: ShapeContent): CircleT | SquareT => {
+switch (content.type) {
+case Shape.Circle: return circleContentIntoProtocolClass(content.value);
+case Shape.Square: return squareContentIntoProtocolClass(content.value);
+}
+}
+export const shapeContentFromProtocolClass = (type: Shape, value: CircleT | SquareT | null): ShapeContent | null => {
+if (value === null) return null;
+switch (type) {
+case Shape.Circle: return { type: Shape.Circle, value: circleContentFromProtocolClass(value as CircleT) };
+case Shape.Square: return { type: Shape.Square, value: squareContentFromProtocolClass(value as SquareT) };
+default: return null;
+}
+}
+export type PayloadContent = 
+	| { type: Payload.Draw, value: DrawContent }
+	| { type: Payload.Drawn, value: DrawnContent }
+	| { type: Payload.Replay, value: ReplayContent }
+	| { type: Payload.Stroke, value: StrokeContent }
+	| { type: Payload.Cursor, value: CursorContent }
+	| { type: Payload.RpcError, value: RpcErrorContent }
+
+
+
+const payloadContentIntoProtocolClass = (content: PayloadContent): DrawT | DrawnT | ReplayT | StrokeT | CursorT | RpcErrorT => {
+switch (content.type) {
+case Payload.Draw: return drawContentIntoProtocolClass(content.value);
+case Payload.Drawn: return drawnContentIntoProtocolClass(content.value);
+case Payload.Replay: return replayContentIntoProtocolClass(content.value);
+case Payload.Stroke: return strokeContentIntoProtocolClass(content.value);
+case Payload.Cursor: return cursorContentIntoProtocolClass(content.value);
+case Payload.RpcError: return rpcErrorContentIntoProtocolClass(content.value);
+}
+}
+export const payloadContentFromProtocolClass = (type: Payload, value: DrawT | DrawnT | ReplayT | StrokeT | CursorT | RpcErrorT | null): PayloadContent | null => {
+if (value === null) return null;
+switch (type) {
+case Payload.Draw: return { type: Payload.Draw, value: drawContentFromProtocolClass(value as DrawT) };
+case Payload.Drawn: return { type: Payload.Drawn, value: drawnContentFromProtocolClass(value as DrawnT) };
+case Payload.Replay: return { type: Payload.Replay, value: replayContentFromProtocolClass(value as ReplayT) };
+case Payload.Stroke: return { type: Payload.Stroke, value: strokeContentFromProtocolClass(value as StrokeT) };
+case Payload.Cursor: return { type: Payload.Cursor, value: cursorContentFromProtocolClass(value as CursorT) };
+case Payload.RpcError: return { type: Payload.RpcError, value: rpcErrorContentFromProtocolClass(value as RpcErrorT) };
+default: return null;
+}
+}
+export interface CanvasHandler {
+draw(content: DrawContent): Promise<DrawnContent>;
+replay(content: ReplayContent): AsyncIterable<DrawnContent>;
+sketch(contents: AsyncIterable<StrokeContent>): Promise<DrawnContent>;
+live(contents: AsyncIterable<CursorContent>): AsyncIterable<CursorContent>;
+}
+export class RpcError extends Error {
+	static readonly BAD_REQUEST = 400;
+	static readonly CANCELLED = 499;
+	static readonly INTERNAL = 500;
+	static readonly NOT_IMPLEMENTED = 501;
+	static readonly BAD_RESPONSE = 502;
+	static readonly UNAVAILABLE = 503;
+
+	constructor(public readonly code: number, message: string, public readonly details: string = "") {
+		super(message);
+		this.name = "RpcError";
+	}
+}
+
+export type RequestHandlers = {
+canvas: CanvasHandler,
+}
+const errorFrame = (id: string, error: unknown): Uint8Array => {
+const rpcError = error instanceof RpcError ? error : new RpcError(RpcError.INTERNAL, String(error));
+const builder = new Builder();
+const payloadOffset = rpcErrorContentIntoProtocolClass({ code: rpcError.code, message: rpcError.message, details: rpcError.details }).pack(builder);
+const idOffset = builder.createString(id);
+const responseRoot = Envelope.createEnvelope(builder, idOffset, Payload.RpcError, payloadOffset);
+builder.finish(responseRoot);
+return builder.asUint8Array();
+}
+const endOfStreamFrame = (id: string): Uint8Array => {
+const builder = new Builder();
+const idOffset = builder.createString(id);
+const responseRoot = Envelope.createEnvelope(builder, idOffset, Payload.NONE, 0);
+builder.finish(responseRoot);
+return builder.asUint8Array();
+}
+export const processRequest = async (handlers: RequestHandlers, buffer: Uint8Array): Promise<Uint8Array> => {
+const root = Envelope.getRootAsEnvelope(new fb.ByteBuffer(buffer));
+const id = root.id() ?? "";
+try {
+switch (root.payloadType()) {
+case Payload.Draw: {
+const requestData = new Draw();
+root.payload(requestData);
+checkDrawRequiredFields(requestData);
+const content: DrawContent = { ...drawContentFromProtocolClass(requestData.unpack()), id };
+const response = await handlers.canvas.draw(content);
+const builder = new Builder();
+const payloadOffset = drawnContentIntoProtocolClass(response).pack(builder);
+const idOffset = builder.createString(id);
+const responseRoot = Envelope.createEnvelope(builder, idOffset, Payload.Drawn, payloadOffset);
+builder.finish(responseRoot);
+return builder.asUint8Array();
+}
+case Payload.Replay:
+throw new RpcError(RpcError.BAD_REQUEST, "Canvas::Replay is a streaming method, serve it with processRequestFrames");
+case Payload.Stroke:
+throw new RpcError(RpcError.BAD_REQUEST, "Canvas::Sketch is a streaming method, serve it with processRequestFrames");
+case Payload.Cursor:
+throw new RpcError(RpcError.BAD_REQUEST, "Canvas::Live is a streaming method, serve it with processRequestFrames");
+case Payload.NONE:
+case Payload.RpcError:
+throw new RpcError(RpcError.BAD_REQUEST, `no request stream is open with id ${JSON.stringify(id)}`);
+default:
+throw new RpcError(RpcError.NOT_IMPLEMENTED, `UNKNOWN PAYLOAD TYPE ${Payload[root.payloadType()]}`);
+}
+} catch (error) {
+return errorFrame(id, error);
+}
+}
+async function* requestContents<T>(buffer: Uint8Array, requests: AsyncIterable<Uint8Array> | Iterable<Uint8Array>, decode: (root: Envelope) => T): AsyncGenerator<T> {
+	yield decode(Envelope.getRootAsEnvelope(new fb.ByteBuffer(buffer)));
+	for await (const frame of requests) {
+		const root = Envelope.getRootAsEnvelope(new fb.ByteBuffer(frame));
+		if (root.payloadType() === Payload.NONE) {
+			return;
+		}
+		yield decode(root);
+	}
+	throw new RpcError(RpcError.UNAVAILABLE, "connection closed before the request stream ended");
+}
+
+export async function* processRequestFrames(handlers: RequestHandlers, buffer: Uint8Array, requests: AsyncIterable<Uint8Array> | Iterable<Uint8Array> = []): AsyncGenerator<Uint8Array> {
+const root = Envelope.getRootAsEnvelope(new fb.ByteBuffer(buffer));
+const id = root.id() ?? "";
+switch (root.payloadType()) {
+case Payload.Replay: {
+try {
+const requestData = new Replay();
+root.payload(requestData);
+const content: ReplayContent = { ...replayContentFromProtocolClass(requestData.unpack()), id };
+for await (const response of handlers.canvas.replay(content)) {
+const builder = new Builder();
+const payloadOffset = drawnContentIntoProtocolClass(response).pack(builder);
+const idOffset = builder.createString(id);
+const responseRoot = Envelope.createEnvelope(builder, idOffset, Payload.Drawn, payloadOffset);
+builder.finish(responseRoot);
+yield builder.asUint8Array();
+}
+} catch (error) {
+yield errorFrame(id, error);
+return;
+}
+yield endOfStreamFrame(id);
+return;
+}
+case Payload.Stroke: {
+try {
+const contents = requestContents(buffer, requests, (root): StrokeContent => {
+switch (root.payloadType()) {
+case Payload.Stroke:
+break;
+case Payload.RpcError: {
+const error = new RpcErrorTable();
+root.payload(error);
+throw new RpcError(Number(error.code()), error.message() ?? "", error.details() ?? "");
+}
+default:
+throw new RpcError(RpcError.BAD_REQUEST, `request stream item must be Stroke, got ${Payload[root.payloadType()]}`);
+}
+const requestData = new Stroke();
+root.payload(requestData);
+return { ...strokeContentFromProtocolClass(requestData.unpack()), id: root.id() ?? "" };
+});
+const response = await handlers.canvas.sketch(contents);
+const builder = new Builder();
+const payloadOffset = drawnContentIntoProtocolClass(response).pack(builder);
+const idOffset = builder.createString(id);
+const responseRoot = Envelope.createEnvelope(builder, idOffset, Payload.Drawn, payloadOffset);
+builder.finish(responseRoot);
+yield builder.asUint8Array();
+} catch (error) {
+yield errorFrame(id, error);
+return;
+}
+return;
+}
+case Payload.Cursor: {
+try {
+const contents = requestContents(buffer, requests, (root): CursorContent => {
+switch (root.payloadType()) {
+case Payload.Cursor:
+break;
+case Payload.RpcError: {
+const error = new RpcErrorTable();
+root.payload(error);
+throw new RpcError(Number(error.code()), error.message() ?? "", error.details() ?? "");
+}
+default:
+throw new RpcError(RpcError.BAD_REQUEST, `request stream item must be Cursor, got ${Payload[root.payloadType()]}`);
+}
+const requestData = new Cursor();
+root.payload(requestData);
+return { ...cursorContentFromProtocolClass(requestData.unpack()), id: root.id() ?? "" };
+});
+for await (const response of handlers.canvas.live(contents)) {
+const builder = new Builder();
+const payloadOffset = cursorContentIntoProtocolClass(response).pack(builder);
+const idOffset = builder.createString(id);
+const responseRoot = Envelope.createEnvelope(builder, idOffset, Payload.Cursor, payloadOffset);
+builder.finish(responseRoot);
+yield builder.asUint8Array();
+}
+} catch (error) {
+yield errorFrame(id, error);
+return;
+}
+yield endOfStreamFrame(id);
+return;
+}
+default:
+yield await processRequest(handlers, buffer);
+}
+}
+class FrameQueue<T> implements AsyncIterable<T> {
+	static readonly LIMIT = 64;
+
+	private readonly frames: T[] = [];
+	private error: Error | undefined;
+	private stopped = false;
+	private wake = () => {};
+
+	/** `false` once the consumer stopped or fell behind, the frame is dropped then */
+	push(frame: T): boolean {
+		if (this.stopped || this.error !== undefined) {
+			return false;
+		}
+		if (this.frames.length >= FrameQueue.LIMIT) {
+			this.fail(new RpcError(RpcError.UNAVAILABLE, `more than ${FrameQueue.LIMIT} frames are waiting, the consumer fell behind`));
+			return false;
+		}
+		this.frames.push(frame);
+		this.wake();
+		return true;
+	}
+
+	/** The consumer gets `error` next, frames still waiting are dropped */
+	fail(error: Error): void {
+		this.error ??= error;
+		this.frames.length = 0;
+		this.wake();
+	}
+
+	async *[Symbol.asyncIterator](): AsyncIterator<T> {
+		try {
+			while (true) {
+				const frame = this.frames.shift();
+				if (frame !== undefined) {
+					yield frame;
+					continue;
+				}
+				if (this.error !== undefined) {
+					throw this.error;
+				}
+				await new Promise<void>((resolve) => this.wake = resolve);
+			}
+		} finally {
+			this.stopped = true;
+			this.frames.length = 0;
+		}
+	}
+}
+
+/** Every response frame waits for the promise `send` returns, so a slow connection slows its calls down.
+A call reading its requests too slowly fails instead of buffering them without limit */
+export class RequestRouter {
+	// `null` once the call stopped reading, the rest of its stream is dropped
+	private readonly streams = new Map<string, FrameQueue<Uint8Array> | null>();
+
+	constructor(private readonly handlers: RequestHandlers, private readonly send: (frame: Uint8Array) => void | Promise<void>) {}
+
+	receive(buffer: Uint8Array): void {
+		const root = Envelope.getRootAsEnvelope(new fb.ByteBuffer(buffer));
+		const id = root.id() ?? "";
+		switch (root.payloadType()) {
+			case Payload.Stroke:
+			case Payload.Cursor: {
+				const requests = this.streams.get(id);
+				if (requests === undefined) {
+					const requests = new FrameQueue<Uint8Array>();
+					this.streams.set(id, requests);
+					void this.serve(buffer, requests);
+				} else if (requests !== null && !requests.push(buffer)) {
+					this.streams.set(id, null);
+				}
+				return;
+			}
+			case Payload.NONE:
+			case Payload.RpcError: {
+				const requests = this.streams.get(id);
+				this.streams.delete(id);
+				if (requests === undefined) {
+					// answered with an error, nobody else would answer the sender
+					void this.serve(buffer, []);
+				} else {
+					requests?.push(buffer);
+				}
+				return;
+			}
+			default:
+				void this.serve(buffer, []);
+		}
+	}
+
+	/** Fails the request streams still open, for a closed connection */
+	close(): void {
+		for (const requests of this.streams.values()) {
+			requests?.fail(new RpcError(RpcError.UNAVAILABLE, "connection closed before the request stream ended"));
+		}
+		this.streams.clear();
+	}
+
+	private async serve(buffer: Uint8Array, requests: AsyncIterable<Uint8Array> | Iterable<Uint8Array>): Promise<void> {
+		try {
+			for await (const frame of processRequestFrames(this.handlers, buffer, requests)) {
+				await this.send(frame);
+			}
+		} catch {
+			// the connection is gone, nobody is left to answer
+		}
+	}
+}